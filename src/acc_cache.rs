@@ -0,0 +1,221 @@
+use core::mem;
+
+use crate::container::{ContainerClear, ContainerLen, SparseContainer};
+use crate::{FnCache, FnCacheMany, FnCachePeek};
+
+/// A cache for a recursive function which also has mutable access to an accumulator.
+///
+/// This is useful for collecting diagnostics during a computation, such as counting how many
+/// times a base case is reached, without resorting to a [`RefCell`](std::cell::RefCell) captured
+/// by the function. The accumulator is shared by every recursive call, and is kept across calls to
+/// [`FnCache::get`] until it is taken using [`Self::take_acc`].
+///
+/// Since the accumulator must be passed along with the cache to each recursive call, the function
+/// reaches it through [`AccRefCache::acc`].
+///
+/// ```
+/// # use fn_cache::acc_cache::AccCache;
+/// # use fn_cache::FnCache;
+/// # use std::collections::HashMap;
+/// let mut cache = AccCache::<HashMap<u64, u64>, usize>::recursive(0, |cache, x| match x {
+///     0 | 1 => {
+///         *cache.acc() += 1;
+///         *x
+///     }
+///     _ => *cache.get(x - 1) + *cache.get(x - 2),
+/// });
+///
+/// assert_eq!(cache.get(10), &55);
+/// assert_eq!(cache.acc(), &2);
+/// ```
+pub struct AccCache<'f, C: SparseContainer, A> {
+	pub(crate) cache: C,
+	acc: A,
+	#[allow(clippy::type_complexity)]
+	f: Box<dyn Fn(&mut AccRefCache<C, A>, &C::Input) -> C::Output + Send + 'f>,
+}
+
+impl<'f, C: SparseContainer, A> AccCache<'f, C, A> {
+	/// Create an `AccCache` out of a cache, an initial accumulator, and a recursive function.
+	pub fn recursive_with_cache(
+		cache: C,
+		acc: A,
+		f: impl Fn(&mut AccRefCache<C, A>, &C::Input) -> C::Output + Send + 'f,
+	) -> Self {
+		Self {
+			cache,
+			acc,
+			f: Box::new(f),
+		}
+	}
+
+	/// Get a reference to the underlying cache object.
+	pub fn cache(&self) -> &C {
+		&self.cache
+	}
+
+	/// Get a reference to the accumulator.
+	pub fn acc(&self) -> &A {
+		&self.acc
+	}
+
+	/// Get a mutable reference to the accumulator.
+	pub fn acc_mut(&mut self) -> &mut A {
+		&mut self.acc
+	}
+
+	/// Take the accumulator, leaving the default value in its place.
+	pub fn take_acc(&mut self) -> A
+	where
+		A: Default,
+	{
+		mem::take(&mut self.acc)
+	}
+
+	fn compute(&mut self, input: C::Input) -> &C::Output {
+		self.cache.begin(&input);
+
+		let mut ref_cache = AccRefCache {
+			cache: &mut self.cache,
+			acc: &mut self.acc,
+			f: self.f.as_ref(),
+		};
+		let output = (self.f)(&mut ref_cache, &input);
+		self.cache.put(input, output)
+	}
+}
+
+impl<'f, C, A> AccCache<'f, C, A>
+where
+	C: SparseContainer + Default,
+{
+	/// Create an `AccCache` using the `Default` implementation of the container, an initial
+	/// accumulator, and a recursive function.
+	pub fn recursive(
+		acc: A,
+		f: impl Fn(&mut AccRefCache<C, A>, &C::Input) -> C::Output + Send + 'f,
+	) -> Self {
+		Self::recursive_with_cache(Default::default(), acc, f)
+	}
+}
+
+impl<'f, C: SparseContainer + ContainerLen, A> AccCache<'f, C, A> {
+	/// Returns the number of elements in the cache.
+	pub fn len(&self) -> usize {
+		self.cache.len()
+	}
+
+	/// Returns true if the cache holds no elements.
+	pub fn is_empty(&self) -> bool {
+		self.cache.is_empty()
+	}
+}
+
+impl<'f, C: SparseContainer + ContainerClear, A> AccCache<'f, C, A> {
+	/// Clears the cache, removing all key-value pairs.
+	/// Keeps the allocated memory for reuse.
+	///
+	/// The accumulator is left untouched.
+	pub fn clear(&mut self) {
+		self.cache.clear()
+	}
+}
+
+impl<'f, C: SparseContainer, A> FnCache<C::Input, C::Output> for AccCache<'f, C, A> {
+	fn get(&mut self, input: C::Input) -> &C::Output {
+		if self.cache.has(&input) {
+			self.cache.touch(&input);
+			self.cache.get(&input).unwrap()
+		} else {
+			self.compute(input)
+		}
+	}
+}
+
+impl<'f, C: SparseContainer, A> FnCachePeek<C::Input, C::Output> for AccCache<'f, C, A> {
+	fn peek(&self, input: &C::Input) -> Option<&C::Output> {
+		self.cache.get(input)
+	}
+
+	fn contains(&self, input: &C::Input) -> bool {
+		self.cache.has(input)
+	}
+}
+
+impl<'f, C, A> FnCacheMany<C::Input, C::Output> for AccCache<'f, C, A>
+where
+	C: SparseContainer,
+	C::Input: Clone,
+{
+	fn get_many<const N: usize>(&mut self, inputs: [C::Input; N]) -> [&C::Output; N] {
+		for i in &inputs {
+			if self.cache.has(i) {
+				self.cache.touch(i);
+			} else {
+				self.compute(i.clone());
+			}
+		}
+
+		inputs.map(|i| self.cache.get(&i).unwrap())
+	}
+}
+
+/// The cache passed to the function of an [`AccCache`], giving access to the accumulator.
+pub struct AccRefCache<'c, C: SparseContainer, A> {
+	cache: &'c mut C,
+	acc: &'c mut A,
+	#[allow(clippy::type_complexity)]
+	f: &'c (dyn Fn(&mut Self, &C::Input) -> C::Output + Send),
+}
+
+impl<'c, C: SparseContainer, A> AccRefCache<'c, C, A> {
+	/// Get a mutable reference to the accumulator.
+	pub fn acc(&mut self) -> &mut A {
+		self.acc
+	}
+
+	fn compute(&mut self, input: C::Input) -> &C::Output {
+		self.cache.begin(&input);
+		let output = (self.f)(self, &input);
+		self.cache.put(input, output)
+	}
+}
+
+impl<'c, C: SparseContainer, A> FnCache<C::Input, C::Output> for AccRefCache<'c, C, A> {
+	fn get(&mut self, input: C::Input) -> &C::Output {
+		if self.cache.has(&input) {
+			self.cache.touch(&input);
+			self.cache.get(&input).unwrap()
+		} else {
+			self.compute(input)
+		}
+	}
+}
+
+impl<'c, C: SparseContainer, A> FnCachePeek<C::Input, C::Output> for AccRefCache<'c, C, A> {
+	fn peek(&self, input: &C::Input) -> Option<&C::Output> {
+		self.cache.get(input)
+	}
+
+	fn contains(&self, input: &C::Input) -> bool {
+		self.cache.has(input)
+	}
+}
+
+impl<'c, C, A> FnCacheMany<C::Input, C::Output> for AccRefCache<'c, C, A>
+where
+	C: SparseContainer,
+	C::Input: Clone,
+{
+	fn get_many<const N: usize>(&mut self, inputs: [C::Input; N]) -> [&C::Output; N] {
+		for i in &inputs {
+			if self.cache.has(i) {
+				self.cache.touch(i);
+			} else {
+				self.compute(i.clone());
+			}
+		}
+
+		inputs.map(|i| self.cache.get(&i).unwrap())
+	}
+}
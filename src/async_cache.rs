@@ -0,0 +1,192 @@
+use std::collections::HashMap;
+use std::future::{poll_fn, Future};
+use std::hash::Hash;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Poll, Waker};
+
+use crate::lock::Mutex;
+
+/// The generic trait for caches of asynchronous functions.
+///
+/// This mirrors [`FnCache`](crate::FnCache), but computing a missing value awaits the function.
+#[allow(async_fn_in_trait)]
+pub trait AsyncFnCache<I, O> {
+	/// Retrieve a value stored in the cache. If the value does not yet exist in the cache, the
+	/// function is called and awaited, and the result is added to the cache before returning it.
+	async fn get<'c>(&'c mut self, input: I) -> &'c O
+	where
+		O: 'c;
+}
+
+type AsyncFn<'f, I, O> =
+	Box<dyn Fn(&I) -> Pin<Box<dyn Future<Output = O> + Send + 'f>> + Send + Sync + 'f>;
+
+enum Slot<O> {
+	Ready(Arc<O>),
+	/// The value is being computed, and these tasks are waiting for it.
+	Pending(Vec<Waker>),
+}
+
+/// A cache for an asynchronous function which uses a [`HashMap`].
+///
+/// Through a mutable reference, [`AsyncFnCache::get`] returns a reference to each value, like the
+/// other caches in this crate. Through a shared reference, [`Self::get_shared`] can be called by
+/// several tasks at once, and returns each value in an [`Arc`]. If several tasks request the same
+/// missing value at once, only the first of them calls the function, and the rest wait for its
+/// result instead of computing the value again. No lock is held while awaiting the function.
+///
+/// If the task computing a value is dropped before it finishes, or the function panics, one of
+/// the waiting tasks takes over and calls the function again.
+///
+/// This does not depend on any async runtime, only on the standard [`Future`] and [`Waker`]
+/// APIs, so it can be used with any executor. It is tested with a minimal single-threaded
+/// executor built on [`std::thread::park`].
+pub struct HashAsyncCache<'f, I, O> {
+	cache: Mutex<HashMap<I, Slot<O>>>,
+	f: AsyncFn<'f, I, O>,
+}
+
+impl<'f, I, O> HashAsyncCache<'f, I, O>
+where
+	I: Eq + Hash + Clone,
+{
+	/// Create a cache for the provided asynchronous function. If the function stores references,
+	/// the cache can only live as long as those references.
+	pub fn new<F, Fut>(f: F) -> Self
+	where
+		F: Fn(&I) -> Fut + Send + Sync + 'f,
+		Fut: Future<Output = O> + Send + 'f,
+	{
+		Self {
+			cache: Mutex::default(),
+			f: Box::new(move |input| Box::pin(f(input))),
+		}
+	}
+
+	/// Retrieve a value stored in the cache, computing it if it does not yet exist, through a
+	/// shared reference.
+	///
+	/// If another task is already computing the value, this waits for that task instead of
+	/// calling the function again.
+	pub async fn get_shared(&self, input: I) -> Arc<O> {
+		let claimed = poll_fn(|cx| {
+			let mut cache = self.cache.lock();
+
+			match cache.get_mut(&input) {
+				Some(Slot::Ready(output)) => Poll::Ready(Some(output.clone())),
+				Some(Slot::Pending(wakers)) => {
+					if !wakers.iter().any(|w| w.will_wake(cx.waker())) {
+						wakers.push(cx.waker().clone());
+					}
+
+					Poll::Pending
+				}
+				None => {
+					cache.insert(input.clone(), Slot::Pending(Vec::new()));
+					Poll::Ready(None)
+				}
+			}
+		})
+		.await;
+
+		if let Some(output) = claimed {
+			return output;
+		}
+
+		let guard = PendingGuard {
+			cache: &self.cache,
+			input: Some(input.clone()),
+		};
+
+		let output = Arc::new((self.f)(&input).await);
+
+		guard.complete(output.clone());
+
+		output
+	}
+
+	/// Returns true if the value for `input` has been computed, without computing it.
+	///
+	/// A value which is still being computed by another task is not yet contained.
+	pub fn contains(&self, input: &I) -> bool {
+		matches!(self.cache.lock().get(input), Some(Slot::Ready(_)))
+	}
+}
+
+impl<'f, I, O> HashAsyncCache<'f, I, O> {
+	/// Returns the number of values which have been computed.
+	///
+	/// Values which are still being computed by another task are not counted.
+	pub fn len(&self) -> usize {
+		self.cache
+			.lock()
+			.values()
+			.filter(|slot| matches!(slot, Slot::Ready(_)))
+			.count()
+	}
+
+	/// Returns true if no values have been computed.
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+}
+
+impl<'f, I, O> AsyncFnCache<I, O> for HashAsyncCache<'f, I, O>
+where
+	I: Eq + Hash + Clone,
+{
+	async fn get<'c>(&'c mut self, input: I) -> &'c O
+	where
+		O: 'c,
+	{
+		let cache = self.cache.get_mut();
+
+		if !matches!(cache.get(&input), Some(Slot::Ready(_))) {
+			let output = (self.f)(&input).await;
+			cache.insert(input.clone(), Slot::Ready(Arc::new(output)));
+		}
+
+		match cache.get(&input) {
+			Some(Slot::Ready(output)) => output,
+			_ => unreachable!(),
+		}
+	}
+}
+
+/// Marks a value as being computed, and wakes every task waiting for it once it is complete.
+///
+/// If dropped before completing, such as when the computing task is cancelled, the value is
+/// marked as missing again, and the waiting tasks are woken so that one of them computes it.
+struct PendingGuard<'c, I: Eq + Hash, O> {
+	cache: &'c Mutex<HashMap<I, Slot<O>>>,
+	input: Option<I>,
+}
+
+impl<'c, I: Eq + Hash, O> PendingGuard<'c, I, O> {
+	fn complete(mut self, output: Arc<O>) {
+		let input = self.input.take().unwrap();
+		let slot = self.cache.lock().insert(input, Slot::Ready(output));
+
+		wake(slot);
+	}
+}
+
+impl<'c, I: Eq + Hash, O> Drop for PendingGuard<'c, I, O> {
+	fn drop(&mut self) {
+		if let Some(input) = self.input.take() {
+			let slot = match self.cache.lock_unpoisoned() {
+				Some(mut cache) => cache.remove(&input),
+				None => return,
+			};
+
+			wake(slot);
+		}
+	}
+}
+
+fn wake<O>(slot: Option<Slot<O>>) {
+	if let Some(Slot::Pending(wakers)) = slot {
+		wakers.into_iter().for_each(Waker::wake);
+	}
+}
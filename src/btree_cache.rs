@@ -3,7 +3,10 @@ use std::collections::BTreeMap;
 use core::cmp::Ord;
 
 use crate::{
-	container::{ContainerClear, ContainerLen, ContainerRemove, SparseContainer},
+	container::{
+		ContainerCapacity, ContainerClear, ContainerDrain, ContainerIter, ContainerLen,
+		ContainerRemove, SparseContainer,
+	},
 	GenericCache,
 };
 
@@ -56,6 +59,15 @@ where
 	}
 }
 
+impl<I, O> ContainerCapacity for BTreeMap<I, O>
+where
+	I: Ord,
+{
+	fn capacity(&self) -> usize {
+		self.len()
+	}
+}
+
 impl<I, O> ContainerRemove for BTreeMap<I, O>
 where
 	I: Ord,
@@ -64,3 +76,23 @@ where
 		self.remove(input)
 	}
 }
+
+/// A [`BTreeMap`] has no allocated memory to keep, so the entries are taken out of it, and
+/// returned in order of the input.
+impl<I, O> ContainerDrain for BTreeMap<I, O>
+where
+	I: Ord,
+{
+	fn drain(&mut self) -> impl Iterator<Item = (Self::Input, Self::Output)> {
+		core::mem::take(self).into_iter()
+	}
+}
+
+impl<I, O> ContainerIter for BTreeMap<I, O>
+where
+	I: Ord,
+{
+	fn iter(&self) -> impl Iterator<Item = (&Self::Input, &Self::Output)> {
+		self.iter()
+	}
+}
@@ -0,0 +1,128 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use core::hash::Hash;
+
+use crate::generic_cache::GenericCache;
+use crate::ttl_cache::{Clock, SystemClock};
+use crate::{FnCache, FnCachePeek};
+
+/// A cache for a function whose values depend on the current time, computing each value once per
+/// time bucket.
+///
+/// Time is divided into buckets of a fixed length, starting when the cache is created, and each
+/// input is cached separately for each bucket, as if the bucket were part of the input. Values
+/// from past buckets are never returned, but are kept until [`Self::purge_old_buckets`] is
+/// called.
+///
+/// ```
+/// # use fn_cache::FnCache;
+/// # use fn_cache::bucket_cache::TimeBucketCache;
+/// # use std::time::Duration;
+/// let mut cache = TimeBucketCache::time_bucketed(Duration::from_secs(60), |x: &usize| *x);
+///
+/// assert_eq!(cache.get(1), &1);
+/// assert_eq!(cache.current_bucket(), 0);
+/// ```
+pub struct TimeBucketCache<'f, I, O, K = SystemClock>
+where
+	I: Eq + Hash,
+{
+	raw: GenericCache<'f, HashMap<(I, u64), O>>,
+	bucket: Duration,
+	origin: Instant,
+	clock: K,
+}
+
+impl<'f, I, O> TimeBucketCache<'f, I, O>
+where
+	I: Eq + Hash,
+{
+	/// Create a cache for the provided function, which computes each value again in every
+	/// `bucket` of time.
+	///
+	/// # Panics
+	/// If `bucket` is zero.
+	pub fn time_bucketed(bucket: Duration, f: impl Fn(&I) -> O + Send + Sync + 'f) -> Self {
+		Self::time_bucketed_with_clock(bucket, SystemClock, f)
+	}
+}
+
+impl<'f, I, O, K> TimeBucketCache<'f, I, O, K>
+where
+	I: Eq + Hash,
+	K: Clock,
+{
+	/// Create a cache for the provided function, which computes each value again in every
+	/// `bucket` of time, according to `clock`.
+	///
+	/// # Panics
+	/// If `bucket` is zero.
+	pub fn time_bucketed_with_clock(
+		bucket: Duration,
+		clock: K,
+		f: impl Fn(&I) -> O + Send + Sync + 'f,
+	) -> Self {
+		assert!(!bucket.is_zero(), "TimeBucketCache bucket must be non-zero");
+
+		Self {
+			raw: GenericCache::with_cache(HashMap::new(), move |(i, _)| f(i)),
+			bucket,
+			origin: clock.now(),
+			clock,
+		}
+	}
+
+	/// Get a reference to the underlying cache, where each input is paired with its bucket.
+	pub fn cache(&self) -> &HashMap<(I, u64), O> {
+		self.raw.cache()
+	}
+
+	/// Returns the index of the current bucket, counting from zero when the cache was created.
+	pub fn current_bucket(&self) -> u64 {
+		let elapsed = self.clock.now().saturating_duration_since(self.origin);
+		(elapsed.as_nanos() / self.bucket.as_nanos()) as u64
+	}
+
+	/// Removes every value computed in a past bucket, returning how many were removed.
+	pub fn purge_old_buckets(&mut self) -> usize {
+		let current = self.current_bucket();
+		let len = self.raw.len();
+
+		self.raw.cache.retain(|(_, bucket), _| *bucket >= current);
+
+		len - self.raw.len()
+	}
+
+	/// Returns the number of elements in the cache, including those from past buckets.
+	pub fn len(&self) -> usize {
+		self.raw.len()
+	}
+
+	/// Returns true if the cache holds no elements.
+	pub fn is_empty(&self) -> bool {
+		self.raw.is_empty()
+	}
+}
+
+impl<'f, I, O, K> FnCache<I, O> for TimeBucketCache<'f, I, O, K>
+where
+	I: Eq + Hash + Clone,
+	K: Clock,
+{
+	fn get(&mut self, input: I) -> &O {
+		let bucket = self.current_bucket();
+		self.raw.get((input, bucket))
+	}
+}
+
+impl<'f, I, O, K> FnCachePeek<I, O> for TimeBucketCache<'f, I, O, K>
+where
+	I: Eq + Hash + Clone,
+	K: Clock,
+{
+	/// Only a value computed in the current bucket is found.
+	fn peek(&self, input: &I) -> Option<&O> {
+		self.raw.cache.get(&(input.clone(), self.current_bucket()))
+	}
+}
@@ -0,0 +1,191 @@
+use std::collections::{BTreeMap, HashMap};
+
+use core::hash::Hash;
+
+use crate::container::{
+	ContainerClear, ContainerIter, ContainerLen, ContainerRemove, SparseContainer,
+};
+use crate::generic_cache::{GenericCache, RefCache};
+
+/// A cache which holds values up to an estimated total size in bytes, evicting the oldest values
+/// to make room for a new one.
+///
+/// This is better suited than a [`LruCache`](crate::LruCache) when the sizes of the values vary
+/// widely, such as buffers of different lengths, since the number of values is a poor measure of
+/// the memory they use.
+///
+/// Values may be evicted while a recursive function is still running, so a recursive function
+/// should not rely on holding more values than fit in the budget. For the same reason,
+/// [`FnCacheMany::get_many`](crate::FnCacheMany::get_many),
+/// [`FnCacheMany::get_many_vec`](crate::FnCacheMany::get_many_vec) and
+/// [`FnCacheMany::get_two`](crate::FnCacheMany::get_two) will panic if asked for more values
+/// than fit in the budget at once.
+///
+/// ```
+/// # use fn_cache::{FnCache, BudgetCache};
+/// let mut cache = BudgetCache::<usize, Vec<u8>>::with_budget(100, Vec::len, |x| vec![0; *x]);
+///
+/// cache.get(60);
+/// cache.get(30);
+/// cache.get(50);
+///
+/// assert!(!cache.cache().contains(&60));
+/// assert_eq!(cache.cache().total_bytes(), 80);
+/// ```
+pub type BudgetCache<'f, I, O> = GenericCache<'f, Budget<I, O>>;
+
+type SizeFn<O> = Box<dyn Fn(&O) -> usize + Send>;
+
+/// A [`SparseContainer`] backed by a [`HashMap`], which evicts the oldest values once the total
+/// estimated size of its values is over a budget.
+///
+/// The size of each value is estimated once by a function when the value is put, and stored
+/// alongside it. A running total of these sizes is kept, which is increased when a value is put,
+/// and decreased by the stored size when a value is evicted, removed, or cleared, so the size
+/// function is never called again for the same value.
+///
+/// After a value is put, the oldest values are evicted until the total fits in the budget. The
+/// newest value is never evicted, so a single value larger than the whole budget is still held,
+/// alone, until the next value is put.
+pub struct Budget<I, O> {
+	map: HashMap<I, (O, usize, u64)>,
+	order: BTreeMap<u64, I>,
+	tick: u64,
+	total: usize,
+	max_bytes: usize,
+	size_of: SizeFn<O>,
+}
+
+impl<I, O> Budget<I, O>
+where
+	I: Eq + Hash + Clone,
+{
+	/// Create an empty container holding values with an estimated total size of at most
+	/// `max_bytes`, as estimated by `size_of`.
+	pub fn with_budget(max_bytes: usize, size_of: impl Fn(&O) -> usize + Send + 'static) -> Self {
+		Self {
+			map: HashMap::new(),
+			order: BTreeMap::new(),
+			tick: 0,
+			total: 0,
+			max_bytes,
+			size_of: Box::new(size_of),
+		}
+	}
+
+	/// Returns the largest estimated total size the container will hold, in bytes.
+	pub fn max_bytes(&self) -> usize {
+		self.max_bytes
+	}
+
+	/// Returns the estimated total size of every value in the container, in bytes.
+	pub fn total_bytes(&self) -> usize {
+		self.total
+	}
+
+	/// Returns true if the container holds a value for `input`.
+	pub fn contains(&self, input: &I) -> bool {
+		self.map.contains_key(input)
+	}
+}
+
+impl<I, O> SparseContainer for Budget<I, O>
+where
+	I: Eq + Hash + Clone,
+{
+	type Input = I;
+	type Output = O;
+
+	fn has(&self, input: &I) -> bool {
+		self.map.contains_key(input)
+	}
+
+	fn get(&self, input: &I) -> Option<&O> {
+		self.map.get(input).map(|(o, _, _)| o)
+	}
+
+	fn put(&mut self, input: I, output: O) -> &O {
+		if !self.map.contains_key(&input) {
+			let size = (self.size_of)(&output);
+
+			self.tick += 1;
+			self.total += size;
+			self.order.insert(self.tick, input.clone());
+			self.map.insert(input.clone(), (output, size, self.tick));
+
+			while self.total > self.max_bytes && self.map.len() > 1 {
+				let (_, oldest) = self.order.pop_first().unwrap();
+				let (_, size, _) = self.map.remove(&oldest).unwrap();
+				self.total -= size;
+			}
+		}
+
+		&self.map[&input].0
+	}
+}
+
+impl<I, O> ContainerLen for Budget<I, O>
+where
+	I: Eq + Hash + Clone,
+{
+	fn len(&self) -> usize {
+		self.map.len()
+	}
+}
+
+impl<I, O> ContainerClear for Budget<I, O>
+where
+	I: Eq + Hash + Clone,
+{
+	fn clear(&mut self) {
+		self.map.clear();
+		self.order.clear();
+		self.total = 0;
+	}
+}
+
+impl<I, O> ContainerRemove for Budget<I, O>
+where
+	I: Eq + Hash + Clone,
+{
+	fn remove(&mut self, input: &I) -> Option<O> {
+		let (output, size, tick) = self.map.remove(input)?;
+		self.order.remove(&tick);
+		self.total -= size;
+		Some(output)
+	}
+}
+
+impl<I, O> ContainerIter for Budget<I, O>
+where
+	I: Eq + Hash + Clone,
+{
+	fn iter(&self) -> impl Iterator<Item = (&I, &O)> {
+		self.map.iter().map(|(i, (o, _, _))| (i, o))
+	}
+}
+
+impl<'f, I, O> GenericCache<'f, Budget<I, O>>
+where
+	I: Eq + Hash + Clone,
+{
+	/// Create a cache holding values with an estimated total size of at most `max_bytes`, as
+	/// estimated by `size_of`, for the provided function.
+	pub fn with_budget(
+		max_bytes: usize,
+		size_of: impl Fn(&O) -> usize + Send + 'static,
+		f: impl Fn(&I) -> O + Send + Sync + 'f,
+	) -> Self {
+		Self::with_cache(Budget::with_budget(max_bytes, size_of), f)
+	}
+
+	/// Create a cache holding values with an estimated total size of at most `max_bytes`, as
+	/// estimated by `size_of`, for the provided recursive function.
+	pub fn recursive_with_budget(
+		max_bytes: usize,
+		size_of: impl Fn(&O) -> usize + Send + 'static,
+		f: impl Fn(&mut RefCache<Budget<I, O>>, &I) -> O + Send + Sync + 'f,
+	) -> Self {
+		Self::recursive_with_cache(Budget::with_budget(max_bytes, size_of), f)
+	}
+}
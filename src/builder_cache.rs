@@ -0,0 +1,135 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use core::hash::Hash;
+
+use crate::container::SparseContainer;
+
+/// The error returned when appending to a [`BuilderCache`] for an input which was already
+/// finalized.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AlreadyFinalized;
+
+impl fmt::Display for AlreadyFinalized {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str("cannot append to a value which was already finalized")
+	}
+}
+
+impl std::error::Error for AlreadyFinalized {}
+
+/// A cache for values built up incrementally, such as aggregating a stream of events for each
+/// input.
+///
+/// Events are fed into a builder for each input using [`Self::append`], starting from the
+/// `Default` builder. Once [`Self::finalize`] is called for an input, the builder is turned into
+/// the final value, which is cached, and any further events for that input are rejected.
+///
+/// ```
+/// # use fn_cache::builder_cache::{AlreadyFinalized, BuilderCache};
+/// # use std::collections::HashMap;
+/// let mut cache = BuilderCache::<HashMap<&str, f64>, Vec<f64>, f64>::new(
+///     |samples, x| samples.push(x),
+///     |_, samples| samples.iter().sum::<f64>() / samples.len() as f64,
+/// );
+///
+/// cache.append("a", 1.0)?;
+/// cache.append("a", 3.0)?;
+///
+/// assert_eq!(cache.finalize("a"), &2.0);
+/// assert_eq!(cache.append("a", 5.0), Err(AlreadyFinalized));
+/// # Ok::<(), AlreadyFinalized>(())
+/// ```
+pub struct BuilderCache<'f, C: SparseContainer, B, E> {
+	cache: C,
+	builders: HashMap<C::Input, B>,
+	#[allow(clippy::type_complexity)]
+	append: Box<dyn Fn(&mut B, E) + Send + 'f>,
+	#[allow(clippy::type_complexity)]
+	finish: Box<dyn Fn(&C::Input, B) -> C::Output + Send + 'f>,
+}
+
+impl<'f, C, B, E> BuilderCache<'f, C, B, E>
+where
+	C: SparseContainer,
+	C::Input: Eq + Hash,
+	B: Default,
+{
+	/// Create a `BuilderCache` out of a cache, a function to add an event to a builder, and a
+	/// function to turn a builder into the final value.
+	pub fn with_cache(
+		cache: C,
+		append: impl Fn(&mut B, E) + Send + 'f,
+		finish: impl Fn(&C::Input, B) -> C::Output + Send + 'f,
+	) -> Self {
+		Self {
+			cache,
+			builders: HashMap::new(),
+			append: Box::new(append),
+			finish: Box::new(finish),
+		}
+	}
+
+	/// Get a reference to the underlying cache object, holding the finalized values.
+	pub fn cache(&self) -> &C {
+		&self.cache
+	}
+
+	/// Feed an event into the builder for `input`, creating the builder if this is the first
+	/// event.
+	///
+	/// Returns [`AlreadyFinalized`] if `input` has already been finalized, in which case the
+	/// event is dropped.
+	pub fn append(&mut self, input: C::Input, event: E) -> Result<(), AlreadyFinalized> {
+		if self.cache.has(&input) {
+			return Err(AlreadyFinalized);
+		}
+
+		(self.append)(self.builders.entry(input).or_default(), event);
+
+		Ok(())
+	}
+
+	/// Turn the builder for `input` into its final value and cache it, or return the value
+	/// already cached if `input` was finalized before.
+	///
+	/// An input with no events is finalized from the `Default` builder.
+	pub fn finalize(&mut self, input: C::Input) -> &C::Output {
+		if self.cache.has(&input) {
+			self.cache.touch(&input);
+			return self.cache.get(&input).unwrap();
+		}
+
+		let builder = self.builders.remove(&input).unwrap_or_default();
+
+		self.cache.begin(&input);
+		let output = (self.finish)(&input, builder);
+		self.cache.put(input, output)
+	}
+
+	/// Returns the final value for `input`, or `None` if it has not been finalized.
+	pub fn get(&self, input: &C::Input) -> Option<&C::Output> {
+		self.cache.get(input)
+	}
+
+	/// Returns the builder for `input`, or `None` if it has no events waiting to be finalized.
+	pub fn builder(&self, input: &C::Input) -> Option<&B> {
+		self.builders.get(input)
+	}
+}
+
+impl<'f, C, B, E> BuilderCache<'f, C, B, E>
+where
+	C: SparseContainer + Default,
+	C::Input: Eq + Hash,
+	B: Default,
+{
+	/// Create a `BuilderCache` using the `Default` implementation of the container, a function
+	/// to add an event to a builder, and a function to turn a builder into the final value.
+	pub fn new(
+		append: impl Fn(&mut B, E) + Send + 'f,
+		finish: impl Fn(&C::Input, B) -> C::Output + Send + 'f,
+	) -> Self {
+		Self::with_cache(Default::default(), append, finish)
+	}
+}
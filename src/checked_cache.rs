@@ -0,0 +1,125 @@
+use std::fmt::Debug;
+
+use crate::container::SparseContainer;
+use crate::generic_cache::{GenericCache, RefCache};
+use crate::{FnCache, FnCachePeek};
+
+/// A cache which checks that its function is pure, by occasionally computing a cached value again
+/// and asserting that it has not changed.
+///
+/// The check only runs in debug builds, on every `n`th hit. In release builds, this behaves
+/// exactly like the [`GenericCache`] it wraps.
+///
+/// ```
+/// # use fn_cache::FnCache;
+/// # use fn_cache::checked_cache::CheckedCache;
+/// # use std::collections::HashMap;
+/// let mut cache = CheckedCache::<HashMap<usize, usize>>::new_checked(|x| x * 2);
+///
+/// for _ in 0..100 {
+///     assert_eq!(cache.get(3), &6);
+/// }
+/// ```
+pub struct CheckedCache<'f, C: SparseContainer> {
+	raw: GenericCache<'f, C>,
+	every: usize,
+	hits: usize,
+}
+
+impl<'f, C: SparseContainer> CheckedCache<'f, C> {
+	/// How many hits happen between each check, unless set using [`Self::check_every`].
+	pub const DEFAULT_CHECK_INTERVAL: usize = 16;
+
+	/// Wrap a cache, checking its function on every
+	/// [`DEFAULT_CHECK_INTERVAL`](Self::DEFAULT_CHECK_INTERVAL)th hit.
+	pub fn with_raw(raw: GenericCache<'f, C>) -> Self {
+		Self {
+			raw,
+			every: Self::DEFAULT_CHECK_INTERVAL,
+			hits: 0,
+		}
+	}
+
+	/// Sets how many hits happen between each check.
+	///
+	/// # Panics
+	/// If `every` is zero.
+	pub fn check_every(mut self, every: usize) -> Self {
+		assert!(every > 0, "CheckedCache interval must be non-zero");
+		self.every = every;
+		self
+	}
+
+	/// Get a reference to the wrapped cache.
+	pub fn raw(&self) -> &GenericCache<'f, C> {
+		&self.raw
+	}
+
+	/// Unwrap the cache, which no longer checks its function.
+	pub fn into_raw(self) -> GenericCache<'f, C> {
+		self.raw
+	}
+}
+
+impl<'f, C> CheckedCache<'f, C>
+where
+	C: SparseContainer + Default,
+{
+	/// Create a cache for the provided function, which checks the function is pure in debug
+	/// builds.
+	pub fn new_checked(f: impl Fn(&C::Input) -> C::Output + Send + Sync + 'f) -> Self {
+		Self::with_raw(GenericCache::new(f))
+	}
+
+	/// Create a cache for the provided recursive function, which checks the function is pure in
+	/// debug builds.
+	pub fn recursive_checked(
+		f: impl Fn(&mut RefCache<C>, &C::Input) -> C::Output + Send + Sync + 'f,
+	) -> Self {
+		Self::with_raw(GenericCache::recursive(f))
+	}
+}
+
+impl<'f, C> FnCache<C::Input, C::Output> for CheckedCache<'f, C>
+where
+	C: SparseContainer,
+	C::Output: PartialEq + Debug,
+{
+	/// # Panics
+	/// In debug builds, if the function returns a different value than the cached one when
+	/// checked.
+	fn get(&mut self, input: C::Input) -> &C::Output {
+		if cfg!(debug_assertions) && self.raw.cache.has(&input) {
+			self.hits += 1;
+
+			if self.hits == self.every {
+				self.hits = 0;
+
+				let f = crate::generic_cache::expect_fn(&self.raw.f).as_ref();
+				let output = f(&mut RefCache::new(&mut self.raw.cache, f), &input);
+
+				assert_eq!(
+					self.raw.cache.get(&input).unwrap(),
+					&output,
+					"cache function returned a different value for the same input"
+				);
+			}
+		}
+
+		self.raw.get(input)
+	}
+}
+
+impl<'f, C> FnCachePeek<C::Input, C::Output> for CheckedCache<'f, C>
+where
+	C: SparseContainer,
+	C::Output: PartialEq + Debug,
+{
+	fn peek(&self, input: &C::Input) -> Option<&C::Output> {
+		self.raw.peek(input)
+	}
+
+	fn contains(&self, input: &C::Input) -> bool {
+		self.raw.contains(input)
+	}
+}
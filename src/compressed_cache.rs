@@ -0,0 +1,108 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+use flate2::Compression;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// A cache which stores each value serialized and compressed, decompressing it on every access.
+///
+/// This trades CPU for memory: every miss pays to serialize and compress the value, and every hit
+/// pays to decompress and deserialize it again. It is only worthwhile for large values which
+/// compress well, and where the function is much more expensive than decompression.
+///
+/// Since the stored form is not an `O`, values are returned by value instead of by reference, so
+/// unlike the other caches in this crate, this cache does not implement
+/// [`FnCache`](crate::FnCache), and each access makes a new copy of the value.
+///
+/// ```
+/// # use fn_cache::compressed_cache::CompressedCache;
+/// let mut cache = CompressedCache::new(|x: &usize| vec![0u64; *x]);
+///
+/// assert_eq!(cache.get(1000).unwrap(), vec![0; 1000]);
+/// assert!(cache.stored_bytes() < 1000);
+/// ```
+pub struct CompressedCache<'f, I, O> {
+	cache: HashMap<I, Vec<u8>>,
+	f: Box<dyn Fn(&I) -> O + Send + 'f>,
+}
+
+impl<'f, I, O> CompressedCache<'f, I, O>
+where
+	I: Eq + Hash,
+	O: Serialize + DeserializeOwned,
+{
+	/// Create a cache for the provided function. If the function stores references, the cache
+	/// can only live as long as those references.
+	pub fn new(f: impl Fn(&I) -> O + Send + 'f) -> Self {
+		Self {
+			cache: HashMap::default(),
+			f: Box::new(f),
+		}
+	}
+
+	/// Retrieve a value from the cache, computing and compressing it if it does not yet exist,
+	/// and return a decompressed copy of it.
+	///
+	/// Returns an error if the value could not be serialized, in which case nothing is cached.
+	pub fn get(&mut self, input: I) -> serde_json::Result<O> {
+		match self.cache.get(&input) {
+			Some(bytes) => decompress(bytes),
+			None => {
+				let output = (self.f)(&input);
+
+				self.cache.insert(input, compress(&output)?);
+
+				Ok(output)
+			}
+		}
+	}
+
+	/// Returns true if the value for `input` is already stored in the cache, without computing
+	/// it.
+	pub fn contains(&self, input: &I) -> bool {
+		self.cache.contains_key(input)
+	}
+
+	/// Returns the compressed bytes stored for `input`, or `None` if it is not in the cache.
+	pub fn compressed(&self, input: &I) -> Option<&[u8]> {
+		self.cache.get(input).map(Vec::as_slice)
+	}
+}
+
+impl<'f, I, O> CompressedCache<'f, I, O> {
+	/// Returns the number of elements in the cache.
+	pub fn len(&self) -> usize {
+		self.cache.len()
+	}
+
+	/// Returns true if the cache holds no elements.
+	pub fn is_empty(&self) -> bool {
+		self.cache.is_empty()
+	}
+
+	/// Returns the total size of every compressed value in the cache, in bytes.
+	pub fn stored_bytes(&self) -> usize {
+		self.cache.values().map(Vec::len).sum()
+	}
+
+	/// Clears the cache, removing all key-value pairs.
+	/// Keeps the allocated memory for reuse.
+	pub fn clear(&mut self) {
+		self.cache.clear()
+	}
+}
+
+fn compress<O: Serialize>(output: &O) -> serde_json::Result<Vec<u8>> {
+	let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+
+	serde_json::to_writer(&mut encoder, output)?;
+
+	encoder.finish().map_err(serde_json::Error::io)
+}
+
+fn decompress<O: DeserializeOwned>(bytes: &[u8]) -> serde_json::Result<O> {
+	serde_json::from_reader(DeflateDecoder::new(bytes))
+}
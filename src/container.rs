@@ -23,6 +23,29 @@ pub trait SparseContainer: Sized {
 	/// Associate a new `output` with the key `input`, which can later be retrieved using
 	/// [`Self::get`]
 	fn put(&mut self, input: Self::Input, output: Self::Output) -> &Self::Output;
+
+	/// Called whenever the output associated with `input` is retrieved from the cache, without
+	/// being computed. Containers which track how recently or how often their values are used,
+	/// such as those with eviction policies, can update their bookkeeping here.
+	///
+	/// Does nothing by default.
+	fn touch(&mut self, input: &Self::Input) {
+		let _ = input;
+	}
+
+	/// Called just before the output associated with `input` is computed by the cache, which is
+	/// followed by a call to [`Self::put`] with the same input once the computation finishes.
+	/// Calls may be nested, when a recursive function computes its dependencies. Containers
+	/// which track the dependencies between values can use this to tell which value is reading
+	/// the others.
+	///
+	/// If the function panics, or fails in a fallible cache such as a
+	/// [`TryCache`](crate::try_cache::TryCache), `put` is never called for the input.
+	///
+	/// Does nothing by default.
+	fn begin(&mut self, input: &Self::Input) {
+		let _ = input;
+	}
 }
 
 /// A trait to clear the container, for cases when caching may need to be temporary during some
@@ -37,6 +60,11 @@ pub trait ContainerClear {
 pub trait ContainerLen {
 	/// Returns the number of elements in the container.
 	fn len(&self) -> usize;
+
+	/// Returns true if the container holds no elements.
+	fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
 }
 
 /// A trait to reserve space in a container, in case you know how many values are about to enter
@@ -48,9 +76,58 @@ pub trait ContainerReserve {
 	fn reserve(&mut self, additional: usize);
 }
 
+/// A trait to release memory a container is holding for values it no longer has, such as after
+/// many values are removed.
+pub trait ContainerShrink {
+	/// Shrinks the capacity of the container as much as possible.
+	fn shrink_to_fit(&mut self);
+}
+
+/// A trait to see how many values a container can hold, such as before it must reallocate, or
+/// before it starts evicting values.
+pub trait ContainerCapacity {
+	/// Returns the number of elements the container can hold without reallocating or evicting.
+	///
+	/// Containers which allocate for each element, such as a `BTreeMap`, return their length.
+	fn capacity(&self) -> usize;
+}
+
 /// A trait to remove items from a container, to prevent growth without bound.
 pub trait ContainerRemove: SparseContainer {
 	/// Removes the input from the cache, returning any value
 	/// if the input was previously in the cache.
 	fn remove(&mut self, input: &Self::Input) -> Option<Self::Output>;
 }
+
+/// A trait to remove every input and output from a container at once, taking ownership of them.
+pub trait ContainerDrain: SparseContainer {
+	/// Removes every input and output from the container, returning them in an iterator.
+	///
+	/// The container keeps any allocated memory for reuse, if it has any. Every value is removed,
+	/// even if the iterator is dropped before it is exhausted.
+	fn drain(&mut self) -> impl Iterator<Item = (Self::Input, Self::Output)>;
+}
+
+/// A trait to iterate over the inputs and outputs held by a container.
+pub trait ContainerIter: SparseContainer {
+	/// Returns an iterator over every input and its associated output in the container.
+	fn iter(&self) -> impl Iterator<Item = (&Self::Input, &Self::Output)>;
+}
+
+/// A trait for containers with a bounded size, which evict a value to make room for a new one.
+pub trait ContainerEvict: SparseContainer {
+	/// Associate a new `output` with the key `input`, the same as [`SparseContainer::put`], but
+	/// return any value evicted to make room for it instead of dropping it.
+	///
+	/// Any function the container calls on eviction is not called for the returned value.
+	#[allow(clippy::type_complexity)]
+	fn put_evicting(
+		&mut self,
+		input: Self::Input,
+		output: Self::Output,
+	) -> (&Self::Output, Option<(Self::Input, Self::Output)>);
+
+	/// Returns the key which would be evicted if `input` were inserted now, or `None` if it
+	/// would fit, or is already present.
+	fn would_evict(&self, input: &Self::Input) -> Option<&Self::Input>;
+}
@@ -0,0 +1,194 @@
+use std::cell::Cell;
+use std::collections::HashMap;
+
+use crate::container::{
+	ContainerClear, ContainerIter, ContainerLen, ContainerRemove, SparseContainer,
+};
+use crate::GenericCache;
+
+/// A cache which counts how many times each value has been accessed.
+///
+/// Computing a value counts as its first access, and every hit after that adds one more.
+///
+/// ```
+/// # use fn_cache::{FnCache, CountedCache};
+/// let mut cache = CountedCache::<usize, usize>::new(|x| *x);
+///
+/// cache.get(1);
+/// cache.get(1);
+/// cache.get(2);
+///
+/// assert_eq!(cache.access_count(&1), Some(2));
+/// assert_eq!(cache.access_count(&2), Some(1));
+/// assert_eq!(cache.access_count(&3), None);
+/// ```
+///
+/// Other containers can be counted by using a `GenericCache<Counted<C>>` directly, as long as
+/// they store a counter alongside each output.
+pub type CountedCache<'f, I, O> = GenericCache<'f, Counted<HashMap<I, (O, Cell<u64>)>>>;
+
+/// A [`SparseContainer`] which stores how many times each output has been accessed alongside
+/// it.
+#[derive(Default)]
+pub struct Counted<C> {
+	inner: C,
+}
+
+impl<C> Counted<C> {
+	/// Wrap a container, which stores each output along with its access count.
+	pub fn new(inner: C) -> Self {
+		Self { inner }
+	}
+
+	/// Get a reference to the wrapped container.
+	pub fn inner(&self) -> &C {
+		&self.inner
+	}
+}
+
+impl<C, O> SparseContainer for Counted<C>
+where
+	C: SparseContainer<Output = (O, Cell<u64>)>,
+{
+	type Input = C::Input;
+	type Output = O;
+
+	fn has(&self, input: &Self::Input) -> bool {
+		self.inner.has(input)
+	}
+
+	fn get(&self, input: &Self::Input) -> Option<&Self::Output> {
+		self.inner.get(input).map(|(o, _)| o)
+	}
+
+	fn put(&mut self, input: Self::Input, output: Self::Output) -> &Self::Output {
+		&self.inner.put(input, (output, Cell::new(1))).0
+	}
+
+	fn touch(&mut self, input: &Self::Input) {
+		if let Some((_, count)) = self.inner.get(input) {
+			count.set(count.get() + 1);
+		}
+
+		self.inner.touch(input)
+	}
+
+	fn begin(&mut self, input: &Self::Input) {
+		self.inner.begin(input)
+	}
+}
+
+impl<C, O> ContainerLen for Counted<C>
+where
+	C: SparseContainer<Output = (O, Cell<u64>)> + ContainerLen,
+{
+	fn len(&self) -> usize {
+		self.inner.len()
+	}
+}
+
+impl<C, O> ContainerClear for Counted<C>
+where
+	C: SparseContainer<Output = (O, Cell<u64>)> + ContainerClear,
+{
+	fn clear(&mut self) {
+		self.inner.clear()
+	}
+}
+
+impl<C, O> ContainerRemove for Counted<C>
+where
+	C: ContainerRemove<Output = (O, Cell<u64>)>,
+{
+	fn remove(&mut self, input: &Self::Input) -> Option<Self::Output> {
+		self.inner.remove(input).map(|(o, _)| o)
+	}
+}
+
+impl<'f, C, O> GenericCache<'f, Counted<C>>
+where
+	C: SparseContainer<Output = (O, Cell<u64>)>,
+{
+	/// Returns how many times the value for `input` has been accessed, or `None` if it is not in
+	/// the cache.
+	pub fn access_count(&self, input: &C::Input) -> Option<u64> {
+		self.cache.inner.get(input).map(|(_, count)| count.get())
+	}
+}
+
+#[cfg(feature = "stats")]
+impl<'f, C, O> GenericCache<'f, Counted<C>>
+where
+	C: ContainerIter<Output = (O, Cell<u64>)>,
+{
+	/// Returns every input whose value was computed, but has never been read since.
+	///
+	/// This helps find values which were computed speculatively, such as by prefetching, but
+	/// turned out not to be needed.
+	///
+	/// ```
+	/// # use fn_cache::{FnCache, CountedCache};
+	/// let mut cache = CountedCache::<usize, usize>::new(|x| *x);
+	///
+	/// cache.get(1);
+	/// cache.get(1);
+	/// cache.get(2);
+	///
+	/// assert_eq!(cache.unread_entries(), [&2]);
+	/// ```
+	pub fn unread_entries(&self) -> Vec<&C::Input> {
+		self.cache
+			.inner
+			.iter()
+			.filter(|(_, (_, count))| count.get() == 1)
+			.map(|(i, _)| i)
+			.collect()
+	}
+}
+
+impl<'f, C, O> GenericCache<'f, Counted<C>>
+where
+	C: ContainerIter<Output = (O, Cell<u64>)> + ContainerRemove + Default,
+	C::Input: Clone,
+{
+	/// Splits the cache in two, by how many times each value has been accessed.
+	///
+	/// The first cache holds every value accessed at least `threshold` times, and the second holds
+	/// the rest. Access counts are kept, and both caches share the same function.
+	///
+	/// ```
+	/// # use fn_cache::{FnCache, CountedCache};
+	/// let mut cache = CountedCache::<usize, usize>::new(|x| *x);
+	///
+	/// cache.get(1);
+	/// cache.get(1);
+	/// cache.get(2);
+	///
+	/// let (warm, cold) = cache.partition_by_access(2);
+	///
+	/// assert_eq!(warm.access_count(&1), Some(2));
+	/// assert_eq!(cold.access_count(&2), Some(1));
+	/// assert_eq!(warm.len() + cold.len(), 2);
+	/// ```
+	pub fn partition_by_access(mut self, threshold: u64) -> (Self, Self) {
+		let mut warm = C::default();
+		let mut cold = C::default();
+
+		let inputs: Vec<_> = self.cache.inner.iter().map(|(i, _)| i.clone()).collect();
+
+		for input in inputs {
+			let entry = self.cache.inner.remove(&input).unwrap();
+
+			if entry.1.get() >= threshold {
+				warm.put(input, entry);
+			} else {
+				cold.put(input, entry);
+			}
+		}
+
+		(
+			self.share_fn(Counted::new(warm)),
+			self.share_fn(Counted::new(cold)),
+		)
+	}
+}
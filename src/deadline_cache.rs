@@ -0,0 +1,113 @@
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use crate::container::SparseContainer;
+use crate::try_cache::{Allowance, TryCache, TryRefCache};
+
+/// The error returned when a [`DeadlineCache`] runs out of time.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TimedOut;
+
+impl fmt::Display for TimedOut {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str("cache computation exceeded its time budget")
+	}
+}
+
+impl std::error::Error for TimedOut {}
+
+/// An [`Allowance`] of time for each call to [`TryCache::try_get`], after which no more values
+/// are computed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TimeBudget(pub Duration);
+
+impl<C, E: From<TimedOut>> Allowance<C, E> for TimeBudget {
+	type State = Instant;
+
+	fn start(&self) -> Instant {
+		Instant::now() + self.0
+	}
+
+	fn check(deadline: &mut Instant, _: &C) -> Result<(), E> {
+		if Instant::now() >= *deadline {
+			Err(TimedOut.into())
+		} else {
+			Ok(())
+		}
+	}
+}
+
+/// A cache for a recursive function which gives up once a time budget is exceeded.
+///
+/// Each call to [`TryCache::try_get`] sets a deadline, which is shared by every recursive call
+/// made through the [`DeadlineRefCache`]. Once the deadline has passed, any value not already in
+/// the cache returns [`TimedOut`] instead of being computed, which the function propagates using
+/// `?`. This aborts the whole computation, rather than only the call that ran over.
+///
+/// The function may return its own errors as well, as long as its error type can be made from
+/// [`TimedOut`]. Values which were computed before the deadline remain in the cache.
+///
+/// ```
+/// # use fn_cache::deadline_cache::DeadlineCache;
+/// # use std::collections::HashMap;
+/// # use std::time::Duration;
+/// let mut cache = DeadlineCache::<HashMap<u64, u64>>::recursive(
+///     Duration::from_secs(1),
+///     |cache, x| match x {
+///         0 => Ok(0),
+///         1 => Ok(1),
+///         _ => Ok(*cache.try_get(x - 1)? + *cache.try_get(x - 2)?),
+///     },
+/// );
+///
+/// assert_eq!(cache.try_get(10), Ok(&55));
+/// ```
+pub type DeadlineCache<'f, C, E = TimedOut> = TryCache<'f, C, E, TimeBudget>;
+
+/// The cache passed to the function of a [`DeadlineCache`], sharing the deadline of the
+/// top-level call.
+pub type DeadlineRefCache<'c, C, E = TimedOut> = TryRefCache<'c, C, E, TimeBudget>;
+
+impl<'f, C: SparseContainer, E: From<TimedOut>> DeadlineCache<'f, C, E> {
+	/// Create a `DeadlineCache` out of a cache, a time budget for each call to
+	/// [`TryCache::try_get`], and a recursive function.
+	pub fn recursive_with_cache(
+		cache: C,
+		budget: Duration,
+		f: impl Fn(&mut DeadlineRefCache<C, E>, &C::Input) -> Result<C::Output, E> + Send + 'f,
+	) -> Self {
+		Self::try_recursive_with_allowance(cache, TimeBudget(budget), f)
+	}
+
+	/// Returns the time budget for each call to [`TryCache::try_get`].
+	pub fn budget(&self) -> Duration {
+		self.allowance().0
+	}
+
+	/// Sets the time budget for each call to [`TryCache::try_get`].
+	pub fn set_budget(&mut self, budget: Duration) {
+		self.allowance_mut().0 = budget;
+	}
+}
+
+impl<'f, C, E> DeadlineCache<'f, C, E>
+where
+	C: SparseContainer + Default,
+	E: From<TimedOut>,
+{
+	/// Create a `DeadlineCache` using the `Default` implementation of the container, a time
+	/// budget for each call to [`TryCache::try_get`], and a recursive function.
+	pub fn recursive(
+		budget: Duration,
+		f: impl Fn(&mut DeadlineRefCache<C, E>, &C::Input) -> Result<C::Output, E> + Send + 'f,
+	) -> Self {
+		Self::recursive_with_cache(Default::default(), budget, f)
+	}
+}
+
+impl<'c, C: SparseContainer, E: From<TimedOut>> DeadlineRefCache<'c, C, E> {
+	/// Returns the instant after which no more values will be computed.
+	pub fn deadline(&self) -> Instant {
+		self.state
+	}
+}
@@ -0,0 +1,329 @@
+use std::collections::{HashMap, HashSet};
+
+use core::hash::Hash;
+
+use crate::container::{
+	ContainerClear, ContainerIter, ContainerLen, ContainerRemove, SparseContainer,
+};
+use crate::generic_cache::GenericCache;
+use crate::FnCache;
+
+/// A cache which records which other values each value read while it was computed.
+///
+/// Only the immediate dependencies of each value are recorded, in the order they were first
+/// read, whether they were computed at that point or already cached.
+///
+/// ```
+/// # use fn_cache::{DepsCache, FnCache};
+/// let mut cache = DepsCache::<usize, u64>::recursive(|cache, x| match x {
+///     0 | 1 => *x as u64,
+///     _ => *cache.get(x - 1) + *cache.get(x - 2),
+/// });
+///
+/// cache.get(5);
+///
+/// assert_eq!(cache.dependencies(&5), Some(&[4, 3][..]));
+/// assert_eq!(cache.dependencies(&1), Some(&[][..]));
+/// ```
+///
+/// Other containers can be used with a `GenericCache<Deps<C>>` directly.
+pub type DepsCache<'f, I, O> = GenericCache<'f, Deps<HashMap<I, O>>>;
+
+/// A [`SparseContainer`] which records the immediate dependencies of each value in the container
+/// it wraps.
+///
+/// Dependencies are tracked using [`SparseContainer::begin`], so a value put into the container
+/// without being computed by the cache, is recorded as having none.
+pub struct Deps<C: SparseContainer> {
+	inner: C,
+	deps: HashMap<C::Input, Vec<C::Input>>,
+	stack: Vec<(C::Input, Vec<C::Input>)>,
+}
+
+impl<C> Deps<C>
+where
+	C: SparseContainer,
+	C::Input: Eq + Hash + Clone,
+{
+	/// Wrap a container, recording the dependencies of each value put into it.
+	pub fn new(inner: C) -> Self {
+		Self {
+			inner,
+			deps: HashMap::new(),
+			stack: Vec::new(),
+		}
+	}
+
+	/// Get a reference to the wrapped container.
+	pub fn inner(&self) -> &C {
+		&self.inner
+	}
+
+	/// Returns the inputs read while computing the value for `input`, or `None` if it is not in
+	/// the container.
+	pub fn dependencies(&self, input: &C::Input) -> Option<&[C::Input]> {
+		self.deps.get(input).map(Vec::as_slice)
+	}
+
+	/// Returns `input` and every input it depends on, directly or indirectly, as recorded in the
+	/// container.
+	///
+	/// Returns an empty set if `input` is not in the container.
+	pub fn dependency_closure<'a>(&'a self, input: &'a C::Input) -> HashSet<&'a C::Input> {
+		let mut closure = HashSet::new();
+		let mut stack = vec![input];
+
+		while let Some(i) = stack.pop() {
+			if let Some(deps) = self.deps.get(i) {
+				if closure.insert(i) {
+					stack.extend(deps);
+				}
+			}
+		}
+
+		closure
+	}
+
+	fn read(&mut self, input: &C::Input) {
+		if let Some((_, reads)) = self.stack.last_mut() {
+			if !reads.contains(input) {
+				reads.push(input.clone());
+			}
+		}
+	}
+}
+
+impl<C> Default for Deps<C>
+where
+	C: SparseContainer + Default,
+	C::Input: Eq + Hash + Clone,
+{
+	fn default() -> Self {
+		Self::new(C::default())
+	}
+}
+
+impl<C> SparseContainer for Deps<C>
+where
+	C: SparseContainer,
+	C::Input: Eq + Hash + Clone,
+{
+	type Input = C::Input;
+	type Output = C::Output;
+
+	fn has(&self, input: &Self::Input) -> bool {
+		self.inner.has(input)
+	}
+
+	fn get(&self, input: &Self::Input) -> Option<&Self::Output> {
+		self.inner.get(input)
+	}
+
+	fn put(&mut self, input: Self::Input, output: Self::Output) -> &Self::Output {
+		let reads = match self.stack.last() {
+			Some((top, _)) if *top == input => self.stack.pop().unwrap().1,
+			_ => Vec::new(),
+		};
+
+		self.deps.insert(input.clone(), reads);
+		self.read(&input);
+		self.inner.put(input, output)
+	}
+
+	fn touch(&mut self, input: &Self::Input) {
+		self.read(input);
+		self.inner.touch(input)
+	}
+
+	fn begin(&mut self, input: &Self::Input) {
+		self.stack.push((input.clone(), Vec::new()));
+		self.inner.begin(input)
+	}
+}
+
+impl<C> ContainerLen for Deps<C>
+where
+	C: ContainerLen + SparseContainer,
+	C::Input: Eq + Hash + Clone,
+{
+	fn len(&self) -> usize {
+		self.inner.len()
+	}
+}
+
+impl<C> ContainerClear for Deps<C>
+where
+	C: ContainerClear + SparseContainer,
+	C::Input: Eq + Hash + Clone,
+{
+	fn clear(&mut self) {
+		self.deps.clear();
+		self.inner.clear()
+	}
+}
+
+impl<C> ContainerRemove for Deps<C>
+where
+	C: ContainerRemove,
+	C::Input: Eq + Hash + Clone,
+{
+	fn remove(&mut self, input: &Self::Input) -> Option<Self::Output> {
+		self.deps.remove(input);
+		self.inner.remove(input)
+	}
+}
+
+impl<C> Deps<C>
+where
+	C: ContainerRemove,
+	C::Input: Eq + Hash + Clone,
+{
+	/// Removes the value for `input`, and every value which depended on it, directly or
+	/// indirectly, returning the number of values removed.
+	///
+	/// Finding the dependents takes time proportional to the number of dependencies recorded in
+	/// the whole container.
+	pub fn invalidate(&mut self, input: &C::Input) -> usize {
+		let mut dependents: HashMap<&C::Input, Vec<&C::Input>> = HashMap::new();
+
+		for (i, deps) in &self.deps {
+			for d in deps {
+				dependents.entry(d).or_default().push(i);
+			}
+		}
+
+		let mut invalid = HashSet::from([input]);
+		let mut stack = vec![input];
+
+		while let Some(i) = stack.pop() {
+			for &d in dependents.get(i).into_iter().flatten() {
+				if invalid.insert(d) {
+					stack.push(d);
+				}
+			}
+		}
+
+		let invalid: Vec<_> = invalid.into_iter().cloned().collect();
+
+		invalid.iter().filter(|i| self.remove(i).is_some()).count()
+	}
+}
+
+impl<C> ContainerIter for Deps<C>
+where
+	C: ContainerIter,
+	C::Input: Eq + Hash + Clone,
+{
+	fn iter(&self) -> impl Iterator<Item = (&Self::Input, &Self::Output)> {
+		self.inner.iter()
+	}
+}
+
+impl<'f, C> GenericCache<'f, Deps<C>>
+where
+	C: SparseContainer,
+	C::Input: Eq + Hash + Clone,
+{
+	/// Returns the inputs read while computing the value for `input`, or `None` if it is not in
+	/// the cache.
+	///
+	/// See [`Deps::dependencies`].
+	pub fn dependencies(&self, input: &C::Input) -> Option<&[C::Input]> {
+		self.cache.dependencies(input)
+	}
+
+	/// Returns `input` and every input it depends on, directly or indirectly.
+	///
+	/// See [`Deps::dependency_closure`].
+	pub fn dependency_closure<'a>(&'a self, input: &'a C::Input) -> HashSet<&'a C::Input> {
+		self.cache.dependency_closure(input)
+	}
+
+	/// Computes the values for `a` and `b` if needed, then returns how many subproblems the two
+	/// computations have in common, counting each input along with everything it depends on.
+	///
+	/// This helps to decide whether two queries benefit from sharing a cache, since a shared
+	/// cache only avoids computing the values they have in common.
+	///
+	/// ```
+	/// # use fn_cache::{DepsCache, FnCache};
+	/// let mut cache = DepsCache::<usize, u64>::recursive(|cache, x| match x {
+	///     0 | 1 => *x as u64,
+	///     _ => *cache.get(x - 1) + *cache.get(x - 2),
+	/// });
+	///
+	/// // 0 through 4 are needed by both
+	/// assert_eq!(cache.shared_subproblems(5, 4), 5);
+	/// ```
+	pub fn shared_subproblems(&mut self, a: C::Input, b: C::Input) -> usize {
+		self.get(a.clone());
+		self.get(b.clone());
+
+		let a = self.dependency_closure(&a);
+		let b = self.dependency_closure(&b);
+
+		a.intersection(&b).count()
+	}
+
+	/// Retrieve a value from the cache, computing it if needed, along with the input and value
+	/// of each of its immediate dependencies.
+	///
+	/// Dependencies which have since been removed from the cache are left out.
+	///
+	/// ```
+	/// # use fn_cache::{DepsCache, FnCache};
+	/// let mut cache = DepsCache::<usize, u64>::recursive(|cache, x| match x {
+	///     0 | 1 => *x as u64,
+	///     _ => *cache.get(x - 1) + *cache.get(x - 2),
+	/// });
+	///
+	/// let (value, deps) = cache.get_with_deps(6);
+	///
+	/// assert_eq!(value, &8);
+	/// assert_eq!(deps, [(5, &5), (4, &3)]);
+	/// ```
+	#[allow(clippy::type_complexity)]
+	pub fn get_with_deps(&mut self, input: C::Input) -> (&C::Output, Vec<(C::Input, &C::Output)>) {
+		self.get(input.clone());
+
+		let cache = &self.cache;
+		let deps = cache
+			.dependencies(&input)
+			.unwrap_or_default()
+			.iter()
+			.filter_map(|d| Some((d.clone(), cache.get(d)?)))
+			.collect();
+
+		(cache.get(&input).unwrap(), deps)
+	}
+}
+
+impl<'f, C> GenericCache<'f, Deps<C>>
+where
+	C: ContainerRemove,
+	C::Input: Eq + Hash + Clone,
+{
+	/// Removes the value for `input`, and every value which depended on it, directly or
+	/// indirectly, returning the number of values removed.
+	///
+	/// This is useful when something the function depends on has changed for `input`, so that
+	/// the next call to [`FnCache::get`] computes every affected value again.
+	///
+	/// ```
+	/// # use fn_cache::{DepsCache, FnCache};
+	/// let mut cache = DepsCache::<usize, u64>::recursive(|cache, x| match x {
+	///     0 | 1 => *x as u64,
+	///     _ => *cache.get(x - 1) + *cache.get(x - 2),
+	/// });
+	///
+	/// cache.get(5);
+	///
+	/// assert_eq!(cache.invalidate(&3), 3);
+	/// assert_eq!(cache.len(), 3);
+	/// ```
+	///
+	/// See [`Deps::invalidate`].
+	pub fn invalidate(&mut self, input: &C::Input) -> usize {
+		self.cache.invalidate(input)
+	}
+}
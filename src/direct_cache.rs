@@ -0,0 +1,114 @@
+use crate::container::{
+	ContainerCapacity, ContainerClear, ContainerLen, ContainerRemove, SparseContainer,
+};
+use crate::generic_cache::GenericCache;
+
+/// A cache for a function of small `usize` inputs, in the range `0..N`, which stores each value
+/// directly at the index of its input.
+///
+/// Unlike a [`VecCache`](crate::VecCache), values do not need to be computed in order, and
+/// unlike a [`HashCache`](crate::HashCache), no hashing is done. Every input outside the range
+/// will cause a panic.
+///
+/// ```
+/// # use fn_cache::{DirectCache, FnCache};
+/// let mut cache = DirectCache::<256, u8>::new(|x| x.count_ones() as u8);
+///
+/// assert_eq!(cache.get(255), &8);
+/// assert_eq!(cache.len(), 1);
+/// ```
+pub type DirectCache<'f, const N: usize, O> = GenericCache<'f, Direct<N, O>>;
+
+/// A [`SparseContainer`] backed by an array of `N` optional values, indexed directly by the input.
+pub struct Direct<const N: usize, O> {
+	values: [Option<O>; N],
+	len: usize,
+}
+
+impl<const N: usize, O> Direct<N, O> {
+	/// Create an empty container for the inputs `0..N`.
+	pub fn new() -> Self {
+		Self {
+			values: std::array::from_fn(|_| None),
+			len: 0,
+		}
+	}
+
+	fn slot(&self, input: usize) -> &Option<O> {
+		self.values
+			.get(input)
+			.unwrap_or_else(|| out_of_range::<N>(input))
+	}
+
+	fn slot_mut(&mut self, input: usize) -> &mut Option<O> {
+		self.values
+			.get_mut(input)
+			.unwrap_or_else(|| out_of_range::<N>(input))
+	}
+}
+
+fn out_of_range<const N: usize>(input: usize) -> ! {
+	panic!("input {input} is out of range for a Direct container of size {N}")
+}
+
+impl<const N: usize, O> Default for Direct<N, O> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<const N: usize, O> SparseContainer for Direct<N, O> {
+	type Input = usize;
+	type Output = O;
+
+	fn has(&self, input: &usize) -> bool {
+		self.slot(*input).is_some()
+	}
+
+	fn get(&self, input: &usize) -> Option<&O> {
+		self.slot(*input).as_ref()
+	}
+
+	fn put(&mut self, input: usize, output: O) -> &O {
+		let slot = self.slot_mut(input);
+		let added = slot.is_none();
+
+		if added {
+			*slot = Some(output);
+			self.len += 1;
+		}
+
+		self.values[input].as_ref().unwrap()
+	}
+}
+
+impl<const N: usize, O> ContainerCapacity for Direct<N, O> {
+	fn capacity(&self) -> usize {
+		N
+	}
+}
+
+impl<const N: usize, O> ContainerLen for Direct<N, O> {
+	fn len(&self) -> usize {
+		self.len
+	}
+}
+
+impl<const N: usize, O> ContainerClear for Direct<N, O> {
+	fn clear(&mut self) {
+		self.values.iter_mut().for_each(|v| *v = None);
+		self.len = 0;
+	}
+}
+
+impl<const N: usize, O> ContainerRemove for Direct<N, O> {
+	fn remove(&mut self, input: &usize) -> Option<O> {
+		let removed = self.slot_mut(*input).take();
+
+		if removed.is_some() {
+			self.len -= 1;
+		}
+
+		removed
+	}
+}
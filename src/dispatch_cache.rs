@@ -0,0 +1,104 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use core::hash::Hash;
+
+/// The error returned when a [`DispatchCache`] has no function registered for a tag.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UnknownTag;
+
+impl fmt::Display for UnknownTag {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str("no function is registered for the tag")
+	}
+}
+
+impl std::error::Error for UnknownTag {}
+
+/// A function registered for a tag in a [`DispatchCache`].
+pub type DispatchFn<'f, I, O> = Box<dyn Fn(&I) -> O + Send + 'f>;
+
+/// A cache for several related functions, where each input is tagged with the function it is
+/// for.
+///
+/// Inputs are `(tag, input)` pairs, and on a miss, the function registered for the tag is called
+/// with the input. Values for the same input under different tags are cached separately.
+///
+/// ```
+/// # use fn_cache::dispatch_cache::{DispatchCache, DispatchFn, UnknownTag};
+/// # use std::collections::HashMap;
+/// let mut cache = DispatchCache::new(HashMap::from([
+///     ("double", Box::new(|x: &u32| x * 2) as DispatchFn<_, _>),
+///     ("half", Box::new(|x: &u32| x / 2)),
+/// ]));
+///
+/// assert_eq!(cache.try_get(("double", 10)), Ok(&20));
+/// assert_eq!(cache.try_get(("half", 10)), Ok(&5));
+/// assert_eq!(cache.try_get(("triple", 10)), Err(UnknownTag));
+/// ```
+pub struct DispatchCache<'f, T, I, O> {
+	cache: HashMap<(T, I), O>,
+	table: HashMap<T, DispatchFn<'f, I, O>>,
+}
+
+impl<'f, T, I, O> DispatchCache<'f, T, I, O>
+where
+	T: Eq + Hash,
+	I: Eq + Hash,
+{
+	/// Create a cache which routes each input to the function registered for its tag in `table`.
+	pub fn new(table: HashMap<T, DispatchFn<'f, I, O>>) -> Self {
+		Self {
+			cache: HashMap::new(),
+			table,
+		}
+	}
+
+	/// Register `f` as the function for `tag`, returning the function it replaces, if any.
+	///
+	/// Values already cached for `tag` are kept.
+	pub fn register(
+		&mut self,
+		tag: T,
+		f: impl Fn(&I) -> O + Send + 'f,
+	) -> Option<DispatchFn<'f, I, O>> {
+		self.table.insert(tag, Box::new(f))
+	}
+
+	/// Get a reference to the underlying cache.
+	pub fn cache(&self) -> &HashMap<(T, I), O> {
+		&self.cache
+	}
+
+	/// Retrieve a value stored in the cache, computing it using the function for its tag if it
+	/// does not yet exist.
+	///
+	/// Returns [`UnknownTag`] if the value is not cached and no function is registered for the
+	/// tag.
+	pub fn try_get(&mut self, key: (T, I)) -> Result<&O, UnknownTag> {
+		if self.cache.contains_key(&key) {
+			return Ok(self.cache.get(&key).unwrap());
+		}
+
+		let f = self.table.get(&key.0).ok_or(UnknownTag)?;
+		let output = f(&key.1);
+
+		Ok(self.cache.entry(key).or_insert(output))
+	}
+
+	/// Returns the number of elements in the cache.
+	pub fn len(&self) -> usize {
+		self.cache.len()
+	}
+
+	/// Returns true if the cache holds no elements.
+	pub fn is_empty(&self) -> bool {
+		self.cache.is_empty()
+	}
+
+	/// Clears the cache, removing all key-value pairs.
+	/// Keeps the allocated memory for reuse.
+	pub fn clear(&mut self) {
+		self.cache.clear()
+	}
+}
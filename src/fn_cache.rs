@@ -10,6 +10,119 @@ pub trait FnCache<I, O> {
 	/// function is called, and the result is added
 	/// to the cache before returning it.
 	fn get(&mut self, input: I) -> &O;
+
+	/// Returns true if the cache is dense, meaning it stores every value from the first input
+	/// up to the last one computed, such as a [`VecCache`](crate::VecCache). Otherwise the cache
+	/// is sparse, and stores values for arbitrary inputs, such as a
+	/// [`HashCache`](crate::HashCache).
+	///
+	/// Generic code can use this to choose an access pattern. Filling a dense cache forward in
+	/// order is cheap, while a sparse cache is better suited to random access.
+	fn is_dense(&self) -> bool {
+		false
+	}
+
+	/// Convert the cache into a function returning owned values, for use with APIs which expect
+	/// an [`FnMut`].
+	///
+	/// The returned function takes ownership of the cache, and clones each output out of it. As
+	/// a result, it is `'static` whenever the cache is.
+	///
+	/// ```
+	/// # use fn_cache::{BTreeCache, FnCache};
+	/// let cache = BTreeCache::<usize, usize>::new(|x| x * x);
+	///
+	/// let squares: Vec<_> = [1, 2, 3, 2].into_iter().map(cache.into_fn()).collect();
+	///
+	/// assert_eq!(squares, [1, 4, 9, 4]);
+	/// ```
+	fn into_fn(mut self) -> impl FnMut(I) -> O
+	where
+		Self: Sized,
+		O: Clone,
+	{
+		move |input| self.get(input).clone()
+	}
+
+	/// Borrow the cache as an adapter which applies `f` to each value it retrieves, returning
+	/// the result by value.
+	///
+	/// Only the original values are stored in the cache, and `f` is called again on every call to
+	/// [`MapOutput::get`], so it should be cheap compared to the cached function.
+	///
+	/// ```
+	/// # use fn_cache::{BTreeCache, FnCache};
+	/// let mut cache = BTreeCache::<usize, Vec<usize>>::new(|x| (0..*x).collect());
+	///
+	/// assert_eq!(cache.map_output(|v| v.len()).get(4), 4);
+	/// assert_eq!(cache.map_output(|v| v.iter().sum::<usize>()).get(4), 6);
+	/// assert_eq!(cache.len(), 1);
+	/// ```
+	fn map_output<T, F>(&mut self, f: F) -> MapOutput<'_, Self, F>
+	where
+		Self: Sized,
+		F: Fn(&O) -> T,
+	{
+		MapOutput { cache: self, f }
+	}
+}
+
+/// An adapter over a cache which applies a function to each value it retrieves.
+///
+/// This is created by [`FnCache::map_output`].
+pub struct MapOutput<'c, C, F> {
+	cache: &'c mut C,
+	f: F,
+}
+
+impl<'c, C, F> MapOutput<'c, C, F> {
+	/// Retrieve a value from the cache, computing it if it does not yet exist, and return the
+	/// result of applying the mapping function to it.
+	pub fn get<I, O, T>(&mut self, input: I) -> T
+	where
+		C: FnCache<I, O>,
+		F: Fn(&O) -> T,
+	{
+		(self.f)(self.cache.get(input))
+	}
+
+	/// Returns true if the value for `input` is already stored in the cache, without computing
+	/// it.
+	pub fn contains<I, O>(&self, input: &I) -> bool
+	where
+		C: FnCachePeek<I, O>,
+	{
+		self.cache.contains(input)
+	}
+}
+
+/// The generic trait for caches which can look up a value without computing it.
+///
+/// This is useful for inspecting a cache, such as while debugging, when computing a missing
+/// value would be too expensive.
+pub trait FnCachePeek<I, O>: FnCache<I, O> {
+	/// Returns the value stored in the cache for `input`, or `None` if it has not been
+	/// computed. The function is never called.
+	///
+	/// ```
+	/// # use fn_cache::{FnCache, FnCachePeek, HashCache};
+	/// let mut cache = HashCache::<u64, u64>::new(|x| x * x);
+	///
+	/// assert_eq!(cache.peek(&3), None);
+	///
+	/// cache.get(3);
+	///
+	/// assert_eq!(cache.peek(&3), Some(&9));
+	/// ```
+	fn peek(&self, input: &I) -> Option<&O>;
+
+	/// Returns true if the value for `input` is already stored in the cache, without computing
+	/// it.
+	///
+	/// By default, this checks whether [`Self::peek`] finds a value.
+	fn contains(&self, input: &I) -> bool {
+		self.peek(input).is_some()
+	}
 }
 
 /// The generic trait for caches which support getting multiple
@@ -30,4 +143,47 @@ pub trait FnCacheMany<I, O>: FnCache<I, O> {
 	/// recursive definition that uses multiple values
 	/// at once.
 	fn get_many<const N: usize>(&mut self, inputs: [I; N]) -> [&O; N];
+
+	/// Retrieve multiple values stored in the cache, for a number of inputs only known at
+	/// runtime.
+	///
+	/// As with [`Self::get_many`], every missing value is computed before any reference is
+	/// returned. By default, each input is retrieved in order, and then looked up again using
+	/// [`FnCachePeek::peek`], since references cannot be held while retrieving the rest, so this
+	/// is only available for caches which can peek.
+	fn get_many_vec(&mut self, inputs: &[I]) -> Vec<&O>
+	where
+		I: Clone,
+		Self: FnCachePeek<I, O>,
+	{
+		for i in inputs {
+			self.get(i.clone());
+		}
+
+		inputs.iter().map(|i| self.peek(i).unwrap()).collect()
+	}
+
+	/// Retrieve two values stored in the cache as a tuple.
+	///
+	/// This is a shorthand for [`Self::get_many`] with two inputs,
+	/// which is the most common case for recursive definitions.
+	/// If `a` and `b` are equal, both references point to the same
+	/// entry.
+	fn get_two(&mut self, a: I, b: I) -> (&O, &O) {
+		let [a, b] = self.get_many([a, b]);
+		(a, b)
+	}
+}
+
+/// The generic trait for caches of fallible functions.
+///
+/// Only successful values are stored, so an input which failed is computed again the next time
+/// it is requested. This allows someone to write a function like
+/// `fn f(cache: &mut impl TryFnCache<u32, u32, E>, x: &u32) -> Result<u32, E>` and have it work
+/// for all the fallible caches written in this crate.
+pub trait TryFnCache<I, O, E> {
+	/// Retrieve a value stored in the cache. If the value does not yet exist in the cache, the
+	/// function is called, and the result is added to the cache before returning it, unless the
+	/// function returns an error.
+	fn try_get(&mut self, input: I) -> Result<&O, E>;
 }
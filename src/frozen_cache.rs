@@ -0,0 +1,61 @@
+use crate::container::{ContainerLen, SparseContainer};
+
+/// A read-only cache, which can no longer compute values.
+///
+/// Since nothing can be computed, every method takes a shared reference, so a frozen cache can be
+/// shared between threads, such as in an [`Arc`](std::sync::Arc), and read without any locking.
+/// It is created by [`GenericCache::freeze`](crate::GenericCache::freeze), or by
+/// [`GenericCache::warm_and_share`](crate::GenericCache::warm_and_share) after computing the
+/// values which will be needed.
+pub struct FrozenCache<C> {
+	cache: C,
+}
+
+impl<C: SparseContainer> FrozenCache<C> {
+	/// Freeze a container, so its values can only be read.
+	pub fn new(cache: C) -> Self {
+		Self { cache }
+	}
+
+	/// Returns the value stored for `input`, or `None` if it was not computed before freezing.
+	pub fn peek(&self, input: &C::Input) -> Option<&C::Output> {
+		self.cache.get(input)
+	}
+
+	/// Returns the value stored for `input`.
+	///
+	/// # Panics
+	/// If the value was not computed before freezing, since it can no longer be computed. Use
+	/// [`Self::peek`] if the value may be missing.
+	pub fn get(&self, input: &C::Input) -> &C::Output {
+		self.peek(input)
+			.expect("FrozenCache value was not computed before freezing")
+	}
+
+	/// Returns true if the value for `input` was computed before freezing.
+	pub fn contains(&self, input: &C::Input) -> bool {
+		self.cache.has(input)
+	}
+
+	/// Get a reference to the underlying cache object.
+	pub fn cache(&self) -> &C {
+		&self.cache
+	}
+
+	/// Unwrap the underlying cache object.
+	pub fn into_inner(self) -> C {
+		self.cache
+	}
+}
+
+impl<C: SparseContainer + ContainerLen> FrozenCache<C> {
+	/// Returns the number of elements in the cache.
+	pub fn len(&self) -> usize {
+		self.cache.len()
+	}
+
+	/// Returns true if the cache holds no elements.
+	pub fn is_empty(&self) -> bool {
+		self.cache.is_empty()
+	}
+}
@@ -0,0 +1,111 @@
+use std::fmt;
+
+use crate::container::SparseContainer;
+use crate::try_cache::{Allowance, TryCache, TryRefCache};
+
+/// The error returned when a [`FuelCache`] runs out of fuel.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OutOfFuel;
+
+impl fmt::Display for OutOfFuel {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str("cache computation exceeded its step budget")
+	}
+}
+
+impl std::error::Error for OutOfFuel {}
+
+/// An [`Allowance`] of function calls for each call to [`TryCache::try_get`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Fuel(pub u64);
+
+impl<C, E: From<OutOfFuel>> Allowance<C, E> for Fuel {
+	type State = u64;
+
+	fn start(&self) -> u64 {
+		self.0
+	}
+
+	fn check(fuel: &mut u64, _: &C) -> Result<(), E> {
+		if *fuel == 0 {
+			Err(OutOfFuel.into())
+		} else {
+			*fuel -= 1;
+			Ok(())
+		}
+	}
+}
+
+/// A cache for a recursive function which gives up once it has called the function a fixed
+/// number of times.
+///
+/// This works like a [`DeadlineCache`](crate::deadline_cache::DeadlineCache), except the budget
+/// is counted in steps rather than time, so whether a computation finishes is deterministic. Each
+/// call to [`TryCache::try_get`] starts with the configured amount of fuel, and every call of the
+/// function, including recursive calls made through the [`FuelRefCache`], uses one unit. Once the
+/// fuel is spent, any value not already in the cache returns [`OutOfFuel`] instead of being
+/// computed, which the function propagates using `?`.
+///
+/// A value whose computation ran out of fuel is never cached, but values which were completely
+/// computed before the fuel ran out remain in the cache.
+///
+/// ```
+/// # use fn_cache::fuel_cache::{FuelCache, OutOfFuel};
+/// # use std::collections::HashMap;
+/// let mut cache = FuelCache::<HashMap<u64, u64>>::recursive(11, |cache, x| match x {
+///     0 => Ok(0),
+///     1 => Ok(1),
+///     _ => Ok(*cache.try_get(x - 1)? + *cache.try_get(x - 2)?),
+/// });
+///
+/// assert_eq!(cache.try_get(10), Ok(&55));
+/// assert_eq!(cache.try_get(100), Err(OutOfFuel));
+/// ```
+pub type FuelCache<'f, C, E = OutOfFuel> = TryCache<'f, C, E, Fuel>;
+
+/// The cache passed to the function of a [`FuelCache`], sharing the fuel of the top-level call.
+pub type FuelRefCache<'c, C, E = OutOfFuel> = TryRefCache<'c, C, E, Fuel>;
+
+impl<'f, C: SparseContainer, E: From<OutOfFuel>> FuelCache<'f, C, E> {
+	/// Create a `FuelCache` out of a cache, the number of function calls allowed for each call to
+	/// [`TryCache::try_get`], and a recursive function.
+	pub fn recursive_with_cache(
+		cache: C,
+		fuel: u64,
+		f: impl Fn(&mut FuelRefCache<C, E>, &C::Input) -> Result<C::Output, E> + Send + 'f,
+	) -> Self {
+		Self::try_recursive_with_allowance(cache, Fuel(fuel), f)
+	}
+
+	/// Returns the number of function calls allowed for each call to [`TryCache::try_get`].
+	pub fn fuel(&self) -> u64 {
+		self.allowance().0
+	}
+
+	/// Sets the number of function calls allowed for each call to [`TryCache::try_get`].
+	pub fn set_fuel(&mut self, fuel: u64) {
+		self.allowance_mut().0 = fuel;
+	}
+}
+
+impl<'f, C, E> FuelCache<'f, C, E>
+where
+	C: SparseContainer + Default,
+	E: From<OutOfFuel>,
+{
+	/// Create a `FuelCache` using the `Default` implementation of the container, the number of
+	/// function calls allowed for each call to [`TryCache::try_get`], and a recursive function.
+	pub fn recursive(
+		fuel: u64,
+		f: impl Fn(&mut FuelRefCache<C, E>, &C::Input) -> Result<C::Output, E> + Send + 'f,
+	) -> Self {
+		Self::recursive_with_cache(Default::default(), fuel, f)
+	}
+}
+
+impl<'c, C: SparseContainer, E: From<OutOfFuel>> FuelRefCache<'c, C, E> {
+	/// Returns the number of function calls which can still be made.
+	pub fn fuel(&self) -> u64 {
+		self.state
+	}
+}
@@ -1,7 +1,29 @@
 use crate::container::{
-	ContainerClear, ContainerLen, ContainerRemove, ContainerReserve, SparseContainer,
+	ContainerCapacity, ContainerClear, ContainerDrain, ContainerEvict, ContainerIter, ContainerLen,
+	ContainerRemove, ContainerReserve, ContainerShrink, SparseContainer,
 };
-use crate::{FnCache, FnCacheMany};
+use crate::frozen_cache::FrozenCache;
+use crate::{FnCache, FnCacheMany, FnCachePeek, TryFnCache};
+
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use core::fmt;
+use core::hash::Hash;
+
+/// The error returned when a [`GenericCache`] needs to compute a value before its function is
+/// set, such as one made by [`GenericCache::uninitialized`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Uninitialized;
+
+impl fmt::Display for Uninitialized {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str("cache function was used before it was set")
+	}
+}
+
+impl std::error::Error for Uninitialized {}
 
 /// A generic cache for a function backed by anything that implements the [`SparseContainer`]
 /// trait.
@@ -13,12 +35,47 @@ use crate::{FnCache, FnCacheMany};
 /// The cache takes ownership of all inputs, but only passes a reference to the function, allowing
 /// it to store the input in the cache without any copies or clones. Additionally, the function is
 /// shared using by using a [`RefCache`] when actually calling the function, preventing any
-/// reference counting or clones of the closure.
+/// reference counting or clones of the closure while computing.
+///
+/// The function is stored in an [`Arc`], so that caches built from one another, such as those
+/// returned by [`Self::fork_empty`] or [`Self::partition_by_access`], can share it without
+/// cloning it. As a result, it must be both [`Send`] and [`Sync`], and the caches sharing it may
+/// call it from several threads at once.
 pub struct GenericCache<'f, C: SparseContainer> {
 	pub(crate) cache: C,
-	f: Box<dyn Fn(&mut RefCache<C>, &C::Input) -> C::Output + Send + 'f>,
+	/// The function of the cache, or `None` until it is set.
+	pub(crate) f: Option<SharedFn<'f, C>>,
+	/// The function without a [`RefCache`], if the cache is not recursive, which can be called
+	/// from several threads at once.
+	#[cfg(feature = "rayon")]
+	pub(crate) simple: Option<SimpleFn<'f, C>>,
 }
 
+/// The function of a cache, which may be shared with the caches built from it, and called by each
+/// of them at the same time.
+pub(crate) type SharedFn<'f, C> = Arc<
+	dyn Fn(&mut RefCache<C>, &<C as SparseContainer>::Input) -> <C as SparseContainer>::Output
+		+ Send
+		+ Sync
+		+ 'f,
+>;
+
+/// The function of a cache, which has been set.
+///
+/// # Panics
+/// If the function has not been set yet.
+pub(crate) fn expect_fn<'a, 'f, C: SparseContainer>(
+	f: &'a Option<SharedFn<'f, C>>,
+) -> &'a SharedFn<'f, C> {
+	f.as_ref()
+		.expect("GenericCache function was used before it was set")
+}
+
+#[cfg(feature = "rayon")]
+pub(crate) type SimpleFn<'f, C> = Arc<
+	dyn Fn(&<C as SparseContainer>::Input) -> <C as SparseContainer>::Output + Send + Sync + 'f,
+>;
+
 impl<'f, C: SparseContainer> GenericCache<'f, C> {
 	/// Create a `GenericCache` out of a cache and a function.
 	///
@@ -26,16 +83,18 @@ impl<'f, C: SparseContainer> GenericCache<'f, C> {
 	/// settings using specific constructors on the cache type, or any variation. If a default
 	/// version of the cache is sufficient for your needs, [`Self::new`] may be less verbose.
 	///
+	/// The function must be [`Send`] and [`Sync`], since it is kept in an [`Arc`] which is shared
+	/// with the caches built from this one, such as by [`Self::fork_empty`].
+	///
 	/// ```
 	/// # use fn_cache::GenericCache;
 	/// # use std::collections::HashMap;
 	/// let cache = GenericCache::with_cache(HashMap::<usize, usize>::new(), |x: &usize| *x);
 	/// ```
-	pub fn with_cache(cache: C, f: impl Fn(&C::Input) -> C::Output + Send + 'f) -> Self {
-		Self {
-			cache,
-			f: Box::new(move |_, i| f(i)),
-		}
+	pub fn with_cache(cache: C, f: impl Fn(&C::Input) -> C::Output + Send + Sync + 'f) -> Self {
+		let mut cache = Self::uninitialized_with_cache(cache);
+		cache.set_fn(f);
+		cache
 	}
 
 	/// Create a `GenericCache` out of a cache and a recursive function.
@@ -44,6 +103,8 @@ impl<'f, C: SparseContainer> GenericCache<'f, C> {
 	/// settings using specific constructors on the cache type, or any variation. If a default
 	/// version of the cache is sufficient for your needs, [`Self::recursive`] may be less verbose.
 	///
+	/// As with [`Self::with_cache`], the function must be [`Send`] and [`Sync`].
+	///
 	/// ```
 	/// # use fn_cache::{FnCacheMany, GenericCache};
 	/// # use std::collections::HashMap;
@@ -55,25 +116,490 @@ impl<'f, C: SparseContainer> GenericCache<'f, C> {
 	/// ```
 	pub fn recursive_with_cache(
 		cache: C,
-		f: impl Fn(&mut RefCache<C>, &C::Input) -> C::Output + Send + 'f,
+		f: impl Fn(&mut RefCache<C>, &C::Input) -> C::Output + Send + Sync + 'f,
 	) -> Self {
+		let mut cache = Self::uninitialized_with_cache(cache);
+		cache.set_recursive_fn(f);
+		cache
+	}
+
+	/// Create a `GenericCache` out of a cache, without a function yet.
+	///
+	/// This allows the cache to be created before the function is known, such as when wiring
+	/// together a graph of objects. The function must be set using [`Self::set_fn`] or
+	/// [`Self::set_recursive_fn`] before any value is computed. Until then,
+	/// [`TryFnCache::try_get`] returns [`Uninitialized`] for a value which is not cached, while
+	/// [`FnCache::get`] panics.
+	///
+	/// If a default version of the cache is sufficient for your needs, [`Self::uninitialized`]
+	/// may be less verbose.
+	pub fn uninitialized_with_cache(cache: C) -> Self {
+		Self {
+			cache,
+			f: None,
+			#[cfg(feature = "rayon")]
+			simple: None,
+		}
+	}
+
+	/// Create a `GenericCache` out of a cache and a recursive function, which is also passed the
+	/// containers of its sibling caches.
+	///
+	/// If a default version of the cache is sufficient for your needs,
+	/// [`Self::recursive_with_siblings`] may be less verbose.
+	pub fn recursive_with_cache_and_siblings(
+		cache: C,
+		siblings: Vec<&'f C>,
+		f: impl Fn(&mut RefCache<C>, &[&C], &C::Input) -> C::Output + Send + Sync + 'f,
+	) -> Self
+	where
+		C: Sync,
+	{
+		Self::recursive_with_cache(cache, move |cache, input| f(cache, &siblings, input))
+	}
+
+	/// Replace the function of the cache, keeping every value already cached.
+	///
+	/// Values computed by the old function are not recomputed, see [`Self::recompute_all`] to do
+	/// so.
+	pub fn set_fn(&mut self, f: impl Fn(&C::Input) -> C::Output + Send + Sync + 'f) {
+		let f = Arc::new(f);
+
+		#[cfg(feature = "rayon")]
+		{
+			self.simple = Some(f.clone());
+		}
+
+		self.f = Some(Arc::new(move |_, i| f(i)));
+	}
+
+	/// Replace the function of the cache with a recursive function, keeping every value already
+	/// cached.
+	///
+	/// Values computed by the old function are not recomputed, see [`Self::recompute_all`] to do
+	/// so.
+	pub fn set_recursive_fn(
+		&mut self,
+		f: impl Fn(&mut RefCache<C>, &C::Input) -> C::Output + Send + Sync + 'f,
+	) {
+		self.f = Some(Arc::new(f));
+
+		#[cfg(feature = "rayon")]
+		{
+			self.simple = None;
+		}
+	}
+
+	/// Create a `GenericCache` out of a cache, sharing the function of this one.
+	pub(crate) fn share_fn(&self, cache: C) -> Self {
 		Self {
 			cache,
-			f: Box::new(f),
+			f: self.f.clone(),
+			#[cfg(feature = "rayon")]
+			simple: self.simple.clone(),
 		}
 	}
 
+	/// Create a `GenericCache` out of a cache, a function to load values on a miss, and a function
+	/// to store newly loaded values elsewhere.
+	///
+	/// If a default version of the cache is sufficient for your needs, [`Self::write_through`]
+	/// may be less verbose.
+	pub fn write_through_with_cache(
+		cache: C,
+		load: impl Fn(&C::Input) -> C::Output + Send + Sync + 'f,
+		store: impl Fn(&C::Input, &C::Output) + Send + Sync + 'f,
+	) -> Self {
+		Self::with_cache(cache, move |i| {
+			let o = load(i);
+			store(i, &o);
+			o
+		})
+	}
+
+	/// Create a `GenericCache` out of a cache and a recursive function, which fills dependencies
+	/// iteratively rather than recursing.
+	///
+	/// If a default version of the cache is sufficient for your needs,
+	/// [`Self::recursive_iterative`] may be less verbose.
+	pub fn recursive_iterative_with_cache(
+		cache: C,
+		deps: impl Fn(&C::Input) -> Vec<C::Input> + Send + Sync + 'f,
+		f: impl Fn(&mut RefCache<C>, &C::Input) -> C::Output + Send + Sync + 'f,
+	) -> Self
+	where
+		C::Input: Clone + Eq + Hash,
+	{
+		Self::recursive_with_cache(cache, move |cache, input| {
+			// each input on the stack, and whether its dependencies have already been pushed
+			let mut stack: Vec<_> = deps(input).into_iter().map(|d| (d, false)).collect();
+			// the inputs waiting on their dependencies, which none of them may depend on
+			let mut pending = HashSet::from([input.clone()]);
+
+			while let Some((top, expanded)) = stack.last() {
+				if cache.cache.has(top) {
+					stack.pop();
+					continue;
+				}
+
+				if !expanded {
+					let missing: Vec<_> = deps(top)
+						.into_iter()
+						.filter(|d| !cache.cache.has(d))
+						.collect();
+
+					if !missing.is_empty() {
+						pending.insert(top.clone());
+
+						if missing.iter().any(|d| pending.contains(d)) {
+							panic!("GenericCache::recursive_iterative found a cyclic dependency");
+						}
+
+						stack.last_mut().unwrap().1 = true;
+						stack.extend(missing.into_iter().map(|d| (d, false)));
+						continue;
+					}
+				}
+
+				let (top, _) = stack.pop().unwrap();
+				pending.remove(&top);
+				cache.cache.begin(&top);
+				let output = f(cache, &top);
+				cache.cache.put(top, output);
+			}
+
+			f(cache, input)
+		})
+	}
+
+	/// Create a `GenericCache` out of a cache and a recursive function, which also returns inputs
+	/// to prefetch.
+	///
+	/// If a default version of the cache is sufficient for your needs,
+	/// [`Self::recursive_prefetching`] may be less verbose.
+	pub fn recursive_prefetching_with_cache(
+		cache: C,
+		f: impl Fn(&mut RefCache<C>, &C::Input) -> (C::Output, Vec<C::Input>) + Send + Sync + 'f,
+	) -> Self
+	where
+		C::Input: Clone + PartialEq,
+	{
+		Self::recursive_with_cache(cache, move |cache, input| {
+			cache.prefetching.push(input.clone());
+			let (output, hints) = f(cache, input);
+
+			for hint in hints {
+				if !cache.cache.has(&hint) && !cache.prefetching.contains(&hint) {
+					cache.prefetching.push(hint.clone());
+					cache.cache.begin(&hint);
+					let (hint_output, _) = f(cache, &hint);
+					cache.prefetching.pop();
+					cache.cache.put(hint, hint_output);
+				}
+			}
+
+			cache.prefetching.pop();
+			output
+		})
+	}
+
 	/// Get a reference to the underlying cache object, letting you use functions exclusive to the
 	/// cache type (as long they only need `&self` of course).
 	pub fn cache(&self) -> &C {
 		&self.cache
 	}
+
+	/// Replace the underlying cache object, returning the old one.
+	///
+	/// Since this takes `&mut self`, there is no point at which another caller can observe the
+	/// cache without either the old or the new values, making this suitable for swapping in a
+	/// freshly built set of values.
+	///
+	/// ```
+	/// # use fn_cache::{FnCache, HashCache};
+	/// # use std::collections::HashMap;
+	/// let mut cache = HashCache::<usize, usize>::new(|x| *x);
+	///
+	/// cache.get(1);
+	///
+	/// let old = cache.replace_cache(HashMap::from([(1, 10)]));
+	///
+	/// assert_eq!(old, HashMap::from([(1, 1)]));
+	/// assert_eq!(cache.get(1), &10);
+	/// ```
+	pub fn replace_cache(&mut self, cache: C) -> C {
+		core::mem::replace(&mut self.cache, cache)
+	}
+
+	/// Get a [`RefCache`] for this cache, the same as a recursive function is given while
+	/// computing.
+	///
+	/// This allows recursive logic to be factored out into standalone functions which take a
+	/// `&mut RefCache<C>`, and called both from inside the function and from outside the cache.
+	///
+	/// ```
+	/// # use fn_cache::{FnCache, HashCache};
+	/// # use fn_cache::generic_cache::RefCache;
+	/// # use std::collections::HashMap;
+	/// fn sum_to(cache: &mut RefCache<HashMap<u64, u64>>, x: u64) -> u64 {
+	///     (0..=x).map(|i| *cache.get(i)).sum()
+	/// }
+	///
+	/// let mut cache = HashCache::<u64, u64>::new(|x| x * x);
+	///
+	/// assert_eq!(sum_to(&mut cache.as_ref_cache(), 3), 14);
+	/// assert_eq!(cache.len(), 4);
+	/// ```
+	///
+	/// # Panics
+	/// If the function has not been set yet.
+	pub fn as_ref_cache(&mut self) -> RefCache<'_, C> {
+		RefCache::new(&mut self.cache, expect_fn(&self.f).as_ref())
+	}
+
+	/// Retrieve a value from the cache, along with whether it was computed and inserted by this
+	/// call (a miss), or was already present (a hit).
+	///
+	/// ```
+	/// # use fn_cache::HashCache;
+	/// let mut cache = HashCache::<usize, usize>::new(|x| *x);
+	///
+	/// assert_eq!(cache.get_entry(1), (true, &1));
+	/// assert_eq!(cache.get_entry(1), (false, &1));
+	/// ```
+	pub fn get_entry(&mut self, input: C::Input) -> (bool, &C::Output) {
+		if self.cache.has(&input) {
+			self.cache.touch(&input);
+			(false, self.cache.get(&input).unwrap())
+		} else {
+			(true, self.compute(input))
+		}
+	}
+
+	/// Insert a known value for `input` into the cache, without calling the function, unless a
+	/// value for `input` is already cached, and return a reference to the stored value.
+	///
+	/// This allows seeding the cache before any value is computed, such as with base cases or the
+	/// results of a previous run. An existing value is always kept, and `output` is dropped, for
+	/// every container, the same as [`VecCache::insert_if_absent`](crate::VecCache::insert_if_absent).
+	///
+	/// ```
+	/// # use fn_cache::{FnCache, HashCache};
+	/// let mut cache = HashCache::<usize, usize>::new(|_| unreachable!());
+	///
+	/// assert_eq!(cache.insert_if_absent(1, 10), &10);
+	/// assert_eq!(cache.insert_if_absent(1, 20), &10);
+	/// assert_eq!(cache.get(1), &10);
+	/// ```
+	pub fn insert_if_absent(&mut self, input: C::Input, output: C::Output) -> &C::Output {
+		if self.cache.has(&input) {
+			self.cache.get(&input).unwrap()
+		} else {
+			self.cache.put(input, output)
+		}
+	}
+
+	/// Freeze the cache, so its values can only be read, and it can be shared between threads
+	/// without locking.
+	///
+	/// The function is dropped, so no more values can be computed.
+	pub fn freeze(self) -> FrozenCache<C> {
+		FrozenCache::new(self.cache)
+	}
+
+	/// Compute the value for each input, then freeze the cache and wrap it in an [`Arc`], so it
+	/// can be cheaply shared between threads which only read it.
+	///
+	/// ```
+	/// # use fn_cache::HashCache;
+	/// let cache = HashCache::<u64, u64>::new(|x| x * x).warm_and_share(0..10);
+	///
+	/// std::thread::scope(|s| {
+	///     for i in 0..10 {
+	///         let cache = cache.clone();
+	///         s.spawn(move || assert_eq!(cache.get(&i), &(i * i)));
+	///     }
+	/// });
+	/// ```
+	pub fn warm_and_share(
+		mut self,
+		inputs: impl IntoIterator<Item = C::Input>,
+	) -> Arc<FrozenCache<C>> {
+		for input in inputs {
+			self.get(input);
+		}
+
+		Arc::new(self.freeze())
+	}
+
+	/// Retrieve a value from the cache, using `f` to compute it instead of the cache function if
+	/// it is not yet cached.
+	///
+	/// This is useful to supply a known value, or a stub, for a specific input only when it is
+	/// needed.
+	///
+	/// ```
+	/// # use fn_cache::{FnCache, HashCache};
+	/// let mut cache = HashCache::<usize, usize>::new(|x| *x);
+	///
+	/// assert_eq!(cache.get_or_insert_with(1, |x| x + 10), &11);
+	/// assert_eq!(cache.get_or_insert_with(1, |x| x + 20), &11);
+	/// assert_eq!(cache.get(1), &11);
+	/// ```
+	pub fn get_or_insert_with(
+		&mut self,
+		input: C::Input,
+		f: impl FnOnce(&C::Input) -> C::Output,
+	) -> &C::Output {
+		if self.cache.has(&input) {
+			self.cache.touch(&input);
+			self.cache.get(&input).unwrap()
+		} else {
+			self.cache.begin(&input);
+			let output = f(&input);
+			self.cache.put(input, output)
+		}
+	}
+
+	/// Computes the value for each input, stopping early if `token` is set.
+	///
+	/// The token is checked before each input, so a computation which has already started is
+	/// finished, and its value cached. Returns the number of inputs which were processed, whether
+	/// they were computed or already in the cache. Every value computed before cancelling stays
+	/// in the cache.
+	///
+	/// ```
+	/// # use fn_cache::HashCache;
+	/// # use std::sync::atomic::{AtomicBool, Ordering};
+	/// let token = AtomicBool::new(false);
+	///
+	/// let mut cache = HashCache::<usize, usize>::new(|x| {
+	///     if *x == 2 {
+	///         token.store(true, Ordering::Relaxed);
+	///     }
+	///     *x
+	/// });
+	///
+	/// assert_eq!(cache.warm_cancellable(0..10, &token), 3);
+	/// assert_eq!(cache.len(), 3);
+	/// ```
+	pub fn warm_cancellable(
+		&mut self,
+		inputs: impl IntoIterator<Item = C::Input>,
+		token: &AtomicBool,
+	) -> usize {
+		let mut completed = 0;
+
+		for input in inputs {
+			if token.load(Ordering::Relaxed) {
+				break;
+			}
+
+			self.get(input);
+			completed += 1;
+		}
+
+		completed
+	}
+
+	/// Computes the values for the inputs with the highest priority first, until `budget` values
+	/// have been computed.
+	///
+	/// Inputs with equal priority are computed in the order given. An input already in the cache
+	/// does not use any of the budget, and values computed for the dependencies of a recursive
+	/// function are not counted separately. Returns the number of values computed.
+	///
+	/// ```
+	/// # use fn_cache::{FnCachePeek, HashCache};
+	/// let mut cache = HashCache::<&str, usize>::new(|x| x.len());
+	///
+	/// assert_eq!(cache.warm_prioritized(vec![("a", 1), ("bb", 5), ("ccc", 3)], 2), 2);
+	///
+	/// assert!(cache.contains(&"bb"));
+	/// assert!(cache.contains(&"ccc"));
+	/// assert!(!cache.contains(&"a"));
+	/// ```
+	pub fn warm_prioritized(
+		&mut self,
+		mut inputs_with_priority: Vec<(C::Input, u64)>,
+		budget: usize,
+	) -> usize {
+		inputs_with_priority.sort_by_key(|(_, priority)| core::cmp::Reverse(*priority));
+
+		let mut computed = 0;
+
+		for (input, _) in inputs_with_priority {
+			if computed >= budget {
+				break;
+			}
+
+			if !self.cache.has(&input) {
+				self.compute(input);
+				computed += 1;
+			}
+		}
+
+		computed
+	}
+
+	fn compute(&mut self, input: C::Input) -> &C::Output {
+		let f = expect_fn(&self.f).as_ref();
+		self.cache.begin(&input);
+		let mut ref_cache = RefCache::new(&mut self.cache, f);
+		let output = f(&mut ref_cache, &input);
+		self.cache.put(input, output)
+	}
 }
 
 impl<'f, C> GenericCache<'f, C>
 where
 	C: SparseContainer + Default,
 {
+	/// Create a `GenericCache` using the `Default` implementation of the container, without a
+	/// function yet.
+	///
+	/// The function must be set using [`Self::set_fn`] or [`Self::set_recursive_fn`] before any
+	/// value is computed, otherwise [`FnCache::get`] panics and [`TryFnCache::try_get`] returns
+	/// [`Uninitialized`] for a value which is not cached.
+	///
+	/// ```
+	/// # use fn_cache::{FnCache, GenericCache};
+	/// # use std::collections::HashMap;
+	/// let mut cache = GenericCache::<HashMap<usize, usize>>::uninitialized();
+	///
+	/// cache.set_fn(|x| x * 2);
+	///
+	/// assert_eq!(cache.get(3), &6);
+	/// ```
+	pub fn uninitialized() -> Self {
+		Self::uninitialized_with_cache(Default::default())
+	}
+
+	/// Create a new, empty cache using the `Default` implementation of the container, sharing the
+	/// function of this one.
+	///
+	/// This is cheaper than building a new cache from the same function, such as when running
+	/// the same computation over many independent inputs. The function is never cloned: the new
+	/// cache holds the same [`Arc`] as this one, which is why every constructor requires the
+	/// function to be [`Send`] and [`Sync`]. In return, the two caches can compute values at the
+	/// same time, including from different threads.
+	///
+	/// ```
+	/// # use fn_cache::{FnCache, HashCache};
+	/// let mut cache = HashCache::<usize, usize>::new(|x| x * x);
+	///
+	/// cache.get(2);
+	///
+	/// let mut fresh = cache.fork_empty();
+	///
+	/// assert!(fresh.is_empty());
+	/// assert_eq!(fresh.get(3), &9);
+	/// assert_eq!(cache.len(), 1);
+	/// ```
+	pub fn fork_empty(&self) -> Self {
+		self.share_fn(Default::default())
+	}
+
 	/// Create a `GenericCache` using the `Default` implementation of the [`Cache`] type.
 	///
 	/// If a specific instance of a cache is required, see [`Self::with_cache`].
@@ -83,7 +609,7 @@ where
 	/// # use std::collections::HashMap;
 	/// let cache: GenericCache<HashMap<_,_>> = GenericCache::new(|x: &usize| *x);
 	/// ```
-	pub fn new(f: impl Fn(&C::Input) -> C::Output + Send + 'f) -> Self {
+	pub fn new(f: impl Fn(&C::Input) -> C::Output + Send + Sync + 'f) -> Self {
 		Self::with_cache(Default::default(), f)
 	}
 
@@ -106,6 +632,11 @@ where
 	/// Currently it does not work if you pass in a recursive function generic over [`FnCache`] via
 	/// a function pointer. Wrap the pointer in a closure.
 	///
+	/// The function must accept a [`RefCache`] borrowing the container for any lifetime, but a
+	/// generic function can only be instantiated for a single type, which includes a single
+	/// lifetime. A closure can be generic over the lifetime, and instantiates the function
+	/// separately for each call.
+	///
 	/// ```
 	/// # use fn_cache::{FnCache, GenericCache};
 	/// # use std::collections::HashMap;
@@ -116,14 +647,312 @@ where
 	///     }
 	/// }
 	///
-	/// // no good
-	/// //let cache: GenericCache<HashMap<_, _>> = GenericCache::recursive(increment);
-	/// //okay
 	/// let cache: GenericCache<HashMap<_, _>> = GenericCache::recursive(|c, i| increment(c, i));
 	/// ```
-	pub fn recursive(f: impl Fn(&mut RefCache<C>, &C::Input) -> C::Output + Send + 'f) -> Self {
+	///
+	/// Passing the function directly does not compile.
+	///
+	/// ```compile_fail
+	/// # use fn_cache::{FnCache, GenericCache};
+	/// # use std::collections::HashMap;
+	/// # fn increment(cache: &mut impl FnCache<usize, usize>, x: &usize) -> usize {
+	/// #     match x {
+	/// #         0 => 0,
+	/// #         _ => cache.get(x - 1) + 1,
+	/// #     }
+	/// # }
+	/// let cache: GenericCache<HashMap<_, _>> = GenericCache::recursive(increment);
+	/// ```
+	pub fn recursive(
+		f: impl Fn(&mut RefCache<C>, &C::Input) -> C::Output + Send + Sync + 'f,
+	) -> Self {
 		Self::recursive_with_cache(Default::default(), f)
 	}
+
+	/// Create a `GenericCache` using the `Default` implementation of the container, and a
+	/// recursive function which is also passed the containers of its sibling caches.
+	///
+	/// This allows a family of caches which share subproblems, such as one cache per parameter,
+	/// to reuse values another cache has already computed. Siblings can only be read, using
+	/// [`SparseContainer::get`], so looking up a value in a sibling never computes it, or anything
+	/// else. Only values computed by this cache are stored in it; a value found in a sibling must
+	/// be copied out of it to be stored here as well.
+	///
+	/// The siblings are borrowed for as long as the cache lives, so they cannot compute any more
+	/// values until it is dropped. Since the cache can be sent to another thread along with those
+	/// borrows, the containers must be [`Sync`].
+	///
+	/// ```
+	/// # use fn_cache::{BTreeCache, FnCache};
+	/// let mut sibling = BTreeCache::<u64, u64>::new(|x| x * x);
+	/// sibling.get(3);
+	///
+	/// let mut cache = BTreeCache::<u64, u64>::recursive_with_siblings(
+	///     vec![sibling.cache()],
+	///     |_, siblings, x| match siblings.iter().find_map(|s| s.get(x)) {
+	///         Some(o) => *o,
+	///         None => x * x,
+	///     },
+	/// );
+	///
+	/// assert_eq!(cache.get(3), &9);
+	/// ```
+	pub fn recursive_with_siblings(
+		siblings: Vec<&'f C>,
+		f: impl Fn(&mut RefCache<C>, &[&C], &C::Input) -> C::Output + Send + Sync + 'f,
+	) -> Self
+	where
+		C: Sync,
+	{
+		Self::recursive_with_cache_and_siblings(Default::default(), siblings, f)
+	}
+
+	/// Create a `GenericCache` using the `Default` implementation of the [`SparseContainer`]
+	/// type, which loads values using `load` on a miss, and passes each newly loaded value to
+	/// `store` before returning it. This keeps an external store in sync with the cache.
+	///
+	/// If a specific instance of a cache is required, see [`Self::write_through_with_cache`].
+	///
+	/// ```
+	/// # use fn_cache::{FnCache, GenericCache};
+	/// # use std::collections::HashMap;
+	/// # use std::sync::Mutex;
+	/// let store = Mutex::new(Vec::new());
+	///
+	/// let mut cache: GenericCache<HashMap<_, _>> = GenericCache::write_through(
+	///     |x: &usize| x * 2,
+	///     |x, y| store.lock().unwrap().push((*x, *y)),
+	/// );
+	///
+	/// cache.get(1);
+	/// cache.get(1);
+	///
+	/// assert_eq!(*store.lock().unwrap(), [(1, 2)]);
+	/// ```
+	pub fn write_through(
+		load: impl Fn(&C::Input) -> C::Output + Send + Sync + 'f,
+		store: impl Fn(&C::Input, &C::Output) + Send + Sync + 'f,
+	) -> Self {
+		Self::write_through_with_cache(Default::default(), load, store)
+	}
+
+	/// Create a `GenericCache` using the `Default` implementation of the [`SparseContainer`]
+	/// type, using a recursive function which is evaluated without native recursion.
+	///
+	/// Along with the function, `deps` must return every input the function will get from the
+	/// cache for a given input. Before computing an input, each of its dependencies, and their
+	/// dependencies in turn, are computed iteratively using an explicit stack, so every call the
+	/// function makes to the cache is a hit. This allows very deep recursion without overflowing
+	/// the stack.
+	///
+	/// If `deps` does not list a dependency, the function will recurse natively to compute it as
+	/// usual.
+	///
+	/// # Panics
+	/// When getting a value, if the dependencies of its input contain a cycle.
+	///
+	/// If a specific instance of a cache is required, see
+	/// [`Self::recursive_iterative_with_cache`].
+	///
+	/// ```
+	/// # use fn_cache::{FnCache, GenericCache};
+	/// # use std::collections::HashMap;
+	/// let mut cache: GenericCache<HashMap<u64, u64>> = GenericCache::recursive_iterative(
+	///     |x| match x {
+	///         0 => vec![],
+	///         _ => vec![x - 1],
+	///     },
+	///     |cache, x| match x {
+	///         0 => 0,
+	///         _ => cache.get(x - 1) + 1,
+	///     },
+	/// );
+	///
+	/// assert_eq!(cache.get(200_000), &200_000);
+	/// ```
+	pub fn recursive_iterative(
+		deps: impl Fn(&C::Input) -> Vec<C::Input> + Send + Sync + 'f,
+		f: impl Fn(&mut RefCache<C>, &C::Input) -> C::Output + Send + Sync + 'f,
+	) -> Self
+	where
+		C::Input: Clone + Eq + Hash,
+	{
+		Self::recursive_iterative_with_cache(Default::default(), deps, f)
+	}
+
+	/// Create a `GenericCache` using the `Default` implementation of the [`SparseContainer`]
+	/// type, using a recursive function which returns its output along with inputs that are
+	/// likely to be requested next.
+	///
+	/// Each hinted input which is not already in the cache is computed eagerly, right after the
+	/// input that hinted it. The hints returned while prefetching are ignored, so that a chain of
+	/// hints cannot grow without bound. A hint for an input which is still being computed, such
+	/// as the input that hinted it, is skipped, since its value is already on the way.
+	///
+	/// If a specific instance of a cache is required, see
+	/// [`Self::recursive_prefetching_with_cache`].
+	///
+	/// ```
+	/// # use fn_cache::{FnCache, GenericCache};
+	/// # use std::collections::HashMap;
+	/// let mut cache: GenericCache<HashMap<usize, usize>> =
+	///     GenericCache::recursive_prefetching(|_, x| (x * 2, vec![x + 1]));
+	///
+	/// cache.get(1);
+	///
+	/// assert_eq!(cache.get_entry(2), (false, &4));
+	/// ```
+	pub fn recursive_prefetching(
+		f: impl Fn(&mut RefCache<C>, &C::Input) -> (C::Output, Vec<C::Input>) + Send + Sync + 'f,
+	) -> Self
+	where
+		C::Input: Clone + PartialEq,
+	{
+		Self::recursive_prefetching_with_cache(Default::default(), f)
+	}
+
+	/// Returns the maximum recursion depth reached while computing `input`.
+	///
+	/// The computation is run against a fresh, empty container, so that values already in the
+	/// cache do not hide any recursion, and the cache itself is left untouched. A non-recursive
+	/// function always has a depth of 1.
+	///
+	/// ```
+	/// # use fn_cache::{FnCache, HashCache};
+	/// let cache = HashCache::<usize, u64>::recursive(|cache, x| match x {
+	///     0 => 0,
+	///     1 => 1,
+	///     _ => *cache.get(x - 1) + *cache.get(x - 2),
+	/// });
+	///
+	/// assert_eq!(cache.max_depth_for(10), 10);
+	/// ```
+	#[cfg(feature = "debug_trace")]
+	pub fn max_depth_for(&self, input: C::Input) -> usize {
+		let mut cache = C::default();
+		let mut ref_cache = RefCache::new(&mut cache, expect_fn(&self.f).as_ref());
+		ref_cache.get(input);
+		ref_cache.max_depth
+	}
+}
+
+#[cfg(feature = "serde")]
+impl<'f, C> GenericCache<'f, C>
+where
+	C: SparseContainer,
+	C::Input: Clone + serde::Serialize,
+	C::Output: serde::Serialize,
+{
+	/// Retrieve a value from the cache, computing it if required, and write the `(input, output)`
+	/// pair to `writer` as a line of JSON.
+	///
+	/// The pair is written whether or not the value was already cached. If only newly computed
+	/// values should be persisted, check [`Self::get_entry`] or [`SparseContainer::has`] first.
+	///
+	/// ```
+	/// # use fn_cache::HashCache;
+	/// let mut cache = HashCache::<usize, usize>::new(|x| x * x);
+	/// let mut log = Vec::new();
+	///
+	/// assert_eq!(cache.get_and_persist(3, &mut log).unwrap(), &9);
+	/// assert_eq!(log, b"[3,9]\n");
+	/// ```
+	pub fn get_and_persist(
+		&mut self,
+		input: C::Input,
+		writer: &mut impl std::io::Write,
+	) -> serde_json::Result<&C::Output> {
+		let (_, output) = self.get_entry(input.clone());
+
+		serde_json::to_writer(&mut *writer, &(&input, output))?;
+		writer.write_all(b"\n").map_err(serde_json::Error::io)?;
+
+		Ok(output)
+	}
+}
+
+#[cfg(feature = "serde")]
+impl<'f, C> GenericCache<'f, C>
+where
+	C: SparseContainer + serde::Serialize,
+{
+	/// Serialize every value stored in the cache, along with its input, but not the function.
+	///
+	/// This allows values to be precomputed, such as in a build step, and loaded later with
+	/// [`Self::load_entries`] instead of being computed again.
+	///
+	/// ```
+	/// # use fn_cache::{BTreeCache, FnCache};
+	/// let mut cache = BTreeCache::<u64, u64>::new(|x| x * x);
+	///
+	/// cache.get(2);
+	/// cache.get(3);
+	///
+	/// let mut json = Vec::new();
+	/// cache.serialize_entries(&mut serde_json::Serializer::new(&mut json)).unwrap();
+	///
+	/// assert_eq!(json, br#"{"2":4,"3":9}"#);
+	/// ```
+	pub fn serialize_entries<S: serde::Serializer>(
+		&self,
+		serializer: S,
+	) -> Result<S::Ok, S::Error> {
+		self.cache.serialize(serializer)
+	}
+}
+
+#[cfg(feature = "serde")]
+impl<'f, C> GenericCache<'f, C>
+where
+	C: SparseContainer,
+{
+	/// Create a cache out of entries written by [`Self::serialize_entries`], and the function to
+	/// compute any other values.
+	///
+	/// The loaded values are returned without calling the function. A recursive function can be
+	/// set afterwards with [`Self::set_recursive_fn`].
+	///
+	/// ```
+	/// # use fn_cache::{BTreeCache, FnCache};
+	/// let mut de = serde_json::Deserializer::from_slice(br#"{"2":4,"3":9}"#);
+	/// let mut cache = BTreeCache::<u64, u64>::load_entries(&mut de, |_| unreachable!()).unwrap();
+	///
+	/// assert_eq!(cache.get(3), &9);
+	/// ```
+	pub fn load_entries<'de, D>(
+		deserializer: D,
+		f: impl Fn(&C::Input) -> C::Output + Send + Sync + 'f,
+	) -> Result<Self, D::Error>
+	where
+		C: serde::Deserialize<'de>,
+		D: serde::Deserializer<'de>,
+	{
+		Ok(Self::with_cache(C::deserialize(deserializer)?, f))
+	}
+}
+
+impl<'f, C, V> GenericCache<'f, C>
+where
+	C: SparseContainer<Output = Option<V>>,
+{
+	/// Returns true if the cached value for `input` is `None`.
+	///
+	/// A cached `None` is a hit like any other value, so functions which may not find a result
+	/// only look for it once. This distinguishes that case from an input which has never been
+	/// computed, for which this returns false.
+	///
+	/// ```
+	/// # use fn_cache::{FnCache, HashCache};
+	/// let mut cache = HashCache::<usize, Option<usize>>::new(|x| x.checked_sub(5));
+	///
+	/// cache.get(3);
+	///
+	/// assert!(cache.is_negative(&3));
+	/// assert!(!cache.is_negative(&4));
+	/// ```
+	pub fn is_negative(&self, input: &C::Input) -> bool {
+		matches!(self.cache.get(input), Some(None))
+	}
 }
 
 impl<'f, C: SparseContainer + ContainerLen> GenericCache<'f, C> {
@@ -131,6 +960,11 @@ impl<'f, C: SparseContainer + ContainerLen> GenericCache<'f, C> {
 	pub fn len(&self) -> usize {
 		self.cache.len()
 	}
+
+	/// Returns true if the cache holds no elements.
+	pub fn is_empty(&self) -> bool {
+		self.cache.is_empty()
+	}
 }
 
 impl<'f, C: SparseContainer + ContainerClear> GenericCache<'f, C> {
@@ -141,6 +975,293 @@ impl<'f, C: SparseContainer + ContainerClear> GenericCache<'f, C> {
 	}
 }
 
+impl<'f, C: ContainerIter> GenericCache<'f, C> {
+	/// Returns an iterator over every input in the cache, along with its output.
+	///
+	/// The order is that of the container's iterator, so it is arbitrary for a [`HashMap`], and
+	/// sorted by input for a [`BTreeMap`].
+	///
+	/// ```
+	/// # use fn_cache::{BTreeCache, FnCache};
+	/// let mut cache = BTreeCache::<usize, usize>::new(|x| x * x);
+	///
+	/// cache.get(3);
+	/// cache.get(1);
+	///
+	/// assert!(cache.iter().eq([(&1, &1), (&3, &9)]));
+	/// ```
+	///
+	/// [`HashMap`]: std::collections::HashMap
+	/// [`BTreeMap`]: std::collections::BTreeMap
+	pub fn iter(&self) -> impl Iterator<Item = (&C::Input, &C::Output)> {
+		self.cache.iter()
+	}
+
+	/// Returns an iterator over every input in the cache.
+	pub fn keys(&self) -> impl Iterator<Item = &C::Input> {
+		self.cache.iter().map(|(i, _)| i)
+	}
+
+	/// Returns the set of every input in the cache.
+	///
+	/// ```
+	/// # use fn_cache::{FnCache, HashCache};
+	/// # use std::collections::HashSet;
+	/// let mut cache = HashCache::<usize, usize>::new(|x| *x);
+	///
+	/// cache.get(1);
+	/// cache.get(3);
+	///
+	/// assert_eq!(cache.key_set(), HashSet::from([&1, &3]));
+	/// ```
+	pub fn key_set(&self) -> HashSet<&C::Input>
+	where
+		C::Input: Eq + Hash,
+	{
+		self.keys().collect()
+	}
+
+	/// Returns every input in the cache, in the order of the container's iterator. Unlike
+	/// [`Self::key_set`], this does not require the inputs to be hashable.
+	pub fn key_vec(&self) -> Vec<&C::Input> {
+		self.keys().collect()
+	}
+
+	/// Returns an iterator over every entry in the cache, in batches of `size` entries.
+	///
+	/// Only one batch is collected at a time, which allows processing a large cache, such as
+	/// writing it out, without collecting every entry at once. The last batch may be smaller.
+	///
+	/// # Panics
+	/// If `size` is zero.
+	///
+	/// ```
+	/// # use fn_cache::{FnCache, HashCache};
+	/// let mut cache = HashCache::<usize, usize>::new(|x| *x);
+	///
+	/// for i in 0..5 {
+	///     cache.get(i);
+	/// }
+	///
+	/// let sizes: Vec<_> = cache.chunks(2).map(|chunk| chunk.len()).collect();
+	///
+	/// assert_eq!(sizes, [2, 2, 1]);
+	/// ```
+	pub fn chunks(&self, size: usize) -> impl Iterator<Item = Vec<(&C::Input, &C::Output)>> {
+		assert!(size > 0, "chunk size must be non-zero");
+
+		let mut iter = self.cache.iter();
+
+		std::iter::from_fn(move || {
+			let chunk: Vec<_> = iter.by_ref().take(size).collect();
+			(!chunk.is_empty()).then_some(chunk)
+		})
+	}
+
+	/// Returns an owned copy of every input in the cache, without any of the outputs.
+	///
+	/// This is useful as a manifest of what has been computed, which can be serialized without
+	/// the cost of the outputs.
+	pub fn key_manifest(&self) -> Vec<C::Input>
+	where
+		C::Input: Clone,
+	{
+		self.keys().cloned().collect()
+	}
+
+	/// Combines every entry in the cache into a single value, without computing anything or
+	/// collecting the entries.
+	///
+	/// Entries are visited in the order of the container's [`ContainerIter::iter`].
+	///
+	/// ```
+	/// # use fn_cache::{FnCache, HashCache};
+	/// let mut cache = HashCache::<u64, u64>::new(|x| x * x);
+	///
+	/// cache.get(2);
+	/// cache.get(3);
+	///
+	/// assert_eq!(cache.fold(0, |sum, _, o| sum + o), 13);
+	/// ```
+	pub fn fold<B>(&self, init: B, mut f: impl FnMut(B, &C::Input, &C::Output) -> B) -> B {
+		self.cache.iter().fold(init, |acc, (i, o)| f(acc, i, o))
+	}
+
+	/// Returns the entry with the largest value in the cache, without computing anything, or
+	/// `None` if the cache is empty.
+	///
+	/// If several entries share the largest value, the one with the smallest input is returned,
+	/// so the result does not depend on the order of the container.
+	///
+	/// ```
+	/// # use fn_cache::{FnCache, HashCache};
+	/// let mut cache = HashCache::<i32, i32>::new(|x| x * x);
+	///
+	/// for i in -3..=2 {
+	///     cache.get(i);
+	/// }
+	///
+	/// assert_eq!(cache.max_by_value(), Some((&-3, &9)));
+	/// assert_eq!(cache.min_by_value(), Some((&0, &0)));
+	/// ```
+	pub fn max_by_value(&self) -> Option<(&C::Input, &C::Output)>
+	where
+		C::Input: Ord,
+		C::Output: Ord,
+	{
+		self.iter()
+			.max_by(|(ai, ao), (bi, bo)| ao.cmp(bo).then_with(|| bi.cmp(ai)))
+	}
+
+	/// Returns the entry with the smallest value in the cache, without computing anything, or
+	/// `None` if the cache is empty.
+	///
+	/// If several entries share the smallest value, the one with the smallest input is returned,
+	/// so the result does not depend on the order of the container.
+	pub fn min_by_value(&self) -> Option<(&C::Input, &C::Output)>
+	where
+		C::Input: Ord,
+		C::Output: Ord,
+	{
+		self.iter()
+			.min_by(|(ai, ao), (bi, bo)| ao.cmp(bo).then_with(|| ai.cmp(bi)))
+	}
+}
+
+impl<'f, C> GenericCache<'f, C>
+where
+	C: ContainerIter,
+	C::Input: Clone,
+	C::Output: Clone,
+{
+	/// Copies every value in this cache into a new cache using a different type of container,
+	/// with the provided function.
+	///
+	/// The function may be the same as the one used by this cache, or a new one, but the values
+	/// copied across are not checked against it.
+	///
+	/// ```
+	/// # use fn_cache::{BTreeCache, FnCache, HashCache};
+	/// let mut hash = HashCache::<usize, usize>::new(|x| x * x);
+	///
+	/// hash.get(3);
+	/// hash.get(1);
+	///
+	/// let btree: BTreeCache<usize, usize> = hash.rebuild_into(|x| x * x);
+	///
+	/// assert_eq!(btree.key_vec(), [&1, &3]);
+	/// ```
+	pub fn rebuild_into<'g, C2>(
+		&self,
+		f: impl Fn(&C::Input) -> C::Output + Send + Sync + 'g,
+	) -> GenericCache<'g, C2>
+	where
+		C2: SparseContainer<Input = C::Input, Output = C::Output> + Default,
+	{
+		let mut cache = C2::default();
+
+		for (i, o) in self.cache.iter() {
+			cache.put(i.clone(), o.clone());
+		}
+
+		GenericCache::with_cache(cache, f)
+	}
+}
+
+impl<'f, C> GenericCache<'f, C>
+where
+	C: ContainerIter,
+	C::Input: Clone + Eq,
+	C::Output: Clone + PartialEq,
+{
+	/// Compares the values in this cache with those in `other`, reporting every input which is
+	/// only in one of them, and every entry which has a different value in each.
+	///
+	/// This is useful for regression testing, by warming two caches built from different
+	/// versions of a function with the same inputs.
+	///
+	/// ```
+	/// # use fn_cache::{FnCache, HashCache};
+	/// let mut left = HashCache::<usize, usize>::new(|x| x * 2);
+	/// let mut right = HashCache::<usize, usize>::new(|x| (*x).min(3) * 2);
+	///
+	/// for i in 0..5 {
+	///     left.get(i);
+	///     right.get(i);
+	/// }
+	///
+	/// left.get(5);
+	///
+	/// let mut diff = left.diff(&right);
+	/// diff.changed.sort();
+	///
+	/// assert_eq!(diff.only_left, [5]);
+	/// assert!(diff.only_right.is_empty());
+	/// assert_eq!(diff.changed, [(4, 8, 6)]);
+	/// ```
+	pub fn diff(&self, other: &Self) -> CacheDiff<C::Input, C::Output> {
+		let mut diff = CacheDiff {
+			only_left: Vec::new(),
+			only_right: Vec::new(),
+			changed: Vec::new(),
+		};
+
+		for (i, o) in self.cache.iter() {
+			match other.cache.get(i) {
+				None => diff.only_left.push(i.clone()),
+				Some(other) if other != o => {
+					diff.changed.push((i.clone(), o.clone(), other.clone()))
+				}
+				Some(_) => (),
+			}
+		}
+
+		for (i, _) in other.cache.iter() {
+			if !self.cache.has(i) {
+				diff.only_right.push(i.clone());
+			}
+		}
+
+		diff
+	}
+}
+
+/// The entries which differ between two caches, as returned by [`GenericCache::diff`].
+///
+/// Each list is in the order of the container's iterator.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CacheDiff<I, O> {
+	/// Inputs only in the cache `diff` was called on.
+	pub only_left: Vec<I>,
+	/// Inputs only in the cache passed to `diff`.
+	pub only_right: Vec<I>,
+	/// Inputs in both caches with a different value in each, along with the value in the cache
+	/// `diff` was called on, then the value in the cache passed to `diff`.
+	pub changed: Vec<(I, O, O)>,
+}
+
+impl<'f, C> GenericCache<'f, C>
+where
+	C: ContainerIter + ContainerClear,
+	C::Input: Clone,
+{
+	/// Computes the function again for every input currently in the cache, replacing the old
+	/// values. This is useful when something the function depends on has changed.
+	///
+	/// The cache is cleared before recomputing, so that recursive functions do not reuse stale
+	/// values for their dependencies. As a result, each input is computed exactly once, and any
+	/// dependency which had been removed from the cache is added back.
+	pub fn recompute_all(&mut self) {
+		let inputs = self.key_manifest();
+
+		self.cache.clear();
+
+		for i in inputs {
+			self.get(i);
+		}
+	}
+}
+
 impl<'f, C: SparseContainer + ContainerReserve> GenericCache<'f, C> {
 	/// Reserves capacity for at least `additional` more elements
 	/// to be inserted in the cache. The collection may
@@ -150,6 +1271,65 @@ impl<'f, C: SparseContainer + ContainerReserve> GenericCache<'f, C> {
 	}
 }
 
+impl<'f, C: ContainerDrain> GenericCache<'f, C> {
+	/// Removes every value from the cache, returning each input and output in an iterator.
+	///
+	/// Unlike [`Self::clear`], this hands over ownership of everything computed so far. The
+	/// container keeps any allocated memory for reuse.
+	///
+	/// ```
+	/// # use fn_cache::{BTreeCache, FnCache};
+	/// let mut cache = BTreeCache::<u64, u64>::new(|x| x * x);
+	///
+	/// cache.get(3);
+	/// cache.get(2);
+	///
+	/// assert_eq!(cache.drain().collect::<Vec<_>>(), [(2, 4), (3, 9)]);
+	/// assert!(cache.is_empty());
+	/// ```
+	pub fn drain(&mut self) -> impl Iterator<Item = (C::Input, C::Output)> + '_ {
+		self.cache.drain()
+	}
+}
+
+impl<'f, C: SparseContainer + ContainerShrink> GenericCache<'f, C> {
+	/// Shrinks the capacity of the cache as much as possible, releasing memory held for values
+	/// which have since been removed.
+	pub fn shrink_to_fit(&mut self) {
+		self.cache.shrink_to_fit()
+	}
+}
+
+impl<'f, C: ContainerEvict> GenericCache<'f, C> {
+	/// Returns the input whose value would be evicted if the value for `input` were computed
+	/// now, or `None` if it would fit, or is already in the cache.
+	///
+	/// This allows deciding whether a value is worth computing before doing so.
+	///
+	/// ```
+	/// # use fn_cache::{FnCache, LruCache};
+	/// let mut cache = LruCache::with_capacity(2, |x: &u64| x * x);
+	///
+	/// cache.get(1);
+	/// assert_eq!(cache.would_evict(&3), None);
+	///
+	/// cache.get(2);
+	/// assert_eq!(cache.would_evict(&2), None);
+	/// assert_eq!(cache.would_evict(&3), Some(&1));
+	/// ```
+	pub fn would_evict(&self, input: &C::Input) -> Option<&C::Input> {
+		self.cache.would_evict(input)
+	}
+}
+
+impl<'f, C: SparseContainer + ContainerCapacity> GenericCache<'f, C> {
+	/// Returns the number of elements the cache can hold without reallocating, or for a
+	/// bounded container, without evicting any values.
+	pub fn capacity(&self) -> usize {
+		self.cache.capacity()
+	}
+}
+
 impl<'f, C: ContainerRemove> GenericCache<'f, C> {
 	/// Removes the input from the cache, returning any value
 	/// if the input was previously in the cache.
@@ -158,15 +1338,80 @@ impl<'f, C: ContainerRemove> GenericCache<'f, C> {
 	}
 }
 
+impl<'f, C> GenericCache<'f, C>
+where
+	C: ContainerIter + ContainerRemove,
+	C::Input: Clone,
+{
+	/// Moves every value for which `pred` returns true out of this cache and into `other`,
+	/// such as to rebalance values between two tiers of caches for the same function.
+	///
+	/// A value already in `other` for the same input is kept, and the moved value is dropped.
+	///
+	/// ```
+	/// # use fn_cache::{BTreeCache, FnCache};
+	/// let mut hot = BTreeCache::new(|x: &usize| x * x);
+	/// let mut cold = BTreeCache::new(|x: &usize| x * x);
+	///
+	/// hot.get(1);
+	/// hot.get(20);
+	///
+	/// hot.move_matching(&mut cold, |_, o| *o > 100);
+	///
+	/// assert_eq!(hot.key_vec(), [&1]);
+	/// assert_eq!(cold.key_vec(), [&20]);
+	/// ```
+	pub fn move_matching(
+		&mut self,
+		other: &mut Self,
+		pred: impl Fn(&C::Input, &C::Output) -> bool,
+	) {
+		let inputs: Vec<_> = self
+			.cache
+			.iter()
+			.filter(|(i, o)| pred(i, o))
+			.map(|(i, _)| i.clone())
+			.collect();
+
+		for i in inputs {
+			if let Some(o) = self.cache.remove(&i) {
+				other.cache.put(i, o);
+			}
+		}
+	}
+}
+
 impl<'f, C: SparseContainer> FnCache<C::Input, C::Output> for GenericCache<'f, C> {
 	fn get(&mut self, input: C::Input) -> &C::Output {
 		if self.cache.has(&input) {
+			self.cache.touch(&input);
 			self.cache.get(&input).unwrap()
 		} else {
-			let mut ref_cache = RefCache::new(&mut self.cache, self.f.as_ref());
-			let output = (self.f)(&mut ref_cache, &input);
-			self.cache.put(input, output)
+			self.compute(input)
+		}
+	}
+}
+
+impl<'f, C: SparseContainer> TryFnCache<C::Input, C::Output, Uninitialized>
+	for GenericCache<'f, C>
+{
+	/// Returns [`Uninitialized`] if the value is not cached and the function has not been set yet.
+	fn try_get(&mut self, input: C::Input) -> Result<&C::Output, Uninitialized> {
+		if self.f.is_none() && !self.cache.has(&input) {
+			return Err(Uninitialized);
 		}
+
+		Ok(self.get(input))
+	}
+}
+
+impl<'f, C: SparseContainer> FnCachePeek<C::Input, C::Output> for GenericCache<'f, C> {
+	fn peek(&self, input: &C::Input) -> Option<&C::Output> {
+		self.cache.get(input)
+	}
+
+	fn contains(&self, input: &C::Input) -> bool {
+		self.cache.has(input)
 	}
 }
 
@@ -175,18 +1420,83 @@ where
 	C: SparseContainer,
 	C::Input: Clone,
 {
+	/// Only inputs missing from the container are computed, skipping the lookup that
+	/// [`FnCache::get`] would perform to return a reference. The final lookup for each input is
+	/// unavoidable, as references cannot be held while computing the remaining inputs mutably.
 	fn get_many<const N: usize>(&mut self, inputs: [C::Input; N]) -> [&C::Output; N] {
 		for i in &inputs {
-			self.get(i.clone());
+			if self.cache.has(i) {
+				self.cache.touch(i);
+			} else {
+				self.compute(i.clone());
+			}
 		}
 
 		inputs.map(|i| self.cache.get(&i).unwrap())
 	}
 }
 
+#[cfg(feature = "rayon")]
+impl<'f, C> GenericCache<'f, C>
+where
+	C: SparseContainer,
+	C::Input: Clone + Eq + Hash + Send + Sync,
+	C::Output: Send,
+{
+	/// Retrieve a batch of values from the cache, computing every missing value in parallel
+	/// before putting them all into the cache.
+	///
+	/// Only a cache built from a function which does not take a [`RefCache`], such as with
+	/// [`Self::new`], can compute in parallel, since a recursive function needs exclusive access
+	/// to the cache. A recursive cache falls back to computing each missing value in order, the
+	/// same as [`FnCacheMany::get_many_vec`].
+	///
+	/// ```
+	/// # use fn_cache::HashCache;
+	/// let mut cache = HashCache::<u64, u64>::new(|x| (1..=*x).product());
+	///
+	/// assert_eq!(cache.get_many_par(&[5, 10, 5]), [&120, &3628800, &120]);
+	/// assert_eq!(cache.len(), 2);
+	/// ```
+	pub fn get_many_par(&mut self, inputs: &[C::Input]) -> Vec<&C::Output> {
+		use rayon::prelude::*;
+
+		let Some(simple) = &self.simple else {
+			return self.get_many_vec(inputs);
+		};
+
+		let mut missing = Vec::new();
+		let mut seen = HashSet::new();
+
+		for i in inputs {
+			if self.cache.has(i) {
+				self.cache.touch(i);
+			} else if seen.insert(i) {
+				missing.push(i);
+			}
+		}
+
+		let outputs: Vec<_> = missing.par_iter().map(|i| simple(i)).collect();
+
+		for (i, o) in missing.into_iter().zip(outputs) {
+			self.cache.begin(i);
+			self.cache.put(i.clone(), o);
+		}
+
+		inputs.iter().map(|i| self.cache.get(i).unwrap()).collect()
+	}
+}
+
 pub struct RefCache<'c, C: SparseContainer> {
 	pub(crate) cache: &'c mut C,
 	f: &'c (dyn Fn(&mut Self, &C::Input) -> C::Output + Send),
+	/// The inputs being computed by [`GenericCache::recursive_prefetching`], which are not hinted
+	/// again.
+	prefetching: Vec<C::Input>,
+	#[cfg(feature = "debug_trace")]
+	depth: usize,
+	#[cfg(feature = "debug_trace")]
+	max_depth: usize,
 }
 
 impl<'c, C: SparseContainer> RefCache<'c, C> {
@@ -194,7 +1504,34 @@ impl<'c, C: SparseContainer> RefCache<'c, C> {
 		cache: &'c mut C,
 		f: &'c (dyn Fn(&mut Self, &C::Input) -> C::Output + Send),
 	) -> Self {
-		Self { cache, f }
+		Self {
+			cache,
+			f,
+			prefetching: Vec::new(),
+			#[cfg(feature = "debug_trace")]
+			depth: 0,
+			#[cfg(feature = "debug_trace")]
+			max_depth: 0,
+		}
+	}
+
+	fn compute(&mut self, input: C::Input) -> &C::Output {
+		self.cache.begin(&input);
+
+		#[cfg(feature = "debug_trace")]
+		{
+			self.depth += 1;
+			self.max_depth = self.max_depth.max(self.depth);
+		}
+
+		let output = (self.f)(self, &input);
+
+		#[cfg(feature = "debug_trace")]
+		{
+			self.depth -= 1;
+		}
+
+		self.cache.put(input, output)
 	}
 }
 
@@ -204,22 +1541,42 @@ where
 {
 	fn get(&mut self, input: C::Input) -> &C::Output {
 		if self.cache.has(&input) {
+			self.cache.touch(&input);
 			self.cache.get(&input).unwrap()
 		} else {
-			let output = (self.f)(self, &input);
-			self.cache.put(input, output)
+			self.compute(input)
 		}
 	}
 }
 
+impl<'c, C> FnCachePeek<C::Input, C::Output> for RefCache<'c, C>
+where
+	C: SparseContainer,
+{
+	fn peek(&self, input: &C::Input) -> Option<&C::Output> {
+		self.cache.get(input)
+	}
+
+	fn contains(&self, input: &C::Input) -> bool {
+		self.cache.has(input)
+	}
+}
+
 impl<'c, C> FnCacheMany<C::Input, C::Output> for RefCache<'c, C>
 where
 	C: SparseContainer,
 	C::Input: Clone,
 {
+	/// Only inputs missing from the container are computed, skipping the lookup that
+	/// [`FnCache::get`] would perform to return a reference. The final lookup for each input is
+	/// unavoidable, as references cannot be held while computing the remaining inputs mutably.
 	fn get_many<const N: usize>(&mut self, inputs: [C::Input; N]) -> [&C::Output; N] {
 		for i in &inputs {
-			self.get(i.clone());
+			if self.cache.has(i) {
+				self.cache.touch(i);
+			} else {
+				self.compute(i.clone());
+			}
 		}
 
 		inputs.map(|i| self.cache.get(&i).unwrap())
@@ -1,5 +1,6 @@
 use std::collections::hash_map::RandomState;
 use std::collections::HashMap;
+use std::sync::Arc;
 
 use core::cmp::Eq;
 use core::hash::BuildHasher;
@@ -8,8 +9,10 @@ use core::hash::Hash;
 use derive_more::derive::{Deref, DerefMut, From};
 
 use crate::container::{
-	ContainerClear, ContainerLen, ContainerRemove, ContainerReserve, SparseContainer,
+	ContainerCapacity, ContainerClear, ContainerDrain, ContainerIter, ContainerLen,
+	ContainerRemove, ContainerReserve, ContainerShrink, SparseContainer,
 };
+use crate::frozen_cache::FrozenCache;
 use crate::generic_cache::{GenericCache, RefCache};
 
 /// A cache for a function which uses a [`HashMap`].
@@ -24,6 +27,12 @@ use crate::generic_cache::{GenericCache, RefCache};
 /// `Hash`, and the following propery must hold:
 ///
 /// ```k1 == k2 -> hash(k1) == hash(k2)```
+///
+/// Functions which may not find a result can return an
+/// [`Option`], and a cached `None` is treated like any
+/// other value, so it is not computed again. See
+/// [`GenericCache::is_negative`] to tell a cached `None`
+/// apart from an input which was never computed.
 #[derive(Deref, DerefMut, From)]
 pub struct HashCache<'f, I, O, S = RandomState>
 where
@@ -37,13 +46,13 @@ impl<'f, I, O> HashCache<'f, I, O, RandomState>
 where
 	I: Eq + Hash,
 {
-	pub fn new(f: impl Fn(&I) -> O + Send + 'f) -> Self {
+	pub fn new(f: impl Fn(&I) -> O + Send + Sync + 'f) -> Self {
 		Self {
 			raw: GenericCache::new(f),
 		}
 	}
 
-	pub fn recursive(f: impl Fn(&mut RefCache<HashMap<I, O>>, &I) -> O + Send + 'f) -> Self {
+	pub fn recursive(f: impl Fn(&mut RefCache<HashMap<I, O>>, &I) -> O + Send + Sync + 'f) -> Self {
 		Self {
 			raw: GenericCache::recursive(f),
 		}
@@ -55,7 +64,7 @@ where
 	I: Eq + Hash,
 	S: BuildHasher,
 {
-	pub fn with_hasher(hash_builder: S, f: impl Fn(&I) -> O + Send + 'f) -> Self {
+	pub fn with_hasher(hash_builder: S, f: impl Fn(&I) -> O + Send + Sync + 'f) -> Self {
 		Self {
 			raw: GenericCache::with_cache(HashMap::with_hasher(hash_builder), f),
 		}
@@ -63,12 +72,142 @@ where
 
 	pub fn recursive_with_hasher(
 		hash_builder: S,
-		f: impl Fn(&mut RefCache<HashMap<I, O, S>>, &I) -> O + Send + 'f,
+		f: impl Fn(&mut RefCache<HashMap<I, O, S>>, &I) -> O + Send + Sync + 'f,
 	) -> Self {
 		Self {
 			raw: GenericCache::recursive_with_cache(HashMap::with_hasher(hash_builder), f),
 		}
 	}
+
+	pub fn with_capacity_and_hasher(
+		capacity: usize,
+		hash_builder: S,
+		f: impl Fn(&I) -> O + Send + Sync + 'f,
+	) -> Self {
+		Self {
+			raw: GenericCache::with_cache(
+				HashMap::with_capacity_and_hasher(capacity, hash_builder),
+				f,
+			),
+		}
+	}
+
+	pub fn recursive_with_capacity_and_hasher(
+		capacity: usize,
+		hash_builder: S,
+		f: impl Fn(&mut RefCache<HashMap<I, O, S>>, &I) -> O + Send + Sync + 'f,
+	) -> Self {
+		Self {
+			raw: GenericCache::recursive_with_cache(
+				HashMap::with_capacity_and_hasher(capacity, hash_builder),
+				f,
+			),
+		}
+	}
+
+	/// Freeze the cache, so its values can only be read. See [`GenericCache::freeze`].
+	pub fn freeze(self) -> FrozenCache<HashMap<I, O, S>> {
+		self.raw.freeze()
+	}
+
+	/// Compute the value for each input, then freeze the cache and wrap it in an [`Arc`]. See
+	/// [`GenericCache::warm_and_share`].
+	pub fn warm_and_share(
+		self,
+		inputs: impl IntoIterator<Item = I>,
+	) -> Arc<FrozenCache<HashMap<I, O, S>>> {
+		self.raw.warm_and_share(inputs)
+	}
+
+	/// Create a cache out of entries written by [`GenericCache::serialize_entries`], and the
+	/// function to compute any other values.
+	///
+	/// See [`GenericCache::load_entries`].
+	#[cfg(feature = "serde")]
+	pub fn load_entries<'de, D>(
+		deserializer: D,
+		f: impl Fn(&I) -> O + Send + Sync + 'f,
+	) -> Result<Self, D::Error>
+	where
+		HashMap<I, O, S>: serde::Deserialize<'de>,
+		D: serde::Deserializer<'de>,
+	{
+		Ok(Self {
+			raw: GenericCache::load_entries(deserializer, f)?,
+		})
+	}
+}
+
+/// How evenly the keys of a [`HashMap`] are spread over its buckets by its hasher.
+///
+/// The standard library does not expose the layout of a [`HashMap`], so these are estimated by
+/// hashing every key with the map's hasher into a simulated table, with a power of two number of
+/// buckets at least as large as the map's capacity. A good hasher gives a few small collisions,
+/// while a poor one gives long chains of keys sharing a bucket.
+#[cfg(feature = "debug_trace")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CollisionStats {
+	/// The number of keys in the map.
+	pub keys: usize,
+	/// The number of buckets in the simulated table.
+	pub buckets: usize,
+	/// The number of buckets holding at least one key.
+	pub occupied_buckets: usize,
+	/// The largest number of keys sharing one bucket.
+	pub max_bucket_len: usize,
+}
+
+#[cfg(feature = "debug_trace")]
+impl CollisionStats {
+	/// Returns the fraction of keys which share a bucket with an earlier key, or zero if there
+	/// are no keys.
+	pub fn collision_rate(&self) -> f64 {
+		match self.keys {
+			0 => 0.0,
+			keys => (keys - self.occupied_buckets) as f64 / keys as f64,
+		}
+	}
+}
+
+#[cfg(feature = "debug_trace")]
+impl<'f, I, O, S> GenericCache<'f, HashMap<I, O, S>>
+where
+	I: Eq + Hash,
+	S: BuildHasher,
+{
+	/// Estimates how well the hasher distributes the keys currently in the cache.
+	///
+	/// See [`CollisionStats`] for how this is estimated.
+	///
+	/// ```
+	/// # use fn_cache::{FnCache, HashCache};
+	/// let mut cache = HashCache::<usize, usize>::new(|x| *x);
+	///
+	/// for i in 0..100 {
+	///     cache.get(i);
+	/// }
+	///
+	/// let stats = cache.collision_stats();
+	///
+	/// assert_eq!(stats.keys, 100);
+	/// assert!(stats.buckets >= 100);
+	/// ```
+	pub fn collision_stats(&self) -> CollisionStats {
+		let map = &self.cache;
+		let buckets = map.capacity().max(1).next_power_of_two();
+		let mut lens = vec![0; buckets];
+
+		for key in map.keys() {
+			lens[map.hasher().hash_one(key) as usize & (buckets - 1)] += 1;
+		}
+
+		CollisionStats {
+			keys: map.len(),
+			buckets,
+			occupied_buckets: lens.iter().filter(|&&len| len > 0).count(),
+			max_bucket_len: lens.into_iter().max().unwrap_or(0),
+		}
+	}
 }
 
 impl<I, O, S> SparseContainer for std::collections::HashMap<I, O, S>
@@ -121,6 +260,26 @@ where
 	}
 }
 
+impl<I, O, S> ContainerShrink for std::collections::HashMap<I, O, S>
+where
+	I: Eq + std::hash::Hash,
+	S: std::hash::BuildHasher,
+{
+	fn shrink_to_fit(&mut self) {
+		self.shrink_to_fit()
+	}
+}
+
+impl<I, O, S> ContainerCapacity for std::collections::HashMap<I, O, S>
+where
+	I: Eq + std::hash::Hash,
+	S: std::hash::BuildHasher,
+{
+	fn capacity(&self) -> usize {
+		self.capacity()
+	}
+}
+
 impl<I, O, S> ContainerRemove for std::collections::HashMap<I, O, S>
 where
 	I: Eq + std::hash::Hash,
@@ -130,3 +289,23 @@ where
 		self.remove(input)
 	}
 }
+
+impl<I, O, S> ContainerDrain for std::collections::HashMap<I, O, S>
+where
+	I: Eq + std::hash::Hash,
+	S: std::hash::BuildHasher,
+{
+	fn drain(&mut self) -> impl Iterator<Item = (I, O)> {
+		self.drain()
+	}
+}
+
+impl<I, O, S> ContainerIter for std::collections::HashMap<I, O, S>
+where
+	I: Eq + std::hash::Hash,
+	S: std::hash::BuildHasher,
+{
+	fn iter(&self) -> impl Iterator<Item = (&I, &O)> {
+		self.iter()
+	}
+}
@@ -0,0 +1,102 @@
+use std::collections::hash_map::RandomState;
+
+use core::cmp::Eq;
+use core::hash::{BuildHasher, Hash};
+
+use indexmap::IndexMap;
+
+use crate::container::{
+	ContainerClear, ContainerIter, ContainerLen, ContainerRemove, ContainerReserve, SparseContainer,
+};
+use crate::GenericCache;
+
+/// A cache for a function which uses an [`IndexMap`] from the [`indexmap`] crate.
+///
+/// This works like a [`HashCache`](crate::HashCache), except the values are kept in the order
+/// they were computed, so iterating over the cache gives the same order on every run.
+///
+/// ```
+/// # use fn_cache::FnCache;
+/// # use fn_cache::index_cache::IndexCache;
+/// let mut cache = IndexCache::<u64, u64>::new(|x| x * x);
+///
+/// cache.get(3);
+/// cache.get(1);
+/// cache.get(2);
+///
+/// assert_eq!(cache.key_vec(), [&3, &1, &2]);
+/// ```
+pub type IndexCache<'f, I, O, S = RandomState> = GenericCache<'f, IndexMap<I, O, S>>;
+
+impl<I, O, S> SparseContainer for IndexMap<I, O, S>
+where
+	I: Eq + Hash,
+	S: BuildHasher,
+{
+	type Input = I;
+	type Output = O;
+
+	fn has(&self, input: &I) -> bool {
+		self.contains_key(input)
+	}
+
+	fn get(&self, input: &I) -> Option<&O> {
+		self.get(input)
+	}
+
+	fn put(&mut self, input: I, output: O) -> &O {
+		self.entry(input).or_insert(output)
+	}
+}
+
+impl<I, O, S> ContainerLen for IndexMap<I, O, S>
+where
+	I: Eq + Hash,
+	S: BuildHasher,
+{
+	fn len(&self) -> usize {
+		self.len()
+	}
+}
+
+impl<I, O, S> ContainerClear for IndexMap<I, O, S>
+where
+	I: Eq + Hash,
+	S: BuildHasher,
+{
+	fn clear(&mut self) {
+		self.clear()
+	}
+}
+
+impl<I, O, S> ContainerReserve for IndexMap<I, O, S>
+where
+	I: Eq + Hash,
+	S: BuildHasher,
+{
+	fn reserve(&mut self, additional: usize) {
+		self.reserve(additional)
+	}
+}
+
+/// Values are removed with [`IndexMap::shift_remove`], which keeps the order of the remaining
+/// values, but takes time proportional to the number of values after the removed one.
+impl<I, O, S> ContainerRemove for IndexMap<I, O, S>
+where
+	I: Eq + Hash,
+	S: BuildHasher,
+{
+	fn remove(&mut self, input: &I) -> Option<O> {
+		self.shift_remove(input)
+	}
+}
+
+impl<I, O, S> ContainerIter for IndexMap<I, O, S>
+where
+	I: Eq + Hash,
+	S: BuildHasher,
+{
+	fn iter(&self) -> impl Iterator<Item = (&I, &O)> {
+		self.iter()
+	}
+}
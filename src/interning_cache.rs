@@ -0,0 +1,170 @@
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use core::cmp::Eq;
+use core::hash::Hash;
+
+use derive_more::derive::{Deref, DerefMut, From};
+
+use crate::container::{
+	ContainerClear, ContainerIter, ContainerLen, ContainerRemove, SparseContainer,
+};
+use crate::generic_cache::{GenericCache, RefCache};
+
+/// A cache for a function which shares a single allocation between equal outputs.
+///
+/// Each output is stored in an [`Arc`], and kept in a pool. When a new output is computed which is
+/// equal to one already in the pool, the pooled [`Arc`] is stored instead, so that many inputs
+/// with the same large output only keep one copy of it.
+///
+/// The requirements for a [`HashMap`] must be met by both the inputs and the outputs.
+///
+/// ```
+/// # use fn_cache::{FnCache, InterningCache};
+/// # use std::sync::Arc;
+/// let mut cache = InterningCache::new(|x: &usize| vec![0u8; x % 2]);
+///
+/// let a = cache.get(1).clone();
+/// let b = cache.get(3).clone();
+///
+/// assert!(Arc::ptr_eq(&a, &b));
+/// assert_eq!(cache.cache().pool_len(), 1);
+/// ```
+#[derive(Deref, DerefMut, From)]
+pub struct InterningCache<'f, I, O>
+where
+	I: Eq + Hash,
+	O: Eq + Hash,
+{
+	raw: GenericCache<'f, Interning<I, O>>,
+}
+
+impl<'f, I, O> InterningCache<'f, I, O>
+where
+	I: Eq + Hash,
+	O: Eq + Hash,
+{
+	pub fn new(f: impl Fn(&I) -> O + Send + Sync + 'f) -> Self {
+		Self {
+			raw: GenericCache::new(move |i| Arc::new(f(i))),
+		}
+	}
+
+	pub fn recursive(
+		f: impl Fn(&mut RefCache<Interning<I, O>>, &I) -> O + Send + Sync + 'f,
+	) -> Self {
+		Self {
+			raw: GenericCache::recursive(move |c, i| Arc::new(f(c, i))),
+		}
+	}
+}
+
+/// A [`SparseContainer`] which shares a single [`Arc`] between equal outputs.
+///
+/// The pool counts how many inputs share each output, and an output is dropped from the pool once
+/// the last input using it is removed, even if a clone of the [`Arc`] is still held elsewhere.
+pub struct Interning<I, O> {
+	map: HashMap<I, Arc<O>>,
+	pool: HashMap<Arc<O>, usize>,
+}
+
+impl<I, O> Default for Interning<I, O> {
+	fn default() -> Self {
+		Self {
+			map: HashMap::new(),
+			pool: HashMap::new(),
+		}
+	}
+}
+
+impl<I, O> Interning<I, O> {
+	/// Returns the number of distinct outputs, each of which has one allocation.
+	pub fn pool_len(&self) -> usize {
+		self.pool.len()
+	}
+}
+
+impl<I, O> SparseContainer for Interning<I, O>
+where
+	I: Eq + Hash,
+	O: Eq + Hash,
+{
+	type Input = I;
+	type Output = Arc<O>;
+
+	fn has(&self, input: &I) -> bool {
+		self.map.contains_key(input)
+	}
+
+	fn get(&self, input: &I) -> Option<&Arc<O>> {
+		self.map.get(input)
+	}
+
+	fn put(&mut self, input: I, output: Arc<O>) -> &Arc<O> {
+		match self.map.entry(input) {
+			Entry::Occupied(entry) => entry.into_mut(),
+			Entry::Vacant(entry) => {
+				let pooled = self.pool.entry(output);
+				let output = pooled.key().clone();
+
+				*pooled.or_insert(0) += 1;
+
+				entry.insert(output)
+			}
+		}
+	}
+}
+
+impl<I, O> ContainerLen for Interning<I, O>
+where
+	I: Eq + Hash,
+	O: Eq + Hash,
+{
+	fn len(&self) -> usize {
+		self.map.len()
+	}
+}
+
+impl<I, O> ContainerClear for Interning<I, O>
+where
+	I: Eq + Hash,
+	O: Eq + Hash,
+{
+	fn clear(&mut self) {
+		self.map.clear();
+		self.pool.clear();
+	}
+}
+
+impl<I, O> ContainerRemove for Interning<I, O>
+where
+	I: Eq + Hash,
+	O: Eq + Hash,
+{
+	/// Removes the input from the cache, returning any value if the input was previously in the
+	/// cache. If no other input shares the output, it is also removed from the pool.
+	fn remove(&mut self, input: &I) -> Option<Arc<O>> {
+		let output = self.map.remove(input)?;
+
+		if let Entry::Occupied(mut pooled) = self.pool.entry(output.clone()) {
+			*pooled.get_mut() -= 1;
+
+			if *pooled.get() == 0 {
+				pooled.remove();
+			}
+		}
+
+		Some(output)
+	}
+}
+
+impl<I, O> ContainerIter for Interning<I, O>
+where
+	I: Eq + Hash,
+	O: Eq + Hash,
+{
+	fn iter(&self) -> impl Iterator<Item = (&I, &Arc<O>)> {
+		self.map.iter()
+	}
+}
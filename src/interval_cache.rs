@@ -0,0 +1,120 @@
+use std::ops::Range;
+
+/// A cache for a function defined over intervals, where a point is a hit if any cached interval
+/// contains it.
+///
+/// On a miss, the function is given the point, and returns an interval along with the value for
+/// every point in that interval. The interval is cached, so later points inside it are hits.
+///
+/// Intervals are kept sorted by their start, alongside the largest end of every interval up to
+/// that point, so that a lookup only visits intervals which start before the point and could
+/// still reach it. Inserting an interval is linear in the number of cached intervals.
+///
+/// ```
+/// # use fn_cache::interval_cache::IntervalCache;
+/// // the value for each point is the multiple of ten it belongs to
+/// let mut cache = IntervalCache::new(|x: &u32| {
+///     let start = x / 10 * 10;
+///     (start..start + 10, start)
+/// });
+///
+/// assert_eq!(cache.get_containing(&15), Some(&10));
+/// assert_eq!(cache.get_containing(&19), Some(&10));
+/// assert_eq!(cache.len(), 1);
+/// ```
+pub struct IntervalCache<'f, K, O> {
+	entries: Vec<(Range<K>, O)>,
+	max_end: Vec<K>,
+	#[allow(clippy::type_complexity)]
+	f: Box<dyn Fn(&K) -> (Range<K>, O) + Send + 'f>,
+}
+
+impl<'f, K, O> IntervalCache<'f, K, O>
+where
+	K: Ord + Clone,
+{
+	/// Create a cache for the provided function, which returns an interval containing the point
+	/// it is given, and the value for every point in that interval.
+	pub fn new(f: impl Fn(&K) -> (Range<K>, O) + Send + 'f) -> Self {
+		Self {
+			entries: Vec::new(),
+			max_end: Vec::new(),
+			f: Box::new(f),
+		}
+	}
+
+	/// Retrieve the value of a cached interval containing `point`. If there is none, the function
+	/// is called and the resulting interval is added to the cache before returning its value.
+	///
+	/// If several cached intervals contain `point`, the one which starts last is used. Returns
+	/// `None` only if the function returns an interval which does not contain `point`, in which
+	/// case the interval is still cached.
+	pub fn get_containing(&mut self, point: &K) -> Option<&O> {
+		let index = match self.find(point) {
+			Some(index) => index,
+			None => {
+				let (range, output) = (self.f)(point);
+				let index = self.insert(range, output);
+
+				if !self.entries[index].0.contains(point) {
+					return None;
+				}
+
+				index
+			}
+		};
+
+		Some(&self.entries[index].1)
+	}
+
+	/// Retrieve the value of a cached interval containing `point`, without computing anything.
+	pub fn get_cached(&self, point: &K) -> Option<&O> {
+		self.find(point).map(|index| &self.entries[index].1)
+	}
+
+	/// Returns the number of intervals in the cache.
+	pub fn len(&self) -> usize {
+		self.entries.len()
+	}
+
+	/// Returns true if the cache holds no intervals.
+	pub fn is_empty(&self) -> bool {
+		self.entries.is_empty()
+	}
+
+	/// Clears the cache, removing all intervals.
+	/// Keeps the allocated memory for reuse.
+	pub fn clear(&mut self) {
+		self.entries.clear();
+		self.max_end.clear();
+	}
+
+	fn find(&self, point: &K) -> Option<usize> {
+		let starts_before = self.entries.partition_point(|(r, _)| r.start <= *point);
+
+		(0..starts_before)
+			.rev()
+			.take_while(|&i| self.max_end[i] > *point)
+			.find(|&i| self.entries[i].0.end > *point)
+	}
+
+	fn insert(&mut self, range: Range<K>, output: O) -> usize {
+		let index = self
+			.entries
+			.partition_point(|(r, _)| r.start <= range.start);
+
+		self.entries.insert(index, (range, output));
+		self.max_end.truncate(index);
+
+		for (r, _) in &self.entries[index..] {
+			let end = match self.max_end.last() {
+				Some(max) if *max > r.end => max.clone(),
+				_ => r.end.clone(),
+			};
+
+			self.max_end.push(end);
+		}
+
+		index
+	}
+}
@@ -0,0 +1,65 @@
+use core::marker::PhantomData;
+
+use crate::FnCache;
+
+/// An extension trait for iterators, to map items through a cache.
+pub trait MemoizedIteratorExt: Iterator + Sized {
+	/// Map each item through a cache, only computing each distinct key once.
+	///
+	/// Each item is converted into a key for the cache using `key_fn`, and the cached output is
+	/// cloned, since iterators yield by value.
+	///
+	/// ```
+	/// # use fn_cache::{BTreeCache, MemoizedIteratorExt};
+	/// let mut cache = BTreeCache::new(|x: &u64| x * x);
+	///
+	/// let squares: Vec<u64> = [1, 2, 1, 3].into_iter().memoized_map(&mut cache, |x| x).collect();
+	///
+	/// assert_eq!(squares, [1, 4, 1, 9]);
+	/// assert_eq!(cache.len(), 3);
+	/// ```
+	fn memoized_map<C, F, I, O>(self, cache: &mut C, key_fn: F) -> MemoizedMap<'_, Self, C, F, I, O>
+	where
+		C: FnCache<I, O>,
+		F: FnMut(Self::Item) -> I,
+		O: Clone,
+	{
+		MemoizedMap {
+			iter: self,
+			cache,
+			key_fn,
+			types: PhantomData,
+		}
+	}
+}
+
+impl<T: Iterator> MemoizedIteratorExt for T {}
+
+/// An iterator which maps items through a cache.
+///
+/// This is created by [`MemoizedIteratorExt::memoized_map`].
+pub struct MemoizedMap<'c, T, C, F, I, O> {
+	iter: T,
+	cache: &'c mut C,
+	key_fn: F,
+	types: PhantomData<fn(I) -> O>,
+}
+
+impl<'c, T, C, F, I, O> Iterator for MemoizedMap<'c, T, C, F, I, O>
+where
+	T: Iterator,
+	C: FnCache<I, O>,
+	F: FnMut(T::Item) -> I,
+	O: Clone,
+{
+	type Item = O;
+
+	fn next(&mut self) -> Option<O> {
+		let item = self.iter.next()?;
+		Some(self.cache.get((self.key_fn)(item)).clone())
+	}
+
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		self.iter.size_hint()
+	}
+}
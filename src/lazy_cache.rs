@@ -0,0 +1,201 @@
+use std::collections::HashMap;
+
+use core::hash::Hash;
+
+use crate::container::{ContainerClear, ContainerLen, SparseContainer};
+use crate::{FnCache, GenericCache};
+
+/// A cache which allows values to be inserted lazily, as a function which is only called the
+/// first time the value is needed.
+///
+/// A lazily inserted value is treated as already being in the cache, so the cache function is
+/// never called for it.
+///
+/// ```
+/// # use fn_cache::{FnCache, LazyCache};
+/// let mut cache = LazyCache::<usize, usize>::new(|x| *x);
+///
+/// cache.insert_lazy(1, || 10);
+///
+/// assert_eq!(cache.get(1), &10);
+/// assert_eq!(cache.get(2), &2);
+/// ```
+///
+/// Other containers can be used by using a `GenericCache<Lazy<C>>` directly.
+pub type LazyCache<'f, I, O> = GenericCache<'f, Lazy<HashMap<I, O>>>;
+
+type Thunk<O> = Box<dyn FnOnce() -> O + Send>;
+
+/// A [`SparseContainer`] which holds functions to compute some of its values, alongside the
+/// values already computed by the container it wraps.
+///
+/// Pending functions are run when their input is touched, which [`GenericCache`] does before
+/// getting any value already present. Getting a value directly from the container without
+/// touching it first returns `None` if its function has not been run yet.
+pub struct Lazy<C: SparseContainer> {
+	inner: C,
+	thunks: HashMap<C::Input, Thunk<C::Output>>,
+}
+
+impl<C: SparseContainer> Lazy<C>
+where
+	C::Input: Eq + Hash,
+{
+	/// Wrap a container, with no pending values.
+	pub fn new(inner: C) -> Self {
+		Self {
+			inner,
+			thunks: HashMap::new(),
+		}
+	}
+
+	/// Get a reference to the wrapped container, which only holds values that have been
+	/// computed.
+	pub fn inner(&self) -> &C {
+		&self.inner
+	}
+
+	/// Returns true if `input` was inserted lazily, and its value has not been computed yet.
+	pub fn is_pending(&self, input: &C::Input) -> bool {
+		self.thunks.contains_key(input)
+	}
+}
+
+impl<C> Default for Lazy<C>
+where
+	C: SparseContainer + Default,
+	C::Input: Eq + Hash,
+{
+	fn default() -> Self {
+		Self::new(C::default())
+	}
+}
+
+impl<C: SparseContainer> SparseContainer for Lazy<C>
+where
+	C::Input: Eq + Hash,
+{
+	type Input = C::Input;
+	type Output = C::Output;
+
+	fn has(&self, input: &Self::Input) -> bool {
+		self.inner.has(input) || self.thunks.contains_key(input)
+	}
+
+	fn get(&self, input: &Self::Input) -> Option<&Self::Output> {
+		self.inner.get(input)
+	}
+
+	fn put(&mut self, input: Self::Input, output: Self::Output) -> &Self::Output {
+		self.thunks.remove(&input);
+		self.inner.put(input, output)
+	}
+
+	fn touch(&mut self, input: &Self::Input) {
+		if let Some((input, thunk)) = self.thunks.remove_entry(input) {
+			self.inner.put(input, thunk());
+		} else {
+			self.inner.touch(input)
+		}
+	}
+
+	fn begin(&mut self, input: &Self::Input) {
+		self.inner.begin(input)
+	}
+}
+
+impl<C: SparseContainer + ContainerLen> ContainerLen for Lazy<C>
+where
+	C::Input: Eq + Hash,
+{
+	fn len(&self) -> usize {
+		self.inner.len() + self.thunks.len()
+	}
+}
+
+impl<C: SparseContainer + ContainerClear> ContainerClear for Lazy<C>
+where
+	C::Input: Eq + Hash,
+{
+	fn clear(&mut self) {
+		self.inner.clear();
+		self.thunks.clear();
+	}
+}
+
+impl<'f, C: SparseContainer> GenericCache<'f, Lazy<C>>
+where
+	C::Input: Eq + Hash,
+{
+	/// Insert a function to compute the value for `input` the first time it is needed, instead
+	/// of the cache function.
+	///
+	/// If the value for `input` has already been computed, this does nothing.
+	pub fn insert_lazy(
+		&mut self,
+		input: C::Input,
+		thunk: impl FnOnce() -> C::Output + Send + 'static,
+	) {
+		if !self.cache.inner.has(&input) {
+			self.cache.thunks.insert(input, Box::new(thunk));
+		}
+	}
+
+	/// Compute the value for `input` now, and return it.
+	///
+	/// If the value was inserted lazily, its function is run and the result cached. Otherwise,
+	/// this is the same as [`FnCache::get`], computing the value using the cache function if it
+	/// is not already cached.
+	///
+	/// ```
+	/// # use fn_cache::LazyCache;
+	/// let mut cache = LazyCache::<usize, usize>::new(|x| *x);
+	///
+	/// cache.insert_lazy(1, || 10);
+	///
+	/// assert!(cache.cache().is_pending(&1));
+	/// assert_eq!(cache.force(&1), &10);
+	/// assert!(!cache.cache().is_pending(&1));
+	/// ```
+	pub fn force(&mut self, input: &C::Input) -> &C::Output
+	where
+		C::Input: Clone,
+	{
+		self.get(input.clone())
+	}
+
+	/// Compute every value which was inserted lazily and has not been computed yet, running their
+	/// functions in parallel, then insert them all.
+	///
+	/// ```
+	/// # use fn_cache::LazyCache;
+	/// let mut cache = LazyCache::<usize, usize>::new(|x| *x);
+	///
+	/// cache.insert_lazy(1, || 10);
+	/// cache.insert_lazy(2, || 20);
+	///
+	/// cache.force_all_parallel();
+	///
+	/// assert!(!cache.cache().is_pending(&1));
+	/// assert_eq!(cache.cache().inner().get(&2), Some(&20));
+	/// ```
+	#[cfg(feature = "rayon")]
+	pub fn force_all_parallel(&mut self)
+	where
+		C::Input: Send,
+		C::Output: Send,
+	{
+		use rayon::prelude::*;
+
+		let thunks: Vec<_> = self.cache.thunks.drain().collect();
+
+		let outputs: Vec<_> = thunks
+			.into_par_iter()
+			.map(|(input, thunk)| (input, thunk()))
+			.collect();
+
+		for (input, output) in outputs {
+			self.cache.inner.put(input, output);
+		}
+	}
+}
@@ -0,0 +1,227 @@
+use std::collections::{BTreeMap, HashMap};
+
+use core::hash::Hash;
+
+use crate::container::{
+	ContainerCapacity, ContainerClear, ContainerEvict, ContainerIter, ContainerLen,
+	ContainerRemove, SparseContainer,
+};
+use crate::generic_cache::{GenericCache, RefCache};
+
+/// A cache which holds at most a fixed number of values, evicting the least frequently used value
+/// to make room for a new one.
+///
+/// Each value counts how many times it was read, including when it was computed and including
+/// hits made by a recursive function. Values with the same count are evicted least recently used
+/// first. Reading a value, putting a value and evicting one are all `O(log n)`.
+///
+/// Values may be evicted while a recursive function is still running, so a recursive function
+/// should not rely on holding more values than the capacity. For the same reason,
+/// [`FnCacheMany::get_many`](crate::FnCacheMany::get_many),
+/// [`FnCacheMany::get_many_vec`](crate::FnCacheMany::get_many_vec) and
+/// [`FnCacheMany::get_two`](crate::FnCacheMany::get_two) will panic if asked for more inputs than
+/// fit in the capacity.
+///
+/// ```
+/// # use fn_cache::{FnCache, LfuCache};
+/// let mut cache = LfuCache::<usize, usize>::with_capacity(2, |x| x * x);
+///
+/// cache.get(1);
+/// cache.get(1);
+/// cache.get(2);
+/// cache.get(3);
+///
+/// assert!(cache.cache().contains(&1));
+/// assert!(!cache.cache().contains(&2));
+/// ```
+pub type LfuCache<'f, I, O> = GenericCache<'f, Lfu<I, O>>;
+
+/// A [`SparseContainer`] backed by a [`HashMap`] with a fixed capacity, which evicts the least
+/// frequently used value when full.
+pub struct Lfu<I, O> {
+	map: HashMap<I, (O, u64, u64)>,
+	order: BTreeMap<(u64, u64), I>,
+	tick: u64,
+	capacity: usize,
+}
+
+impl<I, O> Lfu<I, O>
+where
+	I: Eq + Hash + Clone,
+{
+	/// Create an empty container holding at most `capacity` values.
+	///
+	/// # Panics
+	/// If `capacity` is zero.
+	pub fn with_capacity(capacity: usize) -> Self {
+		assert!(capacity > 0, "Lfu capacity must be non-zero");
+
+		Self {
+			map: HashMap::with_capacity(capacity),
+			order: BTreeMap::new(),
+			tick: 0,
+			capacity,
+		}
+	}
+
+	/// Returns the most values the container will hold.
+	pub fn capacity(&self) -> usize {
+		self.capacity
+	}
+
+	/// Returns true if the container holds a value for `input`, without counting it as a use.
+	pub fn contains(&self, input: &I) -> bool {
+		self.map.contains_key(input)
+	}
+
+	/// Returns the number of times the value for `input` has been used, or `None` if it is not in
+	/// the container.
+	pub fn uses(&self, input: &I) -> Option<u64> {
+		self.map.get(input).map(|(_, uses, _)| *uses)
+	}
+
+	fn next_tick(&mut self) -> u64 {
+		self.tick += 1;
+		self.tick
+	}
+}
+
+impl<I, O> SparseContainer for Lfu<I, O>
+where
+	I: Eq + Hash + Clone,
+{
+	type Input = I;
+	type Output = O;
+
+	fn has(&self, input: &I) -> bool {
+		self.map.contains_key(input)
+	}
+
+	fn get(&self, input: &I) -> Option<&O> {
+		self.map.get(input).map(|(o, _, _)| o)
+	}
+
+	fn put(&mut self, input: I, output: O) -> &O {
+		self.put_evicting(input, output).0
+	}
+
+	fn touch(&mut self, input: &I) {
+		let tick = self.next_tick();
+
+		if let Some((_, uses, last)) = self.map.get_mut(input) {
+			let key = self.order.remove(&(*uses, *last)).unwrap();
+
+			*uses += 1;
+			*last = tick;
+
+			self.order.insert((*uses, tick), key);
+		}
+	}
+}
+
+impl<I, O> ContainerEvict for Lfu<I, O>
+where
+	I: Eq + Hash + Clone,
+{
+	fn put_evicting(&mut self, input: I, output: O) -> (&O, Option<(I, O)>) {
+		let mut evicted = None;
+
+		if !self.map.contains_key(&input) {
+			if self.map.len() >= self.capacity {
+				if let Some((_, least)) = self.order.pop_first() {
+					evicted = self.map.remove_entry(&least).map(|(i, (o, _, _))| (i, o));
+				}
+			}
+
+			let tick = self.next_tick();
+
+			self.order.insert((1, tick), input.clone());
+			self.map.insert(input.clone(), (output, 1, tick));
+		}
+
+		(&self.map[&input].0, evicted)
+	}
+
+	fn would_evict(&self, input: &I) -> Option<&I> {
+		if self.map.contains_key(input) || self.map.len() < self.capacity {
+			return None;
+		}
+
+		self.order.first_key_value().map(|(_, least)| least)
+	}
+}
+
+impl<I, O> ContainerCapacity for Lfu<I, O>
+where
+	I: Eq + Hash + Clone,
+{
+	fn capacity(&self) -> usize {
+		self.capacity
+	}
+}
+
+impl<I, O> ContainerLen for Lfu<I, O>
+where
+	I: Eq + Hash + Clone,
+{
+	fn len(&self) -> usize {
+		self.map.len()
+	}
+}
+
+impl<I, O> ContainerClear for Lfu<I, O>
+where
+	I: Eq + Hash + Clone,
+{
+	fn clear(&mut self) {
+		self.map.clear();
+		self.order.clear();
+	}
+}
+
+impl<I, O> ContainerRemove for Lfu<I, O>
+where
+	I: Eq + Hash + Clone,
+{
+	fn remove(&mut self, input: &I) -> Option<O> {
+		let (output, uses, last) = self.map.remove(input)?;
+		self.order.remove(&(uses, last));
+		Some(output)
+	}
+}
+
+impl<I, O> ContainerIter for Lfu<I, O>
+where
+	I: Eq + Hash + Clone,
+{
+	fn iter(&self) -> impl Iterator<Item = (&I, &O)> {
+		self.map.iter().map(|(i, (o, _, _))| (i, o))
+	}
+}
+
+impl<'f, I, O> GenericCache<'f, Lfu<I, O>>
+where
+	I: Eq + Hash + Clone,
+{
+	/// Create a cache holding at most `capacity` values, for the provided function.
+	///
+	/// # Panics
+	/// If `capacity` is zero.
+	pub fn with_capacity(capacity: usize, f: impl Fn(&I) -> O + Send + Sync + 'f) -> Self {
+		Self::with_cache(Lfu::with_capacity(capacity), f)
+	}
+
+	/// Create a cache holding at most `capacity` values, for the provided recursive function.
+	///
+	/// Values may be evicted while the function is still running, as with
+	/// [`LruCache`](crate::LruCache).
+	///
+	/// # Panics
+	/// If `capacity` is zero.
+	pub fn recursive_with_capacity(
+		capacity: usize,
+		f: impl Fn(&mut RefCache<Lfu<I, O>>, &I) -> O + Send + Sync + 'f,
+	) -> Self {
+		Self::recursive_with_cache(Lfu::with_capacity(capacity), f)
+	}
+}
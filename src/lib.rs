@@ -139,18 +139,82 @@
 //! [fn primitive]: https://doc.rust-lang.org/std/primitive.fn.html
 //! [`Rc`]: std::rc::Rc
 //! [num]: https://docs.rs/num/
+pub mod acc_cache;
+#[cfg(feature = "async")]
+pub mod async_cache;
 pub mod btree_cache;
+pub mod bucket_cache;
+pub mod budget_cache;
+pub mod builder_cache;
+pub mod checked_cache;
+#[cfg(feature = "compress")]
+pub mod compressed_cache;
 pub mod container;
+pub mod counted_cache;
+pub mod deadline_cache;
+pub mod deps_cache;
+pub mod direct_cache;
+pub mod dispatch_cache;
 pub mod fn_cache;
+pub mod frozen_cache;
+pub mod fuel_cache;
 pub mod generic_cache;
 pub mod hash_cache;
+#[cfg(feature = "indexmap")]
+pub mod index_cache;
+pub mod interning_cache;
+#[cfg(feature = "interval")]
+pub mod interval_cache;
+pub mod iter;
+pub mod lazy_cache;
+pub mod lfu_cache;
+pub mod limit_cache;
+pub mod local_cache;
+mod lock;
+#[cfg(feature = "lru")]
+pub mod lru_adapter;
+pub mod lru_cache;
+pub mod merge_cache;
+pub mod prehashed_cache;
+pub mod priority_cache;
+#[cfg(feature = "debug_trace")]
+pub mod provenance_cache;
+#[cfg(feature = "ratelimit")]
+pub mod ratelimit_cache;
+pub mod replay_cache;
+#[cfg(feature = "retry")]
+pub mod retry_cache;
+pub mod spill_cache;
+#[cfg(feature = "stats")]
+pub mod stats_cache;
+pub mod sync_cache;
+pub mod try_cache;
+pub mod ttl_cache;
+pub mod validated_cache;
 pub mod vec_cache;
+pub mod weak_cache;
 
 #[cfg(test)]
 mod tests;
 
 pub use crate::btree_cache::BTreeCache;
-pub use crate::fn_cache::{FnCache, FnCacheMany};
+pub use crate::budget_cache::BudgetCache;
+pub use crate::counted_cache::CountedCache;
+pub use crate::deps_cache::DepsCache;
+pub use crate::direct_cache::DirectCache;
+pub use crate::fn_cache::{FnCache, FnCacheMany, FnCachePeek, TryFnCache};
 pub use crate::generic_cache::GenericCache;
 pub use crate::hash_cache::HashCache;
+pub use crate::interning_cache::InterningCache;
+pub use crate::iter::MemoizedIteratorExt;
+pub use crate::lazy_cache::LazyCache;
+pub use crate::lfu_cache::LfuCache;
+pub use crate::lru_cache::LruCache;
+pub use crate::priority_cache::PriorityCache;
+#[cfg(feature = "debug_trace")]
+pub use crate::provenance_cache::ProvenanceCache;
+pub use crate::replay_cache::ReplayCache;
+#[cfg(feature = "stats")]
+pub use crate::stats_cache::StatsCache;
+pub use crate::ttl_cache::TtlCache;
 pub use crate::vec_cache::VecCache;
@@ -0,0 +1,126 @@
+use std::fmt;
+
+use crate::container::{ContainerLen, SparseContainer};
+use crate::try_cache::{Allowance, TryCache, TryRefCache};
+
+/// The error returned when a [`LimitCache`] would hold more entries than allowed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TooManyEntries;
+
+impl fmt::Display for TooManyEntries {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str("cache computation exceeded its entry limit")
+	}
+}
+
+impl std::error::Error for TooManyEntries {}
+
+/// An [`Allowance`] of entries the cache may hold while computing, counting the values which are
+/// still being computed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EntryLimit(pub usize);
+
+/// The state of an [`EntryLimit`] during a single call to [`TryCache::try_get`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EntryLimitState {
+	max_entries: usize,
+	pending: usize,
+}
+
+impl<C, E> Allowance<C, E> for EntryLimit
+where
+	C: ContainerLen,
+	E: From<TooManyEntries>,
+{
+	type State = EntryLimitState;
+
+	fn start(&self) -> EntryLimitState {
+		EntryLimitState {
+			max_entries: self.0,
+			pending: 0,
+		}
+	}
+
+	fn check(state: &mut EntryLimitState, cache: &C) -> Result<(), E> {
+		if cache.len() + state.pending >= state.max_entries {
+			Err(TooManyEntries.into())
+		} else {
+			state.pending += 1;
+			Ok(())
+		}
+	}
+
+	fn finish(state: &mut EntryLimitState) {
+		state.pending -= 1;
+	}
+}
+
+/// A cache for a recursive function which gives up once the cache would hold too many entries.
+///
+/// This protects against inputs which would make a recursive function compute an unreasonable
+/// number of values, exhausting memory. Values which are still being computed count as entries,
+/// so this also limits the depth of recursion. Once the cache holds `max_entries_during_compute`
+/// entries, any value not already in the cache returns [`TooManyEntries`] instead of being
+/// computed, which the function propagates using `?` through the [`LimitRefCache`]. This aborts
+/// the whole computation, rather than only the call that ran over.
+///
+/// Values which were computed before the limit was reached remain in the cache.
+///
+/// ```
+/// # use fn_cache::limit_cache::{LimitCache, TooManyEntries};
+/// # use std::collections::HashMap;
+/// let mut cache = LimitCache::<HashMap<u64, u64>>::recursive(20, |cache, x| match x {
+///     0 => Ok(0),
+///     1 => Ok(1),
+///     _ => Ok(*cache.try_get(x - 1)? + *cache.try_get(x - 2)?),
+/// });
+///
+/// assert_eq!(cache.try_get(10), Ok(&55));
+/// assert_eq!(cache.try_get(100), Err(TooManyEntries));
+/// ```
+pub type LimitCache<'f, C, E = TooManyEntries> = TryCache<'f, C, E, EntryLimit>;
+
+/// The cache passed to the function of a [`LimitCache`], sharing the entry limit of the
+/// top-level call.
+pub type LimitRefCache<'c, C, E = TooManyEntries> = TryRefCache<'c, C, E, EntryLimit>;
+
+impl<'f, C, E> LimitCache<'f, C, E>
+where
+	C: SparseContainer + ContainerLen,
+	E: From<TooManyEntries>,
+{
+	/// Create a `LimitCache` out of a cache, the most entries it may hold, and a recursive
+	/// function.
+	pub fn recursive_with_cache(
+		cache: C,
+		max_entries_during_compute: usize,
+		f: impl Fn(&mut LimitRefCache<C, E>, &C::Input) -> Result<C::Output, E> + Send + 'f,
+	) -> Self {
+		Self::try_recursive_with_allowance(cache, EntryLimit(max_entries_during_compute), f)
+	}
+
+	/// Returns the most entries the cache may hold before computing stops.
+	pub fn max_entries_during_compute(&self) -> usize {
+		self.allowance().0
+	}
+
+	/// Sets the most entries the cache may hold before computing stops.
+	pub fn set_max_entries_during_compute(&mut self, max_entries: usize) {
+		self.allowance_mut().0 = max_entries;
+	}
+}
+
+impl<'f, C, E> LimitCache<'f, C, E>
+where
+	C: SparseContainer + ContainerLen + Default,
+	E: From<TooManyEntries>,
+{
+	/// Create a `LimitCache` using the `Default` implementation of the container, the most
+	/// entries it may hold, and a recursive function.
+	pub fn recursive(
+		max_entries_during_compute: usize,
+		f: impl Fn(&mut LimitRefCache<C, E>, &C::Input) -> Result<C::Output, E> + Send + 'f,
+	) -> Self {
+		Self::recursive_with_cache(Default::default(), max_entries_during_compute, f)
+	}
+}
@@ -0,0 +1,203 @@
+use std::collections::HashMap;
+
+use crate::container::{ContainerClear, ContainerLen, ContainerRemove, SparseContainer};
+use crate::{FnCache, FnCacheMany, FnCachePeek};
+
+/// A cache for a function using a [`HashMap`], whose function does not need to be [`Send`] or
+/// [`Sync`].
+///
+/// See [`LocalGenericCache`].
+pub type LocalHashCache<'f, I, O> = LocalGenericCache<'f, HashMap<I, O>>;
+
+/// A version of [`GenericCache`](crate::GenericCache) for single threaded use, whose function
+/// does not need to be [`Send`] or [`Sync`].
+///
+/// This allows the function to capture values such as an [`Rc`](std::rc::Rc) or a
+/// [`RefCell`](std::cell::RefCell), at the cost of the cache not being able to move between
+/// threads.
+///
+/// ```
+/// # use fn_cache::FnCache;
+/// # use fn_cache::local_cache::LocalHashCache;
+/// # use std::cell::RefCell;
+/// # use std::rc::Rc;
+/// let log = Rc::new(RefCell::new(Vec::new()));
+/// let calls = log.clone();
+///
+/// let mut cache = LocalHashCache::<usize, usize>::new(move |x| {
+///     calls.borrow_mut().push(*x);
+///     x * x
+/// });
+///
+/// cache.get(2);
+/// cache.get(2);
+///
+/// assert_eq!(*log.borrow(), [2]);
+/// ```
+pub struct LocalGenericCache<'f, C: SparseContainer> {
+	cache: C,
+	#[allow(clippy::type_complexity)]
+	f: Box<dyn Fn(&mut LocalRefCache<C>, &C::Input) -> C::Output + 'f>,
+}
+
+impl<'f, C: SparseContainer> LocalGenericCache<'f, C> {
+	/// Create a `LocalGenericCache` out of a cache and a function.
+	pub fn with_cache(cache: C, f: impl Fn(&C::Input) -> C::Output + 'f) -> Self {
+		Self::recursive_with_cache(cache, move |_, i| f(i))
+	}
+
+	/// Create a `LocalGenericCache` out of a cache and a recursive function.
+	pub fn recursive_with_cache(
+		cache: C,
+		f: impl Fn(&mut LocalRefCache<C>, &C::Input) -> C::Output + 'f,
+	) -> Self {
+		Self {
+			cache,
+			f: Box::new(f),
+		}
+	}
+
+	/// Get a reference to the underlying cache object.
+	pub fn cache(&self) -> &C {
+		&self.cache
+	}
+
+	fn compute(&mut self, input: C::Input) -> &C::Output {
+		self.cache.begin(&input);
+		let mut ref_cache = LocalRefCache {
+			cache: &mut self.cache,
+			f: self.f.as_ref(),
+		};
+		let output = (self.f)(&mut ref_cache, &input);
+		self.cache.put(input, output)
+	}
+}
+
+impl<'f, C> LocalGenericCache<'f, C>
+where
+	C: SparseContainer + Default,
+{
+	/// Create a `LocalGenericCache` using the `Default` implementation of the container.
+	pub fn new(f: impl Fn(&C::Input) -> C::Output + 'f) -> Self {
+		Self::with_cache(Default::default(), f)
+	}
+
+	/// Create a `LocalGenericCache` using the `Default` implementation of the container, using a
+	/// recursive function.
+	pub fn recursive(f: impl Fn(&mut LocalRefCache<C>, &C::Input) -> C::Output + 'f) -> Self {
+		Self::recursive_with_cache(Default::default(), f)
+	}
+}
+
+impl<'f, C: SparseContainer + ContainerLen> LocalGenericCache<'f, C> {
+	/// Returns the number of elements in the cache.
+	pub fn len(&self) -> usize {
+		self.cache.len()
+	}
+
+	/// Returns true if the cache holds no elements.
+	pub fn is_empty(&self) -> bool {
+		self.cache.is_empty()
+	}
+}
+
+impl<'f, C: SparseContainer + ContainerClear> LocalGenericCache<'f, C> {
+	/// Clears the cache, removing all key-value pairs.
+	/// Keeps the allocated memory for reuse.
+	pub fn clear(&mut self) {
+		self.cache.clear()
+	}
+}
+
+impl<'f, C: ContainerRemove> LocalGenericCache<'f, C> {
+	/// Removes the input from the cache, returning any value
+	/// if the input was previously in the cache.
+	pub fn remove(&mut self, input: &C::Input) -> Option<C::Output> {
+		self.cache.remove(input)
+	}
+}
+
+impl<'f, C: SparseContainer> FnCache<C::Input, C::Output> for LocalGenericCache<'f, C> {
+	fn get(&mut self, input: C::Input) -> &C::Output {
+		if self.cache.has(&input) {
+			self.cache.touch(&input);
+			self.cache.get(&input).unwrap()
+		} else {
+			self.compute(input)
+		}
+	}
+}
+
+impl<'f, C: SparseContainer> FnCachePeek<C::Input, C::Output> for LocalGenericCache<'f, C> {
+	fn peek(&self, input: &C::Input) -> Option<&C::Output> {
+		self.cache.get(input)
+	}
+
+	fn contains(&self, input: &C::Input) -> bool {
+		self.cache.has(input)
+	}
+}
+
+impl<'f, C> FnCacheMany<C::Input, C::Output> for LocalGenericCache<'f, C>
+where
+	C: SparseContainer,
+	C::Input: Clone,
+{
+	fn get_many<const N: usize>(&mut self, inputs: [C::Input; N]) -> [&C::Output; N] {
+		for i in &inputs {
+			self.get(i.clone());
+		}
+
+		inputs.map(|i| self.cache.get(&i).unwrap())
+	}
+}
+
+/// The cache passed to the function of a [`LocalGenericCache`].
+pub struct LocalRefCache<'c, C: SparseContainer> {
+	cache: &'c mut C,
+	#[allow(clippy::type_complexity)]
+	f: &'c dyn Fn(&mut Self, &C::Input) -> C::Output,
+}
+
+impl<'c, C: SparseContainer> LocalRefCache<'c, C> {
+	fn compute(&mut self, input: C::Input) -> &C::Output {
+		self.cache.begin(&input);
+		let output = (self.f)(self, &input);
+		self.cache.put(input, output)
+	}
+}
+
+impl<'c, C: SparseContainer> FnCache<C::Input, C::Output> for LocalRefCache<'c, C> {
+	fn get(&mut self, input: C::Input) -> &C::Output {
+		if self.cache.has(&input) {
+			self.cache.touch(&input);
+			self.cache.get(&input).unwrap()
+		} else {
+			self.compute(input)
+		}
+	}
+}
+
+impl<'c, C: SparseContainer> FnCachePeek<C::Input, C::Output> for LocalRefCache<'c, C> {
+	fn peek(&self, input: &C::Input) -> Option<&C::Output> {
+		self.cache.get(input)
+	}
+
+	fn contains(&self, input: &C::Input) -> bool {
+		self.cache.has(input)
+	}
+}
+
+impl<'c, C> FnCacheMany<C::Input, C::Output> for LocalRefCache<'c, C>
+where
+	C: SparseContainer,
+	C::Input: Clone,
+{
+	fn get_many<const N: usize>(&mut self, inputs: [C::Input; N]) -> [&C::Output; N] {
+		for i in &inputs {
+			self.get(i.clone());
+		}
+
+		inputs.map(|i| self.cache.get(&i).unwrap())
+	}
+}
@@ -0,0 +1,101 @@
+//! The locks used inside the caches which can be shared between threads.
+//!
+//! With the `parking_lot` feature, these are the locks from [`parking_lot`], which are never
+//! poisoned. Otherwise they are the locks from [`std::sync`], and locking one which was poisoned
+//! by a panic panics as well. Either way, the caches use them in exactly the same way.
+
+#[cfg(feature = "parking_lot")]
+use parking_lot as imp;
+#[cfg(not(feature = "parking_lot"))]
+use std::sync as imp;
+
+#[cfg(any(feature = "async", feature = "ratelimit"))]
+pub(crate) type MutexGuard<'a, T> = imp::MutexGuard<'a, T>;
+pub(crate) type RwLockReadGuard<'a, T> = imp::RwLockReadGuard<'a, T>;
+pub(crate) type RwLockWriteGuard<'a, T> = imp::RwLockWriteGuard<'a, T>;
+
+/// A mutual exclusion lock, which returns its guard directly instead of a [`LockResult`].
+///
+/// [`LockResult`]: std::sync::LockResult
+#[cfg(any(feature = "async", feature = "ratelimit"))]
+#[derive(Default)]
+pub(crate) struct Mutex<T>(imp::Mutex<T>);
+
+#[cfg(any(feature = "async", feature = "ratelimit"))]
+impl<T> Mutex<T> {
+	#[cfg(feature = "ratelimit")]
+	pub(crate) fn new(value: T) -> Self {
+		Self(imp::Mutex::new(value))
+	}
+
+	/// Locks the mutex, blocking until it is available.
+	///
+	/// # Panics
+	/// Without the `parking_lot` feature, if the mutex was poisoned.
+	pub(crate) fn lock(&self) -> MutexGuard<'_, T> {
+		#[cfg(feature = "parking_lot")]
+		return self.0.lock();
+
+		#[cfg(not(feature = "parking_lot"))]
+		self.0.lock().unwrap()
+	}
+
+	/// Returns a mutable reference to the value, which needs no locking since the mutex is
+	/// borrowed mutably.
+	///
+	/// # Panics
+	/// Without the `parking_lot` feature, if the mutex was poisoned.
+	#[cfg(feature = "async")]
+	pub(crate) fn get_mut(&mut self) -> &mut T {
+		#[cfg(feature = "parking_lot")]
+		return self.0.get_mut();
+
+		#[cfg(not(feature = "parking_lot"))]
+		self.0.get_mut().unwrap()
+	}
+
+	/// Locks the mutex, blocking until it is available, or returns `None` if it was poisoned.
+	///
+	/// This is for cleaning up while a panic is already unwinding, where panicking again would
+	/// abort.
+	#[cfg(feature = "async")]
+	pub(crate) fn lock_unpoisoned(&self) -> Option<MutexGuard<'_, T>> {
+		#[cfg(feature = "parking_lot")]
+		return Some(self.0.lock());
+
+		#[cfg(not(feature = "parking_lot"))]
+		self.0.lock().ok()
+	}
+}
+
+/// A reader-writer lock, which returns its guards directly instead of a [`LockResult`].
+///
+/// [`LockResult`]: std::sync::LockResult
+#[derive(Default)]
+pub(crate) struct RwLock<T>(imp::RwLock<T>);
+
+impl<T> RwLock<T> {
+	/// Locks for shared reading, blocking until there are no writers.
+	///
+	/// # Panics
+	/// Without the `parking_lot` feature, if the lock was poisoned.
+	pub(crate) fn read(&self) -> RwLockReadGuard<'_, T> {
+		#[cfg(feature = "parking_lot")]
+		return self.0.read();
+
+		#[cfg(not(feature = "parking_lot"))]
+		self.0.read().unwrap()
+	}
+
+	/// Locks for exclusive writing, blocking until there are no readers or writers.
+	///
+	/// # Panics
+	/// Without the `parking_lot` feature, if the lock was poisoned.
+	pub(crate) fn write(&self) -> RwLockWriteGuard<'_, T> {
+		#[cfg(feature = "parking_lot")]
+		return self.0.write();
+
+		#[cfg(not(feature = "parking_lot"))]
+		self.0.write().unwrap()
+	}
+}
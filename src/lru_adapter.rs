@@ -0,0 +1,89 @@
+use core::cmp::Eq;
+use core::hash::{BuildHasher, Hash};
+
+use lru::LruCache;
+
+use crate::container::{ContainerClear, ContainerLen, ContainerRemove, SparseContainer};
+
+/// Allows an [`LruCache`] from the [`lru`] crate to be used in a
+/// [`GenericCache`](crate::GenericCache).
+///
+/// Each hit in the cache marks the input as most recently used, and once the `LruCache` is full,
+/// putting a new value evicts the least recently used one.
+///
+/// Values may be evicted while a recursive function is still running, so a recursive function
+/// should not rely on holding more values than the capacity. For the same reason,
+/// [`FnCacheMany::get_many`](crate::FnCacheMany::get_many) will panic if asked for more inputs
+/// than fit in the capacity.
+///
+/// ```
+/// # use fn_cache::{FnCache, GenericCache};
+/// # use lru::LruCache;
+/// # use std::num::NonZeroUsize;
+/// let mut cache = GenericCache::with_cache(
+///     LruCache::new(NonZeroUsize::new(2).unwrap()),
+///     |x: &usize| x * x,
+/// );
+///
+/// cache.get(1);
+/// cache.get(2);
+/// cache.get(1);
+/// cache.get(3);
+///
+/// assert!(cache.cache().contains(&1));
+/// assert!(!cache.cache().contains(&2));
+/// ```
+impl<I, O, S> SparseContainer for LruCache<I, O, S>
+where
+	I: Eq + Hash,
+	S: BuildHasher,
+{
+	type Input = I;
+	type Output = O;
+
+	fn has(&self, input: &I) -> bool {
+		self.contains(input)
+	}
+
+	fn get(&self, input: &I) -> Option<&O> {
+		self.peek(input)
+	}
+
+	fn put(&mut self, input: I, output: O) -> &O {
+		self.get_or_insert(input, || output)
+	}
+
+	fn touch(&mut self, input: &I) {
+		self.promote(input)
+	}
+}
+
+impl<I, O, S> ContainerLen for LruCache<I, O, S>
+where
+	I: Eq + Hash,
+	S: BuildHasher,
+{
+	fn len(&self) -> usize {
+		self.len()
+	}
+}
+
+impl<I, O, S> ContainerClear for LruCache<I, O, S>
+where
+	I: Eq + Hash,
+	S: BuildHasher,
+{
+	fn clear(&mut self) {
+		self.clear()
+	}
+}
+
+impl<I, O, S> ContainerRemove for LruCache<I, O, S>
+where
+	I: Eq + Hash,
+	S: BuildHasher,
+{
+	fn remove(&mut self, input: &I) -> Option<O> {
+		self.pop(input)
+	}
+}
@@ -0,0 +1,341 @@
+use std::collections::{BTreeMap, HashMap};
+
+use core::hash::Hash;
+
+use crate::container::{
+	ContainerCapacity, ContainerClear, ContainerEvict, ContainerIter, ContainerLen,
+	ContainerRemove, SparseContainer,
+};
+use crate::generic_cache::{GenericCache, RefCache};
+
+/// A cache which holds at most a fixed number of values, evicting the least recently used value
+/// to make room for a new one.
+///
+/// Each hit marks the input as most recently used, including hits made by a recursive function.
+///
+/// Values may be evicted while a recursive function is still running, so a recursive function
+/// should not rely on holding more values than the capacity. For the same reason,
+/// [`FnCacheMany::get_many`](crate::FnCacheMany::get_many) will panic if asked for more inputs
+/// than fit in the capacity.
+///
+/// ```
+/// # use fn_cache::{FnCache, LruCache};
+/// let mut cache = LruCache::<usize, usize>::with_capacity(2, |x| x * x);
+///
+/// cache.get(1);
+/// cache.get(2);
+/// cache.get(1);
+/// cache.get(3);
+///
+/// assert!(cache.cache().contains(&1));
+/// assert!(!cache.cache().contains(&2));
+/// ```
+pub type LruCache<'f, I, O> = GenericCache<'f, Lru<I, O>>;
+
+type EvictFn<I, O> = Box<dyn FnMut(I, O) + Send>;
+
+/// A [`SparseContainer`] backed by a [`HashMap`] with a fixed capacity, which evicts the least
+/// recently used value when full.
+///
+/// A function can be set using [`Self::on_evict`] to be given each evicted value, such as to
+/// move it into a slower cache. A value taken out using [`ContainerRemove::remove`] is returned
+/// to the caller instead, unless enabled using [`Self::evict_on_remove`].
+pub struct Lru<I, O> {
+	map: HashMap<I, (O, u64)>,
+	order: BTreeMap<u64, I>,
+	tick: u64,
+	capacity: usize,
+	on_evict: Option<EvictFn<I, O>>,
+	evict_on_clear: bool,
+	evict_on_remove: bool,
+}
+
+impl<I, O> Lru<I, O>
+where
+	I: Eq + Hash + Clone,
+{
+	/// Create an empty container holding at most `capacity` values.
+	///
+	/// # Panics
+	/// If `capacity` is zero.
+	pub fn with_capacity(capacity: usize) -> Self {
+		assert!(capacity > 0, "Lru capacity must be non-zero");
+
+		Self {
+			map: HashMap::with_capacity(capacity),
+			order: BTreeMap::new(),
+			tick: 0,
+			capacity,
+			on_evict: None,
+			evict_on_clear: true,
+			evict_on_remove: false,
+		}
+	}
+
+	/// Sets a function to be called with each input and output evicted from the container.
+	///
+	/// The function is called when a value is evicted to make room for a new one, and for every
+	/// value when the container is cleared, unless disabled using [`Self::evict_on_clear`]. A
+	/// value taken out using [`ContainerRemove::remove`] is only passed to it if enabled using
+	/// [`Self::evict_on_remove`].
+	///
+	/// ```
+	/// # use fn_cache::{FnCache, GenericCache};
+	/// # use fn_cache::lru_cache::Lru;
+	/// # use std::sync::{Arc, Mutex};
+	/// let evicted = Arc::new(Mutex::new(Vec::new()));
+	/// let log = evicted.clone();
+	///
+	/// let mut cache = GenericCache::with_cache(
+	///     Lru::with_capacity(1).on_evict(move |i, o| log.lock().unwrap().push((i, o))),
+	///     |x: &usize| x * x,
+	/// );
+	///
+	/// cache.get(2);
+	/// cache.get(3);
+	///
+	/// assert_eq!(*evicted.lock().unwrap(), [(2, 4)]);
+	/// ```
+	pub fn on_evict(mut self, f: impl FnMut(I, O) + Send + 'static) -> Self {
+		self.on_evict = Some(Box::new(f));
+		self
+	}
+
+	/// Sets whether clearing the container passes every value to the function set by
+	/// [`Self::on_evict`]. This is enabled by default.
+	pub fn evict_on_clear(mut self, evict_on_clear: bool) -> Self {
+		self.evict_on_clear = evict_on_clear;
+		self
+	}
+
+	/// Sets whether [`ContainerRemove::remove`] passes the removed value to the function set by
+	/// [`Self::on_evict`]. This is disabled by default.
+	///
+	/// Since a value can only be given to one of them, once enabled, `remove` returns `None`
+	/// whenever a function is set, instead of the removed value.
+	///
+	/// ```
+	/// # use fn_cache::container::{ContainerRemove, SparseContainer};
+	/// # use fn_cache::lru_cache::Lru;
+	/// # use std::sync::{Arc, Mutex};
+	/// let evicted = Arc::new(Mutex::new(Vec::new()));
+	/// let log = evicted.clone();
+	///
+	/// let mut lru = Lru::with_capacity(2)
+	///     .on_evict(move |i, o| log.lock().unwrap().push((i, o)))
+	///     .evict_on_remove(true);
+	///
+	/// lru.put(1, 10);
+	///
+	/// assert_eq!(lru.remove(&1), None);
+	/// assert_eq!(*evicted.lock().unwrap(), [(1, 10)]);
+	/// ```
+	pub fn evict_on_remove(mut self, evict_on_remove: bool) -> Self {
+		self.evict_on_remove = evict_on_remove;
+		self
+	}
+
+	/// Returns the most values the container will hold.
+	pub fn capacity(&self) -> usize {
+		self.capacity
+	}
+
+	/// Returns the fraction of the capacity which is in use, from 0.0 when empty to 1.0 when
+	/// full.
+	pub fn utilization(&self) -> f64 {
+		self.map.len() as f64 / self.capacity as f64
+	}
+
+	/// Returns true if the container holds a value for `input`, without changing its recency.
+	pub fn contains(&self, input: &I) -> bool {
+		self.map.contains_key(input)
+	}
+
+	fn evict(&mut self, input: I, output: O) {
+		if let Some(on_evict) = &mut self.on_evict {
+			on_evict(input, output);
+		}
+	}
+
+	/// Removes the least recently used value if the container is full and `input` is not in it.
+	fn make_room(&mut self, input: &I) -> Option<(I, O)> {
+		if self.map.contains_key(input) || self.map.len() < self.capacity {
+			return None;
+		}
+
+		let (_, oldest) = self.order.pop_first()?;
+		let (input, (output, _)) = self.map.remove_entry(&oldest).unwrap();
+
+		Some((input, output))
+	}
+
+	fn insert(&mut self, input: I, output: O) -> &O {
+		let tick = self.next_tick();
+
+		if let Some((_, old)) = self.map.get(&input) {
+			let old = self.order.remove(old).unwrap();
+			self.order.insert(tick, old);
+		} else {
+			self.order.insert(tick, input.clone());
+		}
+
+		let entry = self.map.entry(input).or_insert((output, tick));
+		entry.1 = tick;
+		&entry.0
+	}
+
+	fn next_tick(&mut self) -> u64 {
+		self.tick += 1;
+		self.tick
+	}
+}
+
+impl<I, O> SparseContainer for Lru<I, O>
+where
+	I: Eq + Hash + Clone,
+{
+	type Input = I;
+	type Output = O;
+
+	fn has(&self, input: &I) -> bool {
+		self.map.contains_key(input)
+	}
+
+	fn get(&self, input: &I) -> Option<&O> {
+		self.map.get(input).map(|(o, _)| o)
+	}
+
+	fn put(&mut self, input: I, output: O) -> &O {
+		if let Some((input, output)) = self.make_room(&input) {
+			self.evict(input, output);
+		}
+
+		self.insert(input, output)
+	}
+
+	fn touch(&mut self, input: &I) {
+		let tick = self.next_tick();
+
+		if let Some((_, last)) = self.map.get_mut(input) {
+			let key = self.order.remove(last).unwrap();
+			self.order.insert(tick, key);
+			*last = tick;
+		}
+	}
+}
+
+impl<I, O> ContainerEvict for Lru<I, O>
+where
+	I: Eq + Hash + Clone,
+{
+	fn put_evicting(&mut self, input: I, output: O) -> (&O, Option<(I, O)>) {
+		let evicted = self.make_room(&input);
+
+		(self.insert(input, output), evicted)
+	}
+
+	fn would_evict(&self, input: &I) -> Option<&I> {
+		if self.map.contains_key(input) || self.map.len() < self.capacity {
+			return None;
+		}
+
+		self.order.first_key_value().map(|(_, oldest)| oldest)
+	}
+}
+
+impl<I, O> ContainerCapacity for Lru<I, O>
+where
+	I: Eq + Hash + Clone,
+{
+	fn capacity(&self) -> usize {
+		self.capacity
+	}
+}
+
+impl<I, O> ContainerLen for Lru<I, O>
+where
+	I: Eq + Hash + Clone,
+{
+	fn len(&self) -> usize {
+		self.map.len()
+	}
+}
+
+impl<I, O> ContainerClear for Lru<I, O>
+where
+	I: Eq + Hash + Clone,
+{
+	fn clear(&mut self) {
+		self.order.clear();
+
+		match &mut self.on_evict {
+			Some(on_evict) if self.evict_on_clear => {
+				for (input, (output, _)) in self.map.drain() {
+					on_evict(input, output);
+				}
+			}
+			_ => self.map.clear(),
+		}
+	}
+}
+
+impl<I, O> ContainerRemove for Lru<I, O>
+where
+	I: Eq + Hash + Clone,
+{
+	/// The value is passed to the function set by [`Lru::on_evict`] instead of being returned if
+	/// enabled using [`Lru::evict_on_remove`].
+	fn remove(&mut self, input: &I) -> Option<O> {
+		let (input, (output, tick)) = self.map.remove_entry(input)?;
+		self.order.remove(&tick);
+
+		match &mut self.on_evict {
+			Some(on_evict) if self.evict_on_remove => {
+				on_evict(input, output);
+				None
+			}
+			_ => Some(output),
+		}
+	}
+}
+
+impl<I, O> ContainerIter for Lru<I, O>
+where
+	I: Eq + Hash + Clone,
+{
+	fn iter(&self) -> impl Iterator<Item = (&I, &O)> {
+		self.map.iter().map(|(i, (o, _))| (i, o))
+	}
+}
+
+impl<'f, I, O> GenericCache<'f, Lru<I, O>>
+where
+	I: Eq + Hash + Clone,
+{
+	/// Returns the fraction of the capacity which is in use, from 0.0 when empty to 1.0 when
+	/// full.
+	///
+	/// See [`Lru::utilization`].
+	pub fn utilization(&self) -> f64 {
+		self.cache.utilization()
+	}
+
+	/// Create a cache holding at most `capacity` values, for the provided function.
+	///
+	/// # Panics
+	/// If `capacity` is zero.
+	pub fn with_capacity(capacity: usize, f: impl Fn(&I) -> O + Send + Sync + 'f) -> Self {
+		Self::with_cache(Lru::with_capacity(capacity), f)
+	}
+
+	/// Create a cache holding at most `capacity` values, for the provided recursive function.
+	///
+	/// # Panics
+	/// If `capacity` is zero.
+	pub fn recursive_with_capacity(
+		capacity: usize,
+		f: impl Fn(&mut RefCache<Lru<I, O>>, &I) -> O + Send + Sync + 'f,
+	) -> Self {
+		Self::recursive_with_cache(Lru::with_capacity(capacity), f)
+	}
+}
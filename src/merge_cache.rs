@@ -0,0 +1,122 @@
+use crate::container::{
+	ContainerClear, ContainerIter, ContainerLen, ContainerRemove, SparseContainer,
+};
+
+/// A [`SparseContainer`] which decides what to store when a value is put for an input which is
+/// already present.
+///
+/// By default, containers keep the existing value and discard the new one. Wrapping a container
+/// in `Merge` instead stores the result of `merge(&existing, new)`, allowing min, max, or
+/// accumulating semantics for algorithms which may compute the same input more than once, such as
+/// relaxation in shortest path problems.
+///
+/// ```
+/// # use fn_cache::container::SparseContainer;
+/// # use fn_cache::merge_cache::Merge;
+/// # use std::collections::HashMap;
+/// let mut container = Merge::new(HashMap::new(), |a: &u32, b: u32| b.min(*a));
+///
+/// container.put(0, 5);
+/// container.put(0, 3);
+/// container.put(0, 4);
+///
+/// assert_eq!(container.get(&0), Some(&3));
+/// ```
+pub struct Merge<C, F> {
+	inner: C,
+	merge: F,
+}
+
+impl<C, F> Merge<C, F>
+where
+	C: ContainerRemove,
+	F: Fn(&C::Output, C::Output) -> C::Output,
+{
+	/// Wrap a container, merging values put for inputs which are already present using `merge`.
+	pub fn new(inner: C, merge: F) -> Self {
+		Self { inner, merge }
+	}
+
+	/// Get a reference to the wrapped container.
+	pub fn inner(&self) -> &C {
+		&self.inner
+	}
+}
+
+impl<C, F> SparseContainer for Merge<C, F>
+where
+	C: ContainerRemove,
+	F: Fn(&C::Output, C::Output) -> C::Output,
+{
+	type Input = C::Input;
+	type Output = C::Output;
+
+	fn has(&self, input: &Self::Input) -> bool {
+		self.inner.has(input)
+	}
+
+	fn get(&self, input: &Self::Input) -> Option<&Self::Output> {
+		self.inner.get(input)
+	}
+
+	fn put(&mut self, input: Self::Input, output: Self::Output) -> &Self::Output {
+		let output = match self.inner.get(&input) {
+			Some(existing) => {
+				let merged = (self.merge)(existing, output);
+				self.inner.remove(&input);
+				merged
+			}
+			None => output,
+		};
+
+		self.inner.put(input, output)
+	}
+
+	fn touch(&mut self, input: &Self::Input) {
+		self.inner.touch(input)
+	}
+
+	fn begin(&mut self, input: &Self::Input) {
+		self.inner.begin(input)
+	}
+}
+
+impl<C, F> ContainerLen for Merge<C, F>
+where
+	C: ContainerRemove + ContainerLen,
+	F: Fn(&C::Output, C::Output) -> C::Output,
+{
+	fn len(&self) -> usize {
+		self.inner.len()
+	}
+}
+
+impl<C, F> ContainerClear for Merge<C, F>
+where
+	C: ContainerRemove + ContainerClear,
+	F: Fn(&C::Output, C::Output) -> C::Output,
+{
+	fn clear(&mut self) {
+		self.inner.clear()
+	}
+}
+
+impl<C, F> ContainerRemove for Merge<C, F>
+where
+	C: ContainerRemove,
+	F: Fn(&C::Output, C::Output) -> C::Output,
+{
+	fn remove(&mut self, input: &Self::Input) -> Option<Self::Output> {
+		self.inner.remove(input)
+	}
+}
+
+impl<C, F> ContainerIter for Merge<C, F>
+where
+	C: ContainerRemove + ContainerIter,
+	F: Fn(&C::Output, C::Output) -> C::Output,
+{
+	fn iter(&self) -> impl Iterator<Item = (&Self::Input, &Self::Output)> {
+		self.inner.iter()
+	}
+}
@@ -0,0 +1,117 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{BuildHasherDefault, Hash, Hasher};
+use std::ops::Deref;
+
+use crate::GenericCache;
+
+/// A cache for keys which are expensive to hash, which hashes each key only once.
+///
+/// A [`HashMap`] hashes a key every time it is looked up, and a single [`FnCache::get`] may look
+/// up a key several times, such as checking for it, then inserting it. Each key is instead
+/// wrapped in a [`HashedKey`], which stores its hash alongside it, and the map uses a
+/// [`PassThroughHasher`] which only reads that stored hash back.
+///
+/// The function is passed the [`HashedKey`], which dereferences to the original key.
+///
+/// ```
+/// # use fn_cache::{FnCache, FnCachePeek};
+/// # use fn_cache::prehashed_cache::{HashedKey, PrehashedCache};
+/// let mut cache = PrehashedCache::<Vec<u64>, u64>::new(|v| v.iter().sum());
+///
+/// assert_eq!(cache.get(HashedKey::new(vec![1, 2, 3])), &6);
+/// assert!(cache.contains(&HashedKey::new(vec![1, 2, 3])));
+/// ```
+///
+/// [`FnCache::get`]: crate::FnCache::get
+pub type PrehashedCache<'f, K, O> =
+	GenericCache<'f, HashMap<HashedKey<K>, O, BuildHasherDefault<PassThroughHasher>>>;
+
+/// A key along with its precomputed hash.
+///
+/// The hash is computed with [`DefaultHasher::new`], which uses fixed keys, so it is the same for
+/// equal keys in every map. This means a [`PrehashedCache`] does not have the protection against
+/// deliberately colliding keys that a [`HashMap`] with a [`RandomState`] has.
+///
+/// [`RandomState`]: std::collections::hash_map::RandomState
+#[derive(Clone, Debug)]
+pub struct HashedKey<K> {
+	hash: u64,
+	key: K,
+}
+
+impl<K: Hash> HashedKey<K> {
+	/// Hash `key` once, and store the hash alongside it.
+	pub fn new(key: K) -> Self {
+		let mut hasher = DefaultHasher::new();
+		key.hash(&mut hasher);
+
+		Self {
+			hash: hasher.finish(),
+			key,
+		}
+	}
+}
+
+impl<K> HashedKey<K> {
+	/// Returns the precomputed hash of the key.
+	pub fn precomputed_hash(&self) -> u64 {
+		self.hash
+	}
+
+	/// Get a reference to the key.
+	pub fn key(&self) -> &K {
+		&self.key
+	}
+
+	/// Unwrap the key, discarding its hash.
+	pub fn into_key(self) -> K {
+		self.key
+	}
+}
+
+impl<K> Deref for HashedKey<K> {
+	type Target = K;
+
+	fn deref(&self) -> &K {
+		&self.key
+	}
+}
+
+impl<K: PartialEq> PartialEq for HashedKey<K> {
+	fn eq(&self, other: &Self) -> bool {
+		self.hash == other.hash && self.key == other.key
+	}
+}
+
+impl<K: Eq> Eq for HashedKey<K> {}
+
+impl<K> Hash for HashedKey<K> {
+	fn hash<H: Hasher>(&self, state: &mut H) {
+		state.write_u64(self.hash)
+	}
+}
+
+/// A [`Hasher`] which returns the hash written to it, for keys which are already hashed, such as
+/// a [`HashedKey`].
+///
+/// Any other bytes written to it are combined, so it still works for other keys, but hashes them
+/// poorly.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PassThroughHasher(u64);
+
+impl Hasher for PassThroughHasher {
+	fn finish(&self) -> u64 {
+		self.0
+	}
+
+	fn write(&mut self, bytes: &[u8]) {
+		for &b in bytes {
+			self.0 = self.0.rotate_left(8) ^ u64::from(b);
+		}
+	}
+
+	fn write_u64(&mut self, i: u64) {
+		self.0 = i;
+	}
+}
@@ -0,0 +1,251 @@
+use std::collections::{BTreeMap, HashMap};
+
+use core::hash::Hash;
+
+use crate::container::{
+	ContainerCapacity, ContainerClear, ContainerEvict, ContainerIter, ContainerLen,
+	ContainerRemove, SparseContainer,
+};
+use crate::generic_cache::{GenericCache, RefCache};
+
+/// A cache which holds at most a fixed number of values, evicting the value with the lowest
+/// priority to make room for a new one.
+///
+/// The priority of each value is computed once, when it is put into the cache, so it should only
+/// depend on the input and output. Putting a value and evicting one are both `O(log n)`, and
+/// priorities which are equal are evicted oldest first.
+///
+/// A new value is always stored, even if its priority is lower than every value already in the
+/// cache, since the cache must return a reference to it. It will be the first evicted to make room
+/// for the next value.
+///
+/// ```
+/// # use fn_cache::{FnCache, PriorityCache};
+/// // keep the values which took the most steps to compute
+/// let mut cache = PriorityCache::<u64, (u64, u32), u32>::with_priority(
+///     2,
+///     |_, (_, steps)| *steps,
+///     |x| (x * x, x.count_ones()),
+/// );
+///
+/// cache.get(7);
+/// cache.get(15);
+/// cache.get(8);
+/// cache.get(9);
+///
+/// assert!(cache.cache().contains(&15));
+/// assert!(cache.cache().contains(&9));
+/// assert!(!cache.cache().contains(&7));
+/// ```
+pub type PriorityCache<'f, I, O, P = u64> = GenericCache<'f, Priority<I, O, P>>;
+
+type PriorityFn<I, O, P> = Box<dyn Fn(&I, &O) -> P + Send + Sync>;
+
+/// A [`SparseContainer`] backed by a [`HashMap`] with a fixed capacity, which evicts the value
+/// with the lowest priority when full.
+pub struct Priority<I, O, P> {
+	map: HashMap<I, (O, P, u64)>,
+	order: BTreeMap<(P, u64), I>,
+	seq: u64,
+	capacity: usize,
+	priority: PriorityFn<I, O, P>,
+}
+
+impl<I, O, P> Priority<I, O, P>
+where
+	I: Eq + Hash + Clone,
+	P: Ord + Clone,
+{
+	/// Create an empty container holding at most `capacity` values, using `priority` to choose
+	/// which value to evict.
+	///
+	/// # Panics
+	/// If `capacity` is zero.
+	pub fn with_capacity(
+		capacity: usize,
+		priority: impl Fn(&I, &O) -> P + Send + Sync + 'static,
+	) -> Self {
+		assert!(capacity > 0, "Priority capacity must be non-zero");
+
+		Self {
+			map: HashMap::with_capacity(capacity),
+			order: BTreeMap::new(),
+			seq: 0,
+			capacity,
+			priority: Box::new(priority),
+		}
+	}
+
+	/// Returns the most values the container will hold.
+	pub fn capacity(&self) -> usize {
+		self.capacity
+	}
+
+	/// Returns the fraction of the capacity which is in use, from 0.0 when empty to 1.0 when
+	/// full.
+	pub fn utilization(&self) -> f64 {
+		self.map.len() as f64 / self.capacity as f64
+	}
+
+	/// Returns true if the container holds a value for `input`.
+	pub fn contains(&self, input: &I) -> bool {
+		self.map.contains_key(input)
+	}
+
+	/// Returns the priority computed for `input` when its value was stored.
+	pub fn priority(&self, input: &I) -> Option<&P> {
+		self.map.get(input).map(|(_, p, _)| p)
+	}
+}
+
+impl<I, O, P> SparseContainer for Priority<I, O, P>
+where
+	I: Eq + Hash + Clone,
+	P: Ord + Clone,
+{
+	type Input = I;
+	type Output = O;
+
+	fn has(&self, input: &I) -> bool {
+		self.map.contains_key(input)
+	}
+
+	fn get(&self, input: &I) -> Option<&O> {
+		self.map.get(input).map(|(o, _, _)| o)
+	}
+
+	fn put(&mut self, input: I, output: O) -> &O {
+		self.put_evicting(input, output).0
+	}
+}
+
+impl<I, O, P> ContainerEvict for Priority<I, O, P>
+where
+	I: Eq + Hash + Clone,
+	P: Ord + Clone,
+{
+	fn put_evicting(&mut self, input: I, output: O) -> (&O, Option<(I, O)>) {
+		let mut evicted = None;
+
+		if !self.map.contains_key(&input) {
+			if self.map.len() >= self.capacity {
+				if let Some((_, lowest)) = self.order.pop_first() {
+					evicted = self.map.remove_entry(&lowest).map(|(i, (o, _, _))| (i, o));
+				}
+			}
+
+			let priority = (self.priority)(&input, &output);
+			self.seq += 1;
+
+			self.order
+				.insert((priority.clone(), self.seq), input.clone());
+			self.map.insert(input.clone(), (output, priority, self.seq));
+		}
+
+		(&self.map[&input].0, evicted)
+	}
+
+	fn would_evict(&self, input: &I) -> Option<&I> {
+		if self.map.contains_key(input) || self.map.len() < self.capacity {
+			return None;
+		}
+
+		self.order.first_key_value().map(|(_, lowest)| lowest)
+	}
+}
+
+impl<I, O, P> ContainerCapacity for Priority<I, O, P>
+where
+	I: Eq + Hash + Clone,
+	P: Ord + Clone,
+{
+	fn capacity(&self) -> usize {
+		self.capacity
+	}
+}
+
+impl<I, O, P> ContainerLen for Priority<I, O, P>
+where
+	I: Eq + Hash + Clone,
+	P: Ord + Clone,
+{
+	fn len(&self) -> usize {
+		self.map.len()
+	}
+}
+
+impl<I, O, P> ContainerClear for Priority<I, O, P>
+where
+	I: Eq + Hash + Clone,
+	P: Ord + Clone,
+{
+	fn clear(&mut self) {
+		self.map.clear();
+		self.order.clear();
+	}
+}
+
+impl<I, O, P> ContainerRemove for Priority<I, O, P>
+where
+	I: Eq + Hash + Clone,
+	P: Ord + Clone,
+{
+	fn remove(&mut self, input: &I) -> Option<O> {
+		let (output, priority, seq) = self.map.remove(input)?;
+		self.order.remove(&(priority, seq));
+		Some(output)
+	}
+}
+
+impl<I, O, P> ContainerIter for Priority<I, O, P>
+where
+	I: Eq + Hash + Clone,
+	P: Ord + Clone,
+{
+	fn iter(&self) -> impl Iterator<Item = (&I, &O)> {
+		self.map.iter().map(|(i, (o, _, _))| (i, o))
+	}
+}
+
+impl<'f, I, O, P> GenericCache<'f, Priority<I, O, P>>
+where
+	I: Eq + Hash + Clone,
+	P: Ord + Clone,
+{
+	/// Returns the fraction of the capacity which is in use, from 0.0 when empty to 1.0 when
+	/// full.
+	///
+	/// See [`Priority::utilization`].
+	pub fn utilization(&self) -> f64 {
+		self.cache.utilization()
+	}
+
+	/// Create a cache holding at most `capacity` values, for the provided function, evicting
+	/// the value with the lowest priority when full.
+	///
+	/// # Panics
+	/// If `capacity` is zero.
+	pub fn with_priority(
+		capacity: usize,
+		priority: impl Fn(&I, &O) -> P + Send + Sync + 'static,
+		f: impl Fn(&I) -> O + Send + Sync + 'f,
+	) -> Self {
+		Self::with_cache(Priority::with_capacity(capacity, priority), f)
+	}
+
+	/// Create a cache holding at most `capacity` values, for the provided recursive function,
+	/// evicting the value with the lowest priority when full.
+	///
+	/// Values may be evicted while the function is still running, as with
+	/// [`LruCache`](crate::LruCache).
+	///
+	/// # Panics
+	/// If `capacity` is zero.
+	pub fn recursive_with_priority(
+		capacity: usize,
+		priority: impl Fn(&I, &O) -> P + Send + Sync + 'static,
+		f: impl Fn(&mut RefCache<Priority<I, O, P>>, &I) -> O + Send + Sync + 'f,
+	) -> Self {
+		Self::recursive_with_cache(Priority::with_capacity(capacity, priority), f)
+	}
+}
@@ -0,0 +1,120 @@
+use std::collections::HashMap;
+
+use crate::container::{ContainerClear, ContainerLen, SparseContainer};
+use crate::generic_cache::{GenericCache, RefCache};
+
+/// A cache which records whether each value was a base case of a recursive function, or was
+/// computed from its dependencies.
+///
+/// The function marks a base case by returning its value through [`RefCache::base`]. Every
+/// other value is recorded as computed.
+///
+/// ```
+/// # use fn_cache::{FnCache, ProvenanceCache};
+/// let mut cache = ProvenanceCache::<usize, u64>::recursive(|cache, x| match x {
+///     0 | 1 => cache.base(*x as u64),
+///     _ => *cache.get(x - 1) + *cache.get(x - 2),
+/// });
+///
+/// cache.get(5);
+///
+/// assert_eq!(cache.is_base_case(&1), Some(true));
+/// assert_eq!(cache.is_base_case(&5), Some(false));
+/// assert_eq!(cache.is_base_case(&6), None);
+/// ```
+///
+/// Other containers can be used with a `GenericCache<Provenance<C>>` directly, as long as they
+/// store a flag alongside each output.
+pub type ProvenanceCache<'f, I, O> = GenericCache<'f, Provenance<HashMap<I, (O, bool)>>>;
+
+/// A [`SparseContainer`] which stores whether each output was a base case alongside it.
+#[derive(Default)]
+pub struct Provenance<C> {
+	inner: C,
+	base: bool,
+}
+
+impl<C> Provenance<C> {
+	/// Wrap a container, which stores each output along with whether it was a base case.
+	pub fn new(inner: C) -> Self {
+		Self { inner, base: false }
+	}
+
+	/// Get a reference to the wrapped container.
+	pub fn inner(&self) -> &C {
+		&self.inner
+	}
+}
+
+impl<C, O> SparseContainer for Provenance<C>
+where
+	C: SparseContainer<Output = (O, bool)>,
+{
+	type Input = C::Input;
+	type Output = O;
+
+	fn has(&self, input: &Self::Input) -> bool {
+		self.inner.has(input)
+	}
+
+	fn get(&self, input: &Self::Input) -> Option<&Self::Output> {
+		self.inner.get(input).map(|(o, _)| o)
+	}
+
+	fn put(&mut self, input: Self::Input, output: Self::Output) -> &Self::Output {
+		let base = core::mem::take(&mut self.base);
+
+		&self.inner.put(input, (output, base)).0
+	}
+
+	fn touch(&mut self, input: &Self::Input) {
+		self.inner.touch(input)
+	}
+
+	fn begin(&mut self, input: &Self::Input) {
+		self.inner.begin(input)
+	}
+}
+
+impl<C, O> ContainerLen for Provenance<C>
+where
+	C: SparseContainer<Output = (O, bool)> + ContainerLen,
+{
+	fn len(&self) -> usize {
+		self.inner.len()
+	}
+}
+
+impl<C, O> ContainerClear for Provenance<C>
+where
+	C: SparseContainer<Output = (O, bool)> + ContainerClear,
+{
+	fn clear(&mut self) {
+		self.inner.clear()
+	}
+}
+
+impl<'c, C, O> RefCache<'c, Provenance<C>>
+where
+	C: SparseContainer<Output = (O, bool)>,
+{
+	/// Mark the value being computed as a base case, returning it unchanged.
+	///
+	/// This should be called on the value returned by the function, after any other calls to
+	/// the cache.
+	pub fn base(&mut self, output: O) -> O {
+		self.cache.base = true;
+		output
+	}
+}
+
+impl<'f, C, O> GenericCache<'f, Provenance<C>>
+where
+	C: SparseContainer<Output = (O, bool)>,
+{
+	/// Returns whether the value for `input` was a base case, or `None` if it is not in the
+	/// cache.
+	pub fn is_base_case(&self, input: &C::Input) -> Option<bool> {
+		self.cache.inner.get(input).map(|(_, base)| *base)
+	}
+}
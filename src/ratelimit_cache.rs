@@ -0,0 +1,194 @@
+use std::fmt;
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::container::SparseContainer;
+use crate::generic_cache::{GenericCache, RefCache};
+use crate::lock::{Mutex, MutexGuard};
+use crate::{FnCache, FnCachePeek};
+
+/// The error returned by [`RateLimitedCache::try_get`] when computing a value would exceed the
+/// rate limit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RateLimited;
+
+impl fmt::Display for RateLimited {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str("cache computation would exceed its rate limit")
+	}
+}
+
+impl std::error::Error for RateLimited {}
+
+/// A token bucket, limiting how often something may happen.
+///
+/// The bucket holds up to `burst` tokens, and refills at `per_second` tokens every second. Each
+/// use takes one token.
+pub struct TokenBucket {
+	per_second: f64,
+	burst: f64,
+	state: Mutex<(f64, Instant)>,
+}
+
+impl TokenBucket {
+	/// Create a full bucket, which refills at `per_second` tokens per second, up to `burst`
+	/// tokens.
+	///
+	/// # Panics
+	/// If `per_second` is not positive, or `burst` is zero.
+	pub fn new(per_second: f64, burst: u32) -> Self {
+		assert!(per_second > 0.0, "TokenBucket rate must be positive");
+		assert!(burst > 0, "TokenBucket burst must be non-zero");
+
+		Self {
+			per_second,
+			burst: burst as f64,
+			state: Mutex::new((burst as f64, Instant::now())),
+		}
+	}
+
+	/// Returns true if a token is available right now.
+	pub fn is_ready(&self) -> bool {
+		self.refill().0 >= 1.0
+	}
+
+	/// Takes a token, waiting for one to be available if required.
+	pub fn acquire(&self) {
+		loop {
+			let wait = {
+				let mut state = self.refill();
+
+				if state.0 >= 1.0 {
+					state.0 -= 1.0;
+					return;
+				}
+
+				Duration::from_secs_f64((1.0 - state.0) / self.per_second)
+			};
+
+			thread::sleep(wait);
+		}
+	}
+
+	fn refill(&self) -> MutexGuard<'_, (f64, Instant)> {
+		let mut state = self.state.lock();
+		let now = Instant::now();
+
+		state.0 = (state.0 + (now - state.1).as_secs_f64() * self.per_second).min(self.burst);
+		state.1 = now;
+
+		state
+	}
+}
+
+/// A cache which limits how often its function is called, using a [`TokenBucket`].
+///
+/// Every miss takes a token from the bucket before calling the function, including misses while
+/// computing a recursive function, while hits are never limited. [`FnCache::get`] waits for a
+/// token, while [`Self::try_get`] returns [`RateLimited`] instead.
+///
+/// ```
+/// # use fn_cache::FnCache;
+/// # use fn_cache::ratelimit_cache::{RateLimited, RateLimitedCache, TokenBucket};
+/// # use std::collections::HashMap;
+/// let mut cache =
+///     RateLimitedCache::<HashMap<usize, usize>>::new(TokenBucket::new(1.0, 1), |x| *x);
+///
+/// assert_eq!(cache.try_get(1), Ok(&1));
+/// assert_eq!(cache.try_get(1), Ok(&1));
+/// assert_eq!(cache.try_get(2), Err(RateLimited));
+/// ```
+pub struct RateLimitedCache<'f, C: SparseContainer> {
+	raw: GenericCache<'f, C>,
+	bucket: Arc<TokenBucket>,
+}
+
+impl<'f, C: SparseContainer> RateLimitedCache<'f, C> {
+	/// Create a `RateLimitedCache` out of a cache, a token bucket, and a function.
+	pub fn with_cache(
+		cache: C,
+		bucket: TokenBucket,
+		f: impl Fn(&C::Input) -> C::Output + Send + Sync + 'f,
+	) -> Self {
+		Self::recursive_with_cache(cache, bucket, move |_, i| f(i))
+	}
+
+	/// Create a `RateLimitedCache` out of a cache, a token bucket, and a recursive function.
+	pub fn recursive_with_cache(
+		cache: C,
+		bucket: TokenBucket,
+		f: impl Fn(&mut RefCache<C>, &C::Input) -> C::Output + Send + Sync + 'f,
+	) -> Self {
+		let bucket = Arc::new(bucket);
+		let f_bucket = bucket.clone();
+
+		Self {
+			raw: GenericCache::recursive_with_cache(cache, move |cache, i| {
+				f_bucket.acquire();
+				f(cache, i)
+			}),
+			bucket,
+		}
+	}
+
+	/// Get a reference to the underlying cache object.
+	pub fn cache(&self) -> &C {
+		self.raw.cache()
+	}
+
+	/// Get a reference to the token bucket limiting the function.
+	pub fn bucket(&self) -> &TokenBucket {
+		&self.bucket
+	}
+
+	/// Retrieve a value stored in the cache, computing it if it does not yet exist and a token is
+	/// available.
+	///
+	/// Returns [`RateLimited`] if the value is not cached and no token is available. Only the
+	/// first call is checked, so a recursive function may still wait for tokens while computing
+	/// its dependencies.
+	pub fn try_get(&mut self, input: C::Input) -> Result<&C::Output, RateLimited> {
+		if self.raw.cache().has(&input) || self.bucket.is_ready() {
+			Ok(self.raw.get(input))
+		} else {
+			Err(RateLimited)
+		}
+	}
+}
+
+impl<'f, C> RateLimitedCache<'f, C>
+where
+	C: SparseContainer + Default,
+{
+	/// Create a `RateLimitedCache` using the `Default` implementation of the container, a token
+	/// bucket, and a function.
+	pub fn new(bucket: TokenBucket, f: impl Fn(&C::Input) -> C::Output + Send + Sync + 'f) -> Self {
+		Self::with_cache(Default::default(), bucket, f)
+	}
+
+	/// Create a `RateLimitedCache` using the `Default` implementation of the container, a token
+	/// bucket, and a recursive function.
+	pub fn recursive(
+		bucket: TokenBucket,
+		f: impl Fn(&mut RefCache<C>, &C::Input) -> C::Output + Send + Sync + 'f,
+	) -> Self {
+		Self::recursive_with_cache(Default::default(), bucket, f)
+	}
+}
+
+impl<'f, C: SparseContainer> FnCache<C::Input, C::Output> for RateLimitedCache<'f, C> {
+	fn get(&mut self, input: C::Input) -> &C::Output {
+		self.raw.get(input)
+	}
+}
+
+impl<'f, C: SparseContainer> FnCachePeek<C::Input, C::Output> for RateLimitedCache<'f, C> {
+	fn peek(&self, input: &C::Input) -> Option<&C::Output> {
+		self.raw.peek(input)
+	}
+
+	fn contains(&self, input: &C::Input) -> bool {
+		self.raw.contains(input)
+	}
+}
@@ -0,0 +1,126 @@
+use std::collections::HashMap;
+
+use crate::container::{ContainerClear, ContainerIter, ContainerLen, SparseContainer};
+use crate::GenericCache;
+
+/// A cache which records the order in which values were computed, so they can be replayed later.
+///
+/// Values are always inserted after every value they depend on, so the recorded order is a valid
+/// dependency order. This is useful for property testing a recursive function, by checking every
+/// computed value against a reference implementation.
+///
+/// ```
+/// # use fn_cache::{FnCache, ReplayCache};
+/// let mut cache = ReplayCache::<usize, u64>::recursive(|cache, x| match x {
+///     0 => 0,
+///     1 => 1,
+///     _ => *cache.get(x - 1) + *cache.get(x - 2),
+/// });
+///
+/// cache.get(3);
+///
+/// assert_eq!(
+///     cache.replay().collect::<Vec<_>>(),
+///     [(1, 1), (0, 0), (2, 1), (3, 2)],
+/// );
+/// ```
+///
+/// Other containers can be recorded by using a `GenericCache<Replay<C>>` directly.
+pub type ReplayCache<'f, I, O> = GenericCache<'f, Replay<HashMap<I, O>>>;
+
+/// A [`SparseContainer`] which records the order of inputs as they are inserted into the inner
+/// container.
+#[derive(Default)]
+pub struct Replay<C: SparseContainer> {
+	inner: C,
+	order: Vec<C::Input>,
+}
+
+impl<C: SparseContainer> Replay<C> {
+	/// Wrap a container, recording any inputs inserted from now on.
+	pub fn new(inner: C) -> Self {
+		Self {
+			inner,
+			order: Vec::new(),
+		}
+	}
+
+	/// Get a reference to the wrapped container.
+	pub fn inner(&self) -> &C {
+		&self.inner
+	}
+}
+
+impl<C> SparseContainer for Replay<C>
+where
+	C: SparseContainer,
+	C::Input: Clone,
+{
+	type Input = C::Input;
+	type Output = C::Output;
+
+	fn has(&self, input: &Self::Input) -> bool {
+		self.inner.has(input)
+	}
+
+	fn get(&self, input: &Self::Input) -> Option<&Self::Output> {
+		self.inner.get(input)
+	}
+
+	fn put(&mut self, input: Self::Input, output: Self::Output) -> &Self::Output {
+		if !self.inner.has(&input) {
+			self.order.push(input.clone());
+		}
+
+		self.inner.put(input, output)
+	}
+
+	fn touch(&mut self, input: &Self::Input) {
+		self.inner.touch(input)
+	}
+
+	fn begin(&mut self, input: &Self::Input) {
+		self.inner.begin(input)
+	}
+}
+
+impl<C: SparseContainer + ContainerLen> ContainerLen for Replay<C> {
+	fn len(&self) -> usize {
+		self.inner.len()
+	}
+}
+
+impl<C: SparseContainer + ContainerClear> ContainerClear for Replay<C> {
+	fn clear(&mut self) {
+		self.inner.clear();
+		self.order.clear();
+	}
+}
+
+impl<C> ContainerIter for Replay<C>
+where
+	C: ContainerIter,
+	C::Input: Clone,
+{
+	fn iter(&self) -> impl Iterator<Item = (&Self::Input, &Self::Output)> {
+		self.inner.iter()
+	}
+}
+
+impl<'f, C> GenericCache<'f, Replay<C>>
+where
+	C: SparseContainer,
+	C::Input: Clone,
+	C::Output: Clone,
+{
+	/// Returns every `(input, output)` pair computed so far, in the order they were computed.
+	///
+	/// Since every value is computed after the values it depends on, this is a dependency order,
+	/// suitable for checking each value against an oracle.
+	pub fn replay(&self) -> impl Iterator<Item = (C::Input, C::Output)> + '_ {
+		self.cache.order.iter().map(|i| {
+			let o = self.cache.inner.get(i).unwrap();
+			(i.clone(), o.clone())
+		})
+	}
+}
@@ -0,0 +1,137 @@
+use std::thread;
+use std::time::Duration;
+
+use crate::container::SparseContainer;
+use crate::TryFnCache;
+
+/// How a [`RetryCache`] retries a function which fails.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+	/// The most times the function is called for a single value, including the first call.
+	pub attempts: u32,
+	/// How long to wait before the first retry. The wait doubles before each retry after it.
+	pub backoff: Duration,
+}
+
+impl RetryPolicy {
+	/// Returns how long to wait before retrying, after `failures` failed calls.
+	fn wait(&self, failures: u32) -> Duration {
+		self.backoff
+			.saturating_mul(2u32.saturating_pow(failures.saturating_sub(1)))
+	}
+}
+
+/// A cache for a fallible function with transient failures, which retries the function with
+/// exponential backoff before giving up.
+///
+/// Errors are never cached. Each call to [`Self::try_get`] for a missing value calls the function
+/// up to [`RetryPolicy::attempts`] times, sleeping between attempts, and caches the first success.
+/// If every attempt fails, the last error is returned, and the next call starts over.
+///
+/// ```
+/// # use fn_cache::retry_cache::{RetryCache, RetryPolicy};
+/// # use std::collections::HashMap;
+/// # use std::sync::atomic::{AtomicU32, Ordering};
+/// # use std::time::Duration;
+/// let calls = AtomicU32::new(0);
+///
+/// let mut cache = RetryCache::<HashMap<u64, u64>, &str>::retry(
+///     |x| match calls.fetch_add(1, Ordering::Relaxed) {
+///         0 => Err("busy"),
+///         _ => Ok(x * 2),
+///     },
+///     RetryPolicy { attempts: 3, backoff: Duration::from_millis(1) },
+/// );
+///
+/// assert_eq!(cache.try_get(4), Ok(&8));
+/// assert_eq!(calls.load(Ordering::Relaxed), 2);
+/// ```
+pub struct RetryCache<'f, C: SparseContainer, E> {
+	cache: C,
+	policy: RetryPolicy,
+	#[allow(clippy::type_complexity)]
+	f: Box<dyn Fn(&C::Input) -> Result<C::Output, E> + Send + 'f>,
+}
+
+impl<'f, C: SparseContainer, E> RetryCache<'f, C, E> {
+	/// Create a `RetryCache` out of a cache, a fallible function, and how to retry it.
+	pub fn retry_with_cache(
+		cache: C,
+		primary: impl Fn(&C::Input) -> Result<C::Output, E> + Send + 'f,
+		policy: RetryPolicy,
+	) -> Self {
+		Self {
+			cache,
+			policy,
+			f: Box::new(primary),
+		}
+	}
+
+	/// Get a reference to the underlying cache object.
+	pub fn cache(&self) -> &C {
+		&self.cache
+	}
+
+	/// Returns how the function is retried.
+	pub fn policy(&self) -> RetryPolicy {
+		self.policy
+	}
+
+	/// Retrieve a value stored in the cache, computing it if it does not yet exist.
+	///
+	/// Returns the error from the last attempt if every attempt fails, in which case nothing is
+	/// cached for `input`.
+	///
+	/// # Panics
+	/// If the policy allows zero attempts, and the value is not already cached.
+	pub fn try_get(&mut self, input: C::Input) -> Result<&C::Output, E> {
+		if self.cache.has(&input) {
+			self.cache.touch(&input);
+			return Ok(self.cache.get(&input).unwrap());
+		}
+
+		assert!(
+			self.policy.attempts > 0,
+			"retry policy must allow an attempt"
+		);
+
+		self.cache.begin(&input);
+
+		let mut failures = 0;
+
+		loop {
+			match (self.f)(&input) {
+				Ok(output) => return Ok(self.cache.put(input, output)),
+				Err(e) => {
+					failures += 1;
+
+					if failures >= self.policy.attempts {
+						return Err(e);
+					}
+
+					thread::sleep(self.policy.wait(failures));
+				}
+			}
+		}
+	}
+}
+
+impl<'f, C, E> RetryCache<'f, C, E>
+where
+	C: SparseContainer + Default,
+{
+	/// Create a `RetryCache` using the `Default` implementation of the container, a fallible
+	/// function, and how to retry it.
+	pub fn retry(
+		primary: impl Fn(&C::Input) -> Result<C::Output, E> + Send + 'f,
+		policy: RetryPolicy,
+	) -> Self {
+		Self::retry_with_cache(Default::default(), primary, policy)
+	}
+}
+
+impl<'f, C: SparseContainer, E> TryFnCache<C::Input, C::Output, E> for RetryCache<'f, C, E> {
+	fn try_get(&mut self, input: C::Input) -> Result<&C::Output, E> {
+		RetryCache::try_get(self, input)
+	}
+}
@@ -0,0 +1,134 @@
+use crate::container::{
+	ContainerClear, ContainerEvict, ContainerLen, ContainerRemove, SparseContainer,
+};
+use crate::generic_cache::GenericCache;
+
+/// A cache with two tiers, where values evicted from the bounded first tier spill into the second
+/// tier rather than being dropped.
+///
+/// A value found only in the second tier is promoted back into the first tier when it is read,
+/// which may in turn spill another value into the second tier.
+///
+/// ```
+/// # use fn_cache::FnCache;
+/// # use fn_cache::lru_cache::Lru;
+/// # use fn_cache::spill_cache::{Spill, SpillingCache};
+/// # use std::collections::BTreeMap;
+/// let mut cache = SpillingCache::with_cache(
+///     Spill::new(Lru::with_capacity(2), BTreeMap::new()),
+///     |x: &usize| x * x,
+/// );
+///
+/// cache.get(1);
+/// cache.get(2);
+/// cache.get(3);
+///
+/// assert_eq!(cache.cache().l2().keys().collect::<Vec<_>>(), [&1]);
+///
+/// // 1 is promoted without being computed again, spilling 2
+/// cache.get(1);
+///
+/// assert_eq!(cache.cache().l2().keys().collect::<Vec<_>>(), [&2]);
+/// ```
+pub type SpillingCache<'f, L1, L2> = GenericCache<'f, Spill<L1, L2>>;
+
+/// A [`SparseContainer`] made of a bounded first tier, and a second tier which holds every value
+/// evicted from the first.
+///
+/// The two tiers never hold a value for the same input.
+pub struct Spill<L1, L2> {
+	l1: L1,
+	l2: L2,
+}
+
+impl<L1, L2> Spill<L1, L2> {
+	/// Combine a bounded first tier, and a second tier to hold values evicted from the first.
+	pub fn new(l1: L1, l2: L2) -> Self {
+		Self { l1, l2 }
+	}
+
+	/// Get a reference to the first tier.
+	pub fn l1(&self) -> &L1 {
+		&self.l1
+	}
+
+	/// Get a reference to the second tier.
+	pub fn l2(&self) -> &L2 {
+		&self.l2
+	}
+}
+
+impl<L1, L2> SparseContainer for Spill<L1, L2>
+where
+	L1: ContainerEvict,
+	L1::Input: Clone,
+	L2: ContainerRemove<Input = L1::Input, Output = L1::Output>,
+{
+	type Input = L1::Input;
+	type Output = L1::Output;
+
+	fn has(&self, input: &Self::Input) -> bool {
+		self.l1.has(input) || self.l2.has(input)
+	}
+
+	fn get(&self, input: &Self::Input) -> Option<&Self::Output> {
+		self.l1.get(input).or_else(|| self.l2.get(input))
+	}
+
+	fn put(&mut self, input: Self::Input, output: Self::Output) -> &Self::Output {
+		// a value put for an input already spilled replaces it, keeping the tiers apart
+		self.l2.remove(&input);
+
+		let (output, evicted) = self.l1.put_evicting(input, output);
+
+		if let Some((input, output)) = evicted {
+			self.l2.put(input, output);
+		}
+
+		output
+	}
+
+	fn touch(&mut self, input: &Self::Input) {
+		if self.l1.has(input) {
+			self.l1.touch(input);
+		} else if let Some(output) = self.l2.remove(input) {
+			self.put(input.clone(), output);
+		}
+	}
+
+	fn begin(&mut self, input: &Self::Input) {
+		self.l1.begin(input)
+	}
+}
+
+impl<L1, L2> ContainerLen for Spill<L1, L2>
+where
+	L1: ContainerLen,
+	L2: ContainerLen,
+{
+	fn len(&self) -> usize {
+		self.l1.len() + self.l2.len()
+	}
+}
+
+impl<L1, L2> ContainerClear for Spill<L1, L2>
+where
+	L1: ContainerClear,
+	L2: ContainerClear,
+{
+	fn clear(&mut self) {
+		self.l1.clear();
+		self.l2.clear();
+	}
+}
+
+impl<L1, L2> ContainerRemove for Spill<L1, L2>
+where
+	L1: ContainerEvict + ContainerRemove,
+	L1::Input: Clone,
+	L2: ContainerRemove<Input = L1::Input, Output = L1::Output>,
+{
+	fn remove(&mut self, input: &Self::Input) -> Option<Self::Output> {
+		self.l1.remove(input).or_else(|| self.l2.remove(input))
+	}
+}
@@ -0,0 +1,357 @@
+use std::collections::{HashMap, VecDeque};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use crate::container::{
+	ContainerClear, ContainerIter, ContainerLen, ContainerRemove, SparseContainer,
+};
+use crate::generic_cache::{GenericCache, RefCache};
+
+/// A cache which records how many accesses were hits and how many were misses, both over its
+/// whole lifetime and over a window of the most recent accesses.
+///
+/// ```
+/// # use fn_cache::{FnCache, StatsCache};
+/// let mut cache = StatsCache::<usize, usize>::new(|x| *x);
+///
+/// cache.get(1);
+/// cache.get(1);
+/// cache.get(1);
+/// cache.get(2);
+///
+/// assert_eq!(cache.miss_rate(), 0.5);
+/// assert_eq!(cache.windowed_miss_rate(), 0.5);
+/// ```
+///
+/// Other containers can be measured by using a `GenericCache<Stats<C>>` directly.
+pub type StatsCache<'f, I, O> = GenericCache<'f, Stats<HashMap<I, O>>>;
+
+/// How many accesses to a [`Stats`] container were hits, and how many were misses.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CacheStats {
+	/// Accesses which found the value already in the container.
+	pub hits: u64,
+	/// Accesses which computed the value and put it into the container.
+	pub misses: u64,
+}
+
+/// A [`SparseContainer`] which records hits and misses on the container it wraps.
+///
+/// A miss is counted each time the cache begins computing a value, and a hit each time a value is
+/// touched, which [`GenericCache`] does whenever the value is already present. Values put without
+/// being computed, such as by [`GenericCache::insert_if_absent`], are neither.
+pub struct Stats<C> {
+	inner: C,
+	hits: u64,
+	misses: u64,
+	window: VecDeque<bool>,
+	window_size: usize,
+	timed: bool,
+	compute_time: Duration,
+	lookup_time: Duration,
+}
+
+impl<C> Stats<C> {
+	/// The number of recent accesses used by [`Self::windowed_miss_rate`] when no window size is
+	/// given.
+	pub const DEFAULT_WINDOW: usize = 64;
+
+	/// Wrap a container, using the default window size.
+	pub fn new(inner: C) -> Self {
+		Self::with_window(inner, Self::DEFAULT_WINDOW)
+	}
+
+	/// Wrap a container, keeping the last `window_size` accesses for
+	/// [`Self::windowed_miss_rate`].
+	///
+	/// # Panics
+	/// If `window_size` is zero.
+	pub fn with_window(inner: C, window_size: usize) -> Self {
+		assert!(window_size > 0, "Stats window size must be non-zero");
+
+		Self {
+			inner,
+			hits: 0,
+			misses: 0,
+			window: VecDeque::with_capacity(window_size),
+			window_size,
+			timed: false,
+			compute_time: Duration::ZERO,
+			lookup_time: Duration::ZERO,
+		}
+	}
+
+	/// Get a reference to the wrapped container.
+	pub fn inner(&self) -> &C {
+		&self.inner
+	}
+
+	/// Returns the number of hits since the container was created.
+	pub fn hits(&self) -> u64 {
+		self.hits
+	}
+
+	/// Returns the number of misses since the container was created.
+	pub fn misses(&self) -> u64 {
+		self.misses
+	}
+
+	/// Returns the number of hits and misses since the container was created, or since
+	/// [`Self::reset_stats`] was last called.
+	pub fn stats(&self) -> CacheStats {
+		CacheStats {
+			hits: self.hits,
+			misses: self.misses,
+		}
+	}
+
+	/// Resets every statistic, including the window of recent accesses and any recorded times,
+	/// without removing any values.
+	pub fn reset_stats(&mut self) {
+		self.hits = 0;
+		self.misses = 0;
+		self.window.clear();
+		self.compute_time = Duration::ZERO;
+		self.lookup_time = Duration::ZERO;
+	}
+
+	/// Returns the fraction of all accesses which were misses, or zero if there have been no
+	/// accesses.
+	pub fn miss_rate(&self) -> f64 {
+		match self.hits + self.misses {
+			0 => 0.0,
+			total => self.misses as f64 / total as f64,
+		}
+	}
+
+	/// Returns the fraction of the most recent accesses which were misses, or zero if there have
+	/// been no accesses.
+	///
+	/// Since only the last accesses in the window are considered, this responds to a change in
+	/// locality much faster than [`Self::miss_rate`].
+	pub fn windowed_miss_rate(&self) -> f64 {
+		match self.window.len() {
+			0 => 0.0,
+			len => self.window.iter().filter(|&&miss| miss).count() as f64 / len as f64,
+		}
+	}
+
+	/// Returns the total time spent computing values, if the cache was created using one of the
+	/// timed constructors, such as [`GenericCache::timed`].
+	///
+	/// Time spent computing dependencies of a recursive function is only counted once.
+	pub fn compute_time(&self) -> Duration {
+		self.compute_time
+	}
+
+	/// Returns an estimate of how many times faster getting values has been with the cache than
+	/// computing every value each time.
+	///
+	/// This is `(hits + misses) * avg_compute_time / (misses * avg_compute_time + lookup_time)`,
+	/// which assumes that every hit would have taken the average compute time without the
+	/// cache. The time taken for each hit is approximated by timing one extra lookup in the
+	/// wrapped container.
+	///
+	/// Returns 1 if there have been no misses, or no timing was recorded because the cache was
+	/// not created using one of the timed constructors.
+	pub fn estimated_speedup(&self) -> f64 {
+		if self.misses == 0 || self.compute_time.is_zero() {
+			return 1.0;
+		}
+
+		let compute = self.compute_time.as_secs_f64();
+		let avg_compute = compute / self.misses as f64;
+
+		(self.hits + self.misses) as f64 * avg_compute / (compute + self.lookup_time.as_secs_f64())
+	}
+
+	fn record(&mut self, miss: bool) {
+		if miss {
+			self.misses += 1;
+		} else {
+			self.hits += 1;
+		}
+
+		if self.window.len() == self.window_size {
+			self.window.pop_front();
+		}
+
+		self.window.push_back(miss);
+	}
+}
+
+impl<C: Default> Default for Stats<C> {
+	fn default() -> Self {
+		Self::new(C::default())
+	}
+}
+
+impl<C: SparseContainer> SparseContainer for Stats<C> {
+	type Input = C::Input;
+	type Output = C::Output;
+
+	fn has(&self, input: &Self::Input) -> bool {
+		self.inner.has(input)
+	}
+
+	fn get(&self, input: &Self::Input) -> Option<&Self::Output> {
+		self.inner.get(input)
+	}
+
+	fn put(&mut self, input: Self::Input, output: Self::Output) -> &Self::Output {
+		self.inner.put(input, output)
+	}
+
+	fn touch(&mut self, input: &Self::Input) {
+		if self.timed {
+			let start = Instant::now();
+			black_box(self.inner.get(black_box(input)));
+			self.lookup_time += start.elapsed();
+		}
+
+		self.record(false);
+		self.inner.touch(input)
+	}
+
+	fn begin(&mut self, input: &Self::Input) {
+		self.record(true);
+		self.inner.begin(input)
+	}
+}
+
+impl<C: ContainerLen> ContainerLen for Stats<C> {
+	fn len(&self) -> usize {
+		self.inner.len()
+	}
+}
+
+impl<C: ContainerClear> ContainerClear for Stats<C> {
+	fn clear(&mut self) {
+		self.inner.clear()
+	}
+}
+
+impl<C: ContainerRemove> ContainerRemove for Stats<C> {
+	fn remove(&mut self, input: &Self::Input) -> Option<Self::Output> {
+		self.inner.remove(input)
+	}
+}
+
+impl<C: ContainerIter> ContainerIter for Stats<C> {
+	fn iter(&self) -> impl Iterator<Item = (&Self::Input, &Self::Output)> {
+		self.inner.iter()
+	}
+}
+
+impl<'f, C: SparseContainer> GenericCache<'f, Stats<C>> {
+	/// Returns the number of hits and misses, including those made by a recursive function.
+	///
+	/// ```
+	/// # use fn_cache::{FnCache, StatsCache};
+	/// # use fn_cache::stats_cache::CacheStats;
+	/// let mut cache = StatsCache::<usize, usize>::new(|x| *x);
+	///
+	/// cache.get(1);
+	/// cache.get(1);
+	///
+	/// assert_eq!(cache.stats(), CacheStats { hits: 1, misses: 1 });
+	///
+	/// cache.reset_stats();
+	///
+	/// assert_eq!(cache.stats(), CacheStats::default());
+	/// ```
+	pub fn stats(&self) -> CacheStats {
+		self.cache.stats()
+	}
+
+	/// Resets every statistic, without removing any values.
+	///
+	/// See [`Stats::reset_stats`].
+	pub fn reset_stats(&mut self) {
+		self.cache.reset_stats()
+	}
+
+	/// Returns the fraction of all accesses which were misses.
+	///
+	/// See [`Stats::miss_rate`].
+	pub fn miss_rate(&self) -> f64 {
+		self.cache.miss_rate()
+	}
+
+	/// Returns the fraction of the most recent accesses which were misses.
+	///
+	/// See [`Stats::windowed_miss_rate`].
+	pub fn windowed_miss_rate(&self) -> f64 {
+		self.cache.windowed_miss_rate()
+	}
+
+	/// Returns an estimate of how many times faster getting values has been with the cache.
+	///
+	/// See [`Stats::estimated_speedup`].
+	pub fn estimated_speedup(&self) -> f64 {
+		self.cache.estimated_speedup()
+	}
+
+	/// Create a `GenericCache` out of a container and a function, which also records how long
+	/// it takes to compute and look up values, for [`Self::estimated_speedup`].
+	pub fn timed_with_cache(
+		cache: Stats<C>,
+		f: impl Fn(&C::Input) -> C::Output + Send + Sync + 'f,
+	) -> Self {
+		Self::recursive_timed_with_cache(cache, move |_, i| f(i))
+	}
+
+	/// Create a `GenericCache` out of a container and a recursive function, which also records
+	/// how long it takes to compute and look up values, for [`Self::estimated_speedup`].
+	pub fn recursive_timed_with_cache(
+		mut cache: Stats<C>,
+		f: impl Fn(&mut RefCache<Stats<C>>, &C::Input) -> C::Output + Send + Sync + 'f,
+	) -> Self {
+		cache.timed = true;
+
+		Self::recursive_with_cache(cache, move |cache, i| {
+			let before = cache.cache.compute_time;
+			let start = Instant::now();
+
+			let output = f(cache, i);
+
+			// dependencies computed along the way are already included in the elapsed time
+			cache.cache.compute_time = before + start.elapsed();
+
+			output
+		})
+	}
+}
+
+impl<'f, C: SparseContainer + Default> GenericCache<'f, Stats<C>> {
+	/// Create a `GenericCache` using the `Default` implementation of the container, which also
+	/// records how long it takes to compute and look up values, for
+	/// [`Self::estimated_speedup`].
+	///
+	/// ```
+	/// # use fn_cache::{FnCache, StatsCache};
+	/// # use std::{thread, time::Duration};
+	/// let mut cache = StatsCache::<usize, usize>::timed(|x| {
+	///     thread::sleep(Duration::from_millis(1));
+	///     *x
+	/// });
+	///
+	/// for _ in 0..10 {
+	///     cache.get(1);
+	/// }
+	///
+	/// assert!(cache.estimated_speedup() > 1.0);
+	/// ```
+	pub fn timed(f: impl Fn(&C::Input) -> C::Output + Send + Sync + 'f) -> Self {
+		Self::timed_with_cache(Stats::default(), f)
+	}
+
+	/// Create a `GenericCache` using the `Default` implementation of the container and a
+	/// recursive function, which also records how long it takes to compute and look up values,
+	/// for [`Self::estimated_speedup`].
+	pub fn recursive_timed(
+		f: impl Fn(&mut RefCache<Stats<C>>, &C::Input) -> C::Output + Send + Sync + 'f,
+	) -> Self {
+		Self::recursive_timed_with_cache(Stats::default(), f)
+	}
+}
@@ -0,0 +1,133 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::{Arc, OnceLock};
+
+use crate::lock::RwLock;
+
+/// A cache which can be shared between threads, and retrieves values through a shared reference.
+///
+/// Values are stored in a [`HashMap`] behind a [`RwLock`](std::sync::RwLock), or with the
+/// `parking_lot` feature, a `parking_lot::RwLock`, which is never poisoned. Hits only take the
+/// read lock, so threads reading values which are already cached do not block each other. A miss
+/// briefly takes the write lock to reserve a slot for the input, then computes the value without
+/// holding any lock. If several threads miss on the same input at once, only one of them calls
+/// the function, and the others wait for its result.
+///
+/// Since the map may move values when it grows, each value is returned in an [`Arc`] instead of
+/// by reference, so this cache does not implement [`FnCache`](crate::FnCache).
+///
+/// If the function panics, the slot is left empty, and the next request for that input calls the
+/// function again.
+///
+/// ```
+/// # use fn_cache::sync_cache::SyncCache;
+/// let cache = SyncCache::<u64, u64>::new(|x| x * x);
+///
+/// std::thread::scope(|s| {
+///     for _ in 0..4 {
+///         s.spawn(|| assert_eq!(*cache.get(12), 144));
+///     }
+/// });
+///
+/// assert_eq!(cache.len(), 1);
+/// ```
+pub struct SyncCache<'f, I, O> {
+	cache: RwLock<HashMap<I, Arc<OnceLock<Arc<O>>>>>,
+	#[allow(clippy::type_complexity)]
+	f: Box<dyn Fn(&Self, &I) -> O + Send + Sync + 'f>,
+}
+
+impl<'f, I, O> SyncCache<'f, I, O>
+where
+	I: Eq + Hash + Clone,
+{
+	/// Create a cache for the provided function. If the function stores references, the cache
+	/// can only live as long as those references.
+	pub fn new(f: impl Fn(&I) -> O + Send + Sync + 'f) -> Self {
+		Self::recursive(move |_, x| f(x))
+	}
+
+	/// Create a cache for the provided recursive function. If the function stores references,
+	/// the cache can only live as long as those references.
+	///
+	/// No lock is held while the function runs, so it may get other values from the cache.
+	/// However, a function which depends on its own input, directly or indirectly, will deadlock
+	/// instead of recursing forever.
+	pub fn recursive(f: impl Fn(&Self, &I) -> O + Send + Sync + 'f) -> Self {
+		Self {
+			cache: RwLock::default(),
+			f: Box::new(f),
+		}
+	}
+
+	/// Retrieve a value stored in the cache. If the value does not yet exist in the cache, the
+	/// function is called, and the result is added to the cache before returning it.
+	pub fn get(&self, input: I) -> Arc<O> {
+		self.get_or_compute(input).0
+	}
+
+	/// Retrieve a value stored in the cache, computing it if it does not yet exist, along with
+	/// whether this call computed it.
+	///
+	/// If several threads request the same missing value at once, exactly one of them calls the
+	/// function and returns `true`, which allows that thread alone to do any follow up work.
+	///
+	/// ```
+	/// # use fn_cache::sync_cache::SyncCache;
+	/// let cache = SyncCache::<u64, u64>::new(|x| x * x);
+	///
+	/// let (output, computed) = cache.get_or_compute(3);
+	/// assert_eq!((*output, computed), (9, true));
+	///
+	/// let (output, computed) = cache.get_or_compute(3);
+	/// assert_eq!((*output, computed), (9, false));
+	/// ```
+	pub fn get_or_compute(&self, input: I) -> (Arc<O>, bool) {
+		let slot = self.slot(input.clone());
+		let mut computed = false;
+
+		let output = slot.get_or_init(|| {
+			computed = true;
+			Arc::new((self.f)(self, &input))
+		});
+
+		(output.clone(), computed)
+	}
+
+	/// Returns true if the value for `input` has been computed, without computing it.
+	///
+	/// A value which is still being computed by another thread is not yet contained.
+	pub fn contains(&self, input: &I) -> bool {
+		self.cache
+			.read()
+			.get(input)
+			.is_some_and(|slot| slot.get().is_some())
+	}
+
+	/// Returns the slot for `input`, inserting an empty one if required.
+	fn slot(&self, input: I) -> Arc<OnceLock<Arc<O>>> {
+		if let Some(slot) = self.cache.read().get(&input) {
+			return slot.clone();
+		}
+
+		self.cache.write().entry(input).or_default().clone()
+	}
+}
+
+impl<'f, I, O> SyncCache<'f, I, O> {
+	/// Returns the number of values which have been computed.
+	///
+	/// Values which are still being computed by another thread are not counted.
+	pub fn len(&self) -> usize {
+		self.cache
+			.read()
+			.values()
+			.filter(|slot| slot.get().is_some())
+			.count()
+	}
+
+	/// Returns true if no values have been computed.
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+}
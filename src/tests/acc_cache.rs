@@ -0,0 +1,67 @@
+use std::collections::HashMap;
+
+use crate::acc_cache::AccCache;
+use crate::deps_cache::Deps;
+use crate::{FnCache, FnCacheMany};
+
+fn fib_counting_base() -> AccCache<'static, HashMap<u64, u64>, usize> {
+	AccCache::recursive(0, |cache, x| match x {
+		0 | 1 => {
+			*cache.acc() += 1;
+			*x
+		}
+		_ => cache.get_many([x - 1, x - 2]).into_iter().sum(),
+	})
+}
+
+#[test]
+fn count_base_cases() {
+	let mut ac = fib_counting_base();
+
+	assert_eq!(ac.get(20), &6765);
+	assert_eq!(ac.acc(), &2);
+	assert_eq!(ac.len(), 21);
+
+	// cached values don't reach the base cases again
+	assert_eq!(ac.get(25), &75025);
+	assert_eq!(ac.acc(), &2);
+
+	ac.clear();
+	assert_eq!(ac.take_acc(), 2);
+
+	assert_eq!(ac.get_many([3, 4]), [&2, &3]);
+	assert_eq!(ac.acc(), &2);
+}
+
+#[test]
+fn acc_mut() {
+	let mut ac = AccCache::<HashMap<u64, u64>, Vec<u64>>::recursive(Vec::new(), |cache, x| {
+		cache.acc().push(*x);
+		x * 2
+	});
+
+	ac.get(1);
+	ac.get(2);
+	ac.get(1);
+
+	assert_eq!(ac.acc(), &[1, 2]);
+
+	ac.acc_mut().clear();
+	ac.get(3);
+
+	assert_eq!(ac.acc(), &[3]);
+}
+
+#[test]
+fn records_dependencies() {
+	let mut ac = AccCache::<Deps<HashMap<u64, u64>>, ()>::recursive((), |cache, x| match x {
+		0 | 1 => *x,
+		_ => *cache.get(x - 1) + *cache.get(x - 2),
+	});
+
+	ac.get(4);
+
+	// the value asked for records what it read, the same as those it recursed into
+	assert_eq!(ac.cache().dependencies(&4), Some(&[3, 2][..]));
+	assert_eq!(ac.cache().dependencies(&3), Some(&[2, 1][..]));
+}
@@ -0,0 +1,153 @@
+use std::future::{poll_fn, Future};
+use std::pin::pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake};
+use std::thread::{self, Thread};
+
+use crate::async_cache::{AsyncFnCache, HashAsyncCache};
+
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+	fn wake(self: Arc<Self>) {
+		self.0.unpark();
+	}
+}
+
+/// Runs a future to completion on the current thread.
+fn block_on<T>(fut: impl Future<Output = T>) -> T {
+	let mut fut = pin!(fut);
+	let waker = Arc::new(ThreadWaker(thread::current())).into();
+	let mut cx = Context::from_waker(&waker);
+
+	loop {
+		match fut.as_mut().poll(&mut cx) {
+			Poll::Ready(t) => return t,
+			Poll::Pending => thread::park(),
+		}
+	}
+}
+
+/// Returns pending `n` times before completing, so other futures can run in between.
+async fn yield_times(n: usize) {
+	let mut remaining = n;
+
+	poll_fn(|cx| {
+		if remaining == 0 {
+			Poll::Ready(())
+		} else {
+			remaining -= 1;
+			cx.waker().wake_by_ref();
+			Poll::Pending
+		}
+	})
+	.await
+}
+
+/// Polls both futures until both are complete.
+async fn join<A, B>(a: impl Future<Output = A>, b: impl Future<Output = B>) -> (A, B) {
+	let mut a = pin!(a);
+	let mut b = pin!(b);
+	let (mut ra, mut rb) = (None, None);
+
+	poll_fn(|cx| {
+		if ra.is_none() {
+			if let Poll::Ready(r) = a.as_mut().poll(cx) {
+				ra = Some(r);
+			}
+		}
+
+		if rb.is_none() {
+			if let Poll::Ready(r) = b.as_mut().poll(cx) {
+				rb = Some(r);
+			}
+		}
+
+		if ra.is_some() && rb.is_some() {
+			Poll::Ready((ra.take().unwrap(), rb.take().unwrap()))
+		} else {
+			Poll::Pending
+		}
+	})
+	.await
+}
+
+#[test]
+fn get() {
+	let calls = AtomicUsize::new(0);
+
+	let mut ac = HashAsyncCache::new(|x: &u64| {
+		calls.fetch_add(1, Ordering::Relaxed);
+		let x = *x;
+		async move {
+			yield_times(3).await;
+			x * x
+		}
+	});
+
+	assert_eq!(block_on(ac.get(4)), &16);
+	assert_eq!(block_on(ac.get(4)), &16);
+	assert_eq!(calls.load(Ordering::Relaxed), 1);
+	assert!(ac.contains(&4));
+	assert_eq!(ac.len(), 1);
+}
+
+#[test]
+fn concurrent_gets_share_computation() {
+	let calls = AtomicUsize::new(0);
+
+	let ac = HashAsyncCache::new(|x: &u64| {
+		calls.fetch_add(1, Ordering::Relaxed);
+		let x = *x;
+		async move {
+			yield_times(5).await;
+			x * x
+		}
+	});
+
+	let (a, b) = block_on(join(ac.get_shared(7), ac.get_shared(7)));
+
+	assert_eq!((*a, *b), (49, 49));
+	assert!(Arc::ptr_eq(&a, &b));
+	assert_eq!(calls.load(Ordering::Relaxed), 1);
+
+	let (c, d) = block_on(join(ac.get_shared(7), ac.get_shared(8)));
+
+	assert_eq!((*c, *d), (49, 64));
+	assert_eq!(calls.load(Ordering::Relaxed), 2);
+}
+
+#[test]
+fn cancelled_computation_is_taken_over() {
+	let calls = AtomicUsize::new(0);
+
+	let ac = HashAsyncCache::new(|x: &u64| {
+		calls.fetch_add(1, Ordering::Relaxed);
+		let x = *x;
+		async move {
+			yield_times(5).await;
+			x + 1
+		}
+	});
+
+	block_on(async {
+		let mut first = Box::pin(ac.get_shared(1));
+		let mut second = Box::pin(ac.get_shared(1));
+
+		// start computing in the first, and wait on it in the second
+		poll_fn(|cx| {
+			assert!(first.as_mut().poll(cx).is_pending());
+			assert!(second.as_mut().poll(cx).is_pending());
+			Poll::Ready(())
+		})
+		.await;
+
+		drop(first);
+
+		assert_eq!(*second.await, 2);
+	});
+
+	assert_eq!(calls.load(Ordering::Relaxed), 2);
+	assert_eq!(ac.len(), 1);
+}
@@ -27,13 +27,14 @@ fn get_closure_capture() {
 	test_factor_square(&mut bc, 3)
 }
 
-// TODO this currently doesn't work
-//#[test]
-//fn get_fn_ptr_recursive() {
-//	let mut bc = BTreeCache::recursive(fib);
-//
-//	test_fib(&mut bc)
-//}
+// `fib` cannot be passed directly, since it can only be instantiated for a `RefCache` with one
+// specific lifetime, so it is wrapped in a closure. See `GenericCache::recursive`.
+#[test]
+fn get_fn_recursive() {
+	let mut bc = BTreeCache::recursive(|c, x| fib(c, x));
+
+	test_fib(&mut bc);
+}
 
 #[test]
 fn get_closure_recursive() {
@@ -114,3 +115,208 @@ fn static_context() {
 	hc.get(1);
 	hc.get(2);
 }
+
+#[test]
+fn write_through() {
+	use std::sync::Mutex;
+
+	let store = Mutex::new(Vec::new());
+
+	let mut bc = BTreeCache::write_through(square, |x, y| store.lock().unwrap().push((*x, *y)));
+
+	test_square(&mut bc);
+
+	let mut writes = store.lock().unwrap().clone();
+	writes.sort();
+
+	assert_eq!(writes, [(1, 1), (2, 4), (5, 25), (10, 100)]);
+}
+
+#[test]
+fn recompute_all() {
+	use std::sync::atomic::{AtomicU64, Ordering};
+
+	let factor = AtomicU64::new(1);
+
+	let mut bc = BTreeCache::new(|x: &usize| factor.load(Ordering::Relaxed) * square(x));
+
+	test_square(&mut bc);
+
+	factor.store(3, Ordering::Relaxed);
+	bc.recompute_all();
+
+	assert_eq!(bc.len(), 4);
+	assert_eq!(
+		bc.cache().values().copied().collect::<Vec<_>>(),
+		[3, 12, 75, 300]
+	);
+}
+
+#[test]
+fn recompute_all_recursive() {
+	use std::sync::atomic::{AtomicU64, Ordering};
+
+	let base = AtomicU64::new(1);
+
+	let mut bc = BTreeCache::<usize, u64>::recursive(|cache, x| match x {
+		0 => 0,
+		1 => base.load(Ordering::Relaxed),
+		_ => *cache.get(x - 1) + *cache.get(x - 2),
+	});
+
+	assert_eq!(bc.get(10), &55);
+
+	base.store(2, Ordering::Relaxed);
+	bc.remove(&5);
+	bc.recompute_all();
+
+	assert_eq!(bc.len(), 11);
+	assert_eq!(bc.get(10), &110);
+	assert_eq!(bc.get(5), &10);
+}
+
+#[test]
+fn key_vec() {
+	let mut bc = BTreeCache::new(square);
+
+	bc.get(3);
+	bc.get(1);
+	bc.get(2);
+
+	assert_eq!(bc.key_vec(), [&1, &2, &3]);
+	assert_eq!(bc.keys().count(), 3);
+}
+
+#[test]
+fn key_manifest() {
+	let mut bc = BTreeCache::<usize, u64>::recursive(|c, x| fib(c, x));
+
+	bc.get(5);
+
+	let manifest = bc.key_manifest();
+
+	assert_eq!(manifest, [0, 1, 2, 3, 4, 5]);
+	assert_eq!(manifest.iter().collect::<Vec<_>>(), bc.key_vec());
+}
+
+#[test]
+fn iter() {
+	let mut bc = BTreeCache::new(square);
+
+	for i in [7, 2, 9, 4] {
+		bc.get(i);
+	}
+
+	let entries: Vec<_> = bc.iter().collect();
+
+	assert_eq!(entries, [(&2, &4), (&4, &16), (&7, &49), (&9, &81)]);
+}
+
+#[test]
+fn fold() {
+	let mut bc = BTreeCache::new(square);
+
+	for i in [7, 2, 9, 4] {
+		bc.get(i);
+	}
+
+	assert_eq!(bc.fold(0, |sum, _, o| sum + o), 4 + 16 + 49 + 81);
+	assert_eq!(
+		bc.fold(Vec::new(), |mut keys, i, _| {
+			keys.push(*i);
+			keys
+		}),
+		[2, 4, 7, 9]
+	);
+}
+
+#[test]
+fn peek() {
+	use std::sync::atomic::{AtomicUsize, Ordering};
+
+	use crate::FnCachePeek;
+
+	let calls = AtomicUsize::new(0);
+
+	let mut bc = BTreeCache::<usize, u64>::new(|x| {
+		calls.fetch_add(1, Ordering::Relaxed);
+		square(x)
+	});
+
+	assert_eq!(bc.peek(&4), None);
+
+	bc.get(4);
+
+	assert_eq!(bc.peek(&4), Some(&16));
+	assert_eq!(bc.peek(&5), None);
+	assert_eq!(calls.load(Ordering::Relaxed), 1);
+}
+
+#[test]
+fn recursive_with_siblings() {
+	use std::sync::atomic::{AtomicUsize, Ordering};
+
+	let mut sibling = BTreeCache::<usize, u64>::recursive(|c, x| fib(c, x));
+	sibling.get(30);
+
+	let calls = AtomicUsize::new(0);
+
+	let mut bc = BTreeCache::<usize, u64>::recursive_with_siblings(
+		vec![sibling.cache()],
+		|c, siblings, x| {
+			if let Some(o) = siblings.iter().find_map(|s| s.get(x)) {
+				return *o;
+			}
+
+			calls.fetch_add(1, Ordering::Relaxed);
+			fib(c, x)
+		},
+	);
+
+	// only values past those in the sibling are computed
+	assert_eq!(bc.get(32), &2178309);
+	assert_eq!(calls.load(Ordering::Relaxed), 2);
+	assert_eq!(bc.len(), 4);
+}
+
+#[test]
+fn move_matching() {
+	let mut left = BTreeCache::new(square);
+	let mut right = BTreeCache::new(square);
+
+	for i in 0..6 {
+		left.get(i);
+	}
+
+	right.get(10);
+
+	left.move_matching(&mut right, |i, _| i % 2 == 1);
+
+	assert_eq!(left.key_vec(), [&0, &2, &4]);
+	assert_eq!(right.key_vec(), [&1, &3, &5, &10]);
+	assert_eq!(right.cache().get(&5), Some(&25));
+}
+
+#[test]
+fn map_output() {
+	let mut bc = BTreeCache::<usize, Vec<usize>>::new(|x| (0..*x).collect());
+
+	let mut lens = bc.map_output(|v| v.len());
+
+	assert_eq!(lens.get(3), 3);
+	assert!(lens.contains(&3));
+	assert!(!lens.contains(&5));
+
+	assert_eq!(bc.map_output(|v| v.last().copied()).get(3), Some(2));
+	assert_eq!(bc.len(), 1);
+	assert_eq!(bc.get(3), &[0, 1, 2]);
+}
+
+#[test]
+fn capacity() {
+	let mut bc = BTreeCache::new(square);
+
+	test_square(&mut bc);
+
+	assert_eq!(bc.capacity(), bc.len());
+}
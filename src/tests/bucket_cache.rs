@@ -0,0 +1,40 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use crate::bucket_cache::TimeBucketCache;
+use crate::tests::*;
+use crate::{FnCache, FnCachePeek};
+
+#[test]
+fn buckets() {
+	let clock = ManualClock::default();
+	let calls = AtomicUsize::new(0);
+
+	let mut tc =
+		TimeBucketCache::time_bucketed_with_clock(Duration::from_secs(60), clock.clone(), |x| {
+			calls.fetch_add(1, Ordering::Relaxed);
+			square(x)
+		});
+
+	tc.get(1);
+	tc.get(2);
+	clock.advance(Duration::from_secs(59));
+	tc.get(1);
+
+	assert_eq!(tc.current_bucket(), 0);
+	assert_eq!(calls.load(Ordering::Relaxed), 2);
+
+	clock.advance(Duration::from_secs(1));
+
+	assert_eq!(tc.current_bucket(), 1);
+	assert!(!tc.contains(&1));
+	assert_eq!(tc.get(1), &1);
+	assert_eq!(calls.load(Ordering::Relaxed), 3);
+	assert_eq!(tc.len(), 3);
+
+	assert_eq!(tc.purge_old_buckets(), 2);
+	assert_eq!(tc.len(), 1);
+	assert!(tc.cache().contains_key(&(1, 1)));
+	assert!(tc.contains(&1));
+	assert!(!tc.contains(&2));
+}
@@ -0,0 +1,85 @@
+use crate::{BudgetCache, FnCache, FnCacheMany};
+
+/// A decoded image, which is one byte per pixel.
+struct Image {
+	pixels: Vec<u8>,
+}
+
+fn decode(&(width, height): &(usize, usize)) -> Image {
+	Image {
+		pixels: vec![0; width * height],
+	}
+}
+
+fn size_of(image: &Image) -> usize {
+	image.pixels.len()
+}
+
+#[test]
+fn keeps_within_budget() {
+	let mut bc = BudgetCache::with_budget(10_000, size_of, decode);
+
+	bc.get((50, 50));
+	bc.get((10, 10));
+	bc.get((40, 100));
+	assert_eq!(bc.cache().total_bytes(), 2500 + 100 + 4000);
+	assert_eq!(bc.len(), 3);
+
+	// evicts the oldest, and only as many as needed
+	bc.get((60, 60));
+	assert!(!bc.cache().contains(&(50, 50)));
+	assert!(bc.cache().contains(&(10, 10)));
+	assert_eq!(bc.cache().total_bytes(), 100 + 4000 + 3600);
+
+	// a hit does not change the size
+	bc.get((10, 10));
+	assert_eq!(bc.cache().total_bytes(), 7700);
+
+	for i in 1..100 {
+		bc.get((i, 30));
+		assert!(bc.cache().total_bytes() <= 10_000);
+	}
+
+	assert!(bc.cache().total_bytes() > 10_000 - 99 * 30);
+}
+
+#[test]
+fn oversized_value_is_held_alone() {
+	let mut bc = BudgetCache::with_budget(100, size_of, decode);
+
+	bc.get((5, 5));
+	assert_eq!(bc.get((20, 20)).pixels.len(), 400);
+
+	assert_eq!(bc.len(), 1);
+	assert_eq!(bc.cache().total_bytes(), 400);
+
+	bc.get((1, 1));
+
+	assert_eq!(bc.len(), 1);
+	assert_eq!(bc.cache().total_bytes(), 1);
+}
+
+#[test]
+fn remove_and_clear() {
+	let mut bc = BudgetCache::with_budget(1000, size_of, decode);
+
+	bc.get((10, 10));
+	bc.get((20, 10));
+
+	assert_eq!(bc.remove(&(10, 10)).map(|i| i.pixels.len()), Some(100));
+	assert_eq!(bc.cache().total_bytes(), 200);
+
+	bc.clear();
+
+	assert_eq!(bc.cache().total_bytes(), 0);
+	assert!(bc.is_empty());
+}
+
+#[test]
+#[should_panic]
+fn get_many_over_budget() {
+	let mut bc = BudgetCache::with_budget(10_000, size_of, decode);
+
+	// the second image evicts the first before both can be returned
+	bc.get_many([(80, 80), (70, 70)]);
+}
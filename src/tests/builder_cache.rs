@@ -0,0 +1,69 @@
+use std::collections::HashMap;
+
+use crate::builder_cache::{AlreadyFinalized, BuilderCache};
+use crate::container::SparseContainer;
+
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Summary {
+	count: usize,
+	total: u64,
+	max: u64,
+}
+
+fn summaries<'f>() -> BuilderCache<'f, HashMap<&'static str, Summary>, Summary, u64> {
+	BuilderCache::new(
+		|s: &mut Summary, x| {
+			s.count += 1;
+			s.total += x;
+			s.max = s.max.max(x);
+		},
+		|_, s| s,
+	)
+}
+
+#[test]
+fn append_then_finalize() {
+	let mut bc = summaries();
+
+	for x in [3, 9, 4] {
+		bc.append("a", x).unwrap();
+	}
+
+	bc.append("b", 1).unwrap();
+
+	assert_eq!(bc.builder(&"a").map(|s| s.count), Some(3));
+	assert_eq!(bc.get(&"a"), None);
+
+	let expected = Summary {
+		count: 3,
+		total: 16,
+		max: 9,
+	};
+
+	assert_eq!(bc.finalize("a"), &expected);
+	assert_eq!(bc.get(&"a"), Some(&expected));
+	assert_eq!(bc.builder(&"a"), None);
+
+	// b is still being built
+	assert!(!bc.cache().has(&"b"));
+	assert_eq!(bc.append("b", 2), Ok(()));
+}
+
+#[test]
+fn append_after_finalize() {
+	let mut bc = summaries();
+
+	bc.append("a", 5).unwrap();
+	bc.finalize("a");
+
+	assert_eq!(bc.append("a", 6), Err(AlreadyFinalized));
+	assert_eq!(bc.finalize("a").total, 5);
+}
+
+#[test]
+fn finalize_without_events() {
+	let mut bc = summaries();
+
+	assert_eq!(bc.finalize("empty"), &Summary::default());
+	assert_eq!(bc.append("empty", 1), Err(AlreadyFinalized));
+}
@@ -0,0 +1,55 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::checked_cache::CheckedCache;
+use crate::tests::*;
+use crate::FnCache;
+
+#[test]
+fn pure_function() {
+	let mut cc =
+		CheckedCache::<HashMap<usize, u64>>::recursive_checked(|c, x| fib(c, x)).check_every(1);
+
+	for _ in 0..10 {
+		assert_eq!(cc.get(20), &6765);
+		assert_eq!(cc.get(10), &55);
+	}
+
+	assert_eq!(cc.raw().len(), 21);
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "cache function returned a different value")]
+fn impure_function() {
+	let calls = AtomicUsize::new(0);
+	let mut cc = CheckedCache::<HashMap<usize, usize>>::new_checked(|x| {
+		x + calls.fetch_add(1, Ordering::Relaxed)
+	})
+	.check_every(4);
+
+	for _ in 0..4 {
+		cc.get(1);
+	}
+
+	// the fourth hit
+	cc.get(1);
+}
+
+#[cfg(debug_assertions)]
+#[test]
+fn checks_only_every_nth_hit() {
+	let calls = AtomicUsize::new(0);
+	let mut cc = CheckedCache::<HashMap<usize, usize>>::new_checked(|x| {
+		calls.fetch_add(1, Ordering::Relaxed);
+		*x
+	})
+	.check_every(3);
+
+	for _ in 0..10 {
+		cc.get(1);
+	}
+
+	// computed once, then checked on hits 3, 6 and 9
+	assert_eq!(calls.load(Ordering::Relaxed), 4);
+}
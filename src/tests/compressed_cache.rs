@@ -0,0 +1,26 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::compressed_cache::CompressedCache;
+
+#[test]
+fn round_trip() {
+	let calls = AtomicUsize::new(0);
+
+	let mut cc = CompressedCache::new(|x: &u64| {
+		calls.fetch_add(1, Ordering::Relaxed);
+		vec![*x; 10_000]
+	});
+
+	assert!(!cc.contains(&7));
+	assert_eq!(cc.get(7).unwrap(), vec![7; 10_000]);
+	assert!(cc.contains(&7));
+
+	// the repeated value compresses to far less than even one byte per element
+	let stored = cc.compressed(&7).unwrap().len();
+	assert!(stored < 10_000, "stored {stored} bytes");
+	assert_eq!(cc.stored_bytes(), stored);
+
+	assert_eq!(cc.get(7).unwrap(), vec![7; 10_000]);
+	assert_eq!(calls.load(Ordering::Relaxed), 1);
+	assert_eq!(cc.len(), 1);
+}
@@ -0,0 +1,75 @@
+use crate::tests::*;
+use crate::{CountedCache, FnCache, FnCacheMany};
+
+#[test]
+fn get_fn_ptr() {
+	let mut cc = CountedCache::new(square);
+
+	test_square(&mut cc);
+}
+
+#[test]
+fn get_closure_recursive() {
+	let mut cc = CountedCache::<usize, u64>::recursive(|c, x| fib(c, x));
+
+	test_fib(&mut cc);
+}
+
+#[test]
+fn access_count() {
+	let mut cc = CountedCache::<usize, u64>::recursive(|c, x| fib(c, x));
+
+	cc.get(4);
+
+	// 2 is read once computing 3, and again computing 4
+	assert_eq!(cc.access_count(&2), Some(2));
+	assert_eq!(cc.access_count(&4), Some(1));
+
+	cc.get_many([4, 4]);
+
+	assert_eq!(cc.access_count(&4), Some(3));
+}
+
+#[test]
+fn partition_by_access() {
+	let mut cc = CountedCache::new(square);
+
+	for i in 0..10 {
+		for _ in 0..=i {
+			cc.get(i);
+		}
+	}
+
+	let (mut warm, mut cold) = cc.partition_by_access(7);
+
+	let mut warm_keys: Vec<_> = warm.cache().inner().keys().copied().collect();
+	let mut cold_keys: Vec<_> = cold.cache().inner().keys().copied().collect();
+	warm_keys.sort();
+	cold_keys.sort();
+
+	assert_eq!(warm_keys, [6, 7, 8, 9]);
+	assert_eq!(cold_keys, [0, 1, 2, 3, 4, 5]);
+	assert_eq!(warm.access_count(&9), Some(10));
+	assert_eq!(cold.access_count(&0), Some(1));
+
+	// both still compute using the same function
+	assert_eq!(warm.get(12), &144);
+	assert_eq!(cold.get(11), &121);
+}
+
+#[cfg(feature = "stats")]
+#[test]
+fn unread_entries() {
+	let mut cc =
+		CountedCache::<usize, u64>::recursive_prefetching(|_, x| (square(x), vec![x + 1, x + 2]));
+
+	cc.get(1);
+	cc.get(2);
+	cc.get(5);
+
+	let mut unread = cc.unread_entries();
+	unread.sort();
+
+	// 2 was prefetched and then read, while the rest of the prefetched values never were
+	assert_eq!(unread, [&1, &3, &5, &6, &7]);
+}
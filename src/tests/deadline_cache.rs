@@ -0,0 +1,108 @@
+use std::collections::HashMap;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::container::SparseContainer;
+use crate::deadline_cache::{DeadlineCache, TimedOut};
+use crate::deps_cache::Deps;
+use crate::TryFnCache;
+
+#[test]
+fn within_budget() {
+	let mut dc =
+		DeadlineCache::<HashMap<u64, u64>>::recursive(Duration::from_secs(10), |c, x| match x {
+			0 => Ok(0),
+			1 => Ok(1),
+			_ => Ok(*c.try_get(x - 1)? + *c.try_get(x - 2)?),
+		});
+
+	assert_eq!(dc.try_get(10), Ok(&55));
+	assert_eq!(dc.try_get(12), Ok(&144));
+	assert_eq!(dc.cache().len(), 13);
+}
+
+#[test]
+fn slow_base_case() {
+	let mut dc =
+		DeadlineCache::<HashMap<u64, u64>>::recursive(Duration::from_millis(10), |c, x| match x {
+			0 => Ok(0),
+			1 => {
+				thread::sleep(Duration::from_millis(50));
+				Ok(1)
+			}
+			_ => Ok(*c.try_get(x - 1)? + *c.try_get(x - 2)?),
+		});
+
+	let start = Instant::now();
+
+	assert_eq!(dc.try_get(1000), Err(TimedOut));
+	assert!(start.elapsed() < Duration::from_secs(1));
+
+	// the slow value finished, but nothing depending on 0 could be computed
+	assert!(dc.cache().has(&1));
+	assert!(!dc.cache().has(&0));
+	assert!(!dc.cache().has(&2));
+
+	// values already cached are returned even after the budget is spent
+	dc.set_budget(Duration::ZERO);
+	assert_eq!(dc.try_get(1), Ok(&1));
+	assert_eq!(dc.try_get(0), Err(TimedOut));
+
+	dc.set_budget(Duration::from_secs(10));
+	assert_eq!(dc.try_get(10), Ok(&55));
+}
+
+#[test]
+fn records_dependencies() {
+	let mut dc = DeadlineCache::<Deps<HashMap<u64, u64>>>::recursive(
+		Duration::from_secs(10),
+		|c, x| match x {
+			0 => Ok(0),
+			1 => Ok(1),
+			_ => Ok(*c.try_get(x - 1)? + *c.try_get(x - 2)?),
+		},
+	);
+
+	dc.try_get(4).unwrap();
+
+	// the value asked for records what it read, the same as those it recursed into
+	assert_eq!(dc.cache().dependencies(&4), Some(&[3, 2][..]));
+	assert_eq!(dc.cache().dependencies(&3), Some(&[2, 1][..]));
+}
+
+#[derive(Debug, PartialEq)]
+enum FibError {
+	Unlucky(u64),
+	TimedOut,
+}
+
+impl From<TimedOut> for FibError {
+	fn from(_: TimedOut) -> Self {
+		FibError::TimedOut
+	}
+}
+
+fn fib(c: &mut impl TryFnCache<u64, u64, FibError>, x: &u64) -> Result<u64, FibError> {
+	match x {
+		0 => Ok(0),
+		1 => Ok(1),
+		13 => Err(FibError::Unlucky(13)),
+		_ => Ok(*c.try_get(x - 1)? + *c.try_get(x - 2)?),
+	}
+}
+
+#[test]
+fn custom_error() {
+	let mut dc =
+		DeadlineCache::<HashMap<u64, u64>, FibError>::recursive(Duration::from_secs(10), |c, x| {
+			fib(c, x)
+		});
+
+	assert_eq!(dc.try_get(10), Ok(&55));
+	assert_eq!(dc.try_get(15), Err(FibError::Unlucky(13)));
+	assert!(!dc.cache().has(&14));
+
+	dc.set_budget(Duration::ZERO);
+	assert_eq!(TryFnCache::try_get(&mut dc, 10), Ok(&55));
+	assert_eq!(TryFnCache::try_get(&mut dc, 11), Err(FibError::TimedOut));
+}
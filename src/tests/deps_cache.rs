@@ -0,0 +1,123 @@
+use std::collections::HashSet;
+
+use crate::container::SparseContainer;
+use crate::tests::*;
+use crate::{DepsCache, FnCache};
+
+fn fib_deps() -> DepsCache<'static, usize, u64> {
+	DepsCache::recursive(|c, x| fib(c, x))
+}
+
+#[test]
+fn get_closure_recursive() {
+	let mut dc = fib_deps();
+
+	test_fib(&mut dc);
+}
+
+#[test]
+fn get_with_deps() {
+	let mut dc = fib_deps();
+
+	let (value, mut deps) = dc.get_with_deps(5);
+
+	assert_eq!(value, &5);
+
+	deps.sort();
+
+	assert_eq!(deps, [(3, &2), (4, &3)]);
+}
+
+#[test]
+fn cached_dependencies_are_recorded() {
+	let mut dc = fib_deps();
+
+	dc.get(3);
+	dc.get(5);
+
+	// 4 was computed after 3 was already cached
+	assert_eq!(dc.dependencies(&4), Some(&[3, 2][..]));
+	assert_eq!(dc.dependencies(&0), Some(&[][..]));
+	assert_eq!(dc.dependencies(&6), None);
+}
+
+#[test]
+fn removed_dependencies_are_skipped() {
+	let mut dc = fib_deps();
+
+	dc.get(5);
+	dc.remove(&3);
+
+	assert_eq!(dc.get_with_deps(5).1, [(4, &3)]);
+	assert!(!dc.cache().has(&3));
+}
+
+#[test]
+fn inserted_values_have_no_dependencies() {
+	let mut dc = fib_deps();
+
+	dc.get_or_insert_with(10, |_| 55);
+
+	assert_eq!(dc.dependencies(&10), Some(&[][..]));
+}
+
+#[test]
+fn invalidate() {
+	let mut dc = fib_deps();
+
+	dc.get(10);
+
+	// 2 and everything above it depends on 1
+	assert_eq!(dc.invalidate(&1), 10);
+	assert_eq!(dc.len(), 1);
+	assert!(dc.cache().has(&0));
+
+	dc.get(10);
+
+	assert_eq!(dc.invalidate(&8), 3);
+	assert!(dc.cache().has(&7));
+	assert!(!dc.cache().has(&9));
+
+	// an input which is not cached has no dependents
+	assert_eq!(dc.invalidate(&20), 0);
+	assert_eq!(dc.len(), 8);
+}
+
+#[test]
+fn invalidate_recomputes() {
+	use std::sync::atomic::{AtomicU64, Ordering};
+
+	let base = AtomicU64::new(1);
+
+	let mut dc = DepsCache::<usize, u64>::recursive(|c, x| match x {
+		0 => 0,
+		1 => base.load(Ordering::Relaxed),
+		_ => *c.get(x - 1) + *c.get(x - 2),
+	});
+
+	assert_eq!(dc.get(10), &55);
+
+	base.store(2, Ordering::Relaxed);
+	dc.invalidate(&1);
+
+	assert_eq!(dc.get(10), &110);
+}
+
+#[test]
+fn shared_subproblems() {
+	let mut dc = fib_deps();
+
+	assert_eq!(dc.shared_subproblems(5, 4), 5);
+
+	let shared: HashSet<_> = dc
+		.dependency_closure(&5)
+		.intersection(&dc.dependency_closure(&4))
+		.copied()
+		.collect();
+
+	assert_eq!(shared, HashSet::from([&0, &1, &2, &3, &4]));
+
+	assert_eq!(dc.shared_subproblems(10, 10), 11);
+	assert_eq!(dc.shared_subproblems(1, 0), 0);
+	assert!(dc.dependency_closure(&20).is_empty());
+}
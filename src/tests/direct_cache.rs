@@ -0,0 +1,53 @@
+use crate::tests::*;
+use crate::{DirectCache, FnCache, FnCachePeek};
+
+#[test]
+fn get_fn_ptr() {
+	let mut dc = DirectCache::<16, u64>::new(square);
+
+	test_square(&mut dc);
+}
+
+#[test]
+fn get_closure_recursive() {
+	let mut dc = DirectCache::<128, u64>::recursive(|c, x| fib(c, x));
+
+	test_fib(&mut dc);
+}
+
+#[test]
+fn sparse_inputs() {
+	let mut dc = DirectCache::<256, u64>::new(square);
+
+	dc.get(200);
+	dc.get(3);
+
+	assert_eq!(dc.len(), 2);
+	assert!(dc.contains(&200));
+	assert!(!dc.contains(&4));
+
+	assert_eq!(dc.remove(&3), Some(9));
+	assert_eq!(dc.len(), 1);
+
+	dc.clear();
+
+	assert!(dc.is_empty());
+	assert!(!dc.contains(&200));
+}
+
+#[test]
+#[should_panic(expected = "out of range")]
+fn out_of_range() {
+	let mut dc = DirectCache::<4, u64>::new(square);
+
+	dc.get(4);
+}
+
+#[test]
+fn capacity() {
+	let mut dc = DirectCache::<8, u64>::new(square);
+
+	dc.get(3);
+
+	assert_eq!(dc.capacity(), 8);
+}
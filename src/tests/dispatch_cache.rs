@@ -0,0 +1,29 @@
+use std::collections::HashMap;
+
+use crate::dispatch_cache::{DispatchCache, DispatchFn, UnknownTag};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum Power {
+	Square,
+	Cube,
+	Fourth,
+}
+
+#[test]
+fn dispatch() {
+	let mut dc = DispatchCache::new(HashMap::from([
+		(Power::Square, Box::new(|x: &u64| x * x) as DispatchFn<_, _>),
+		(Power::Cube, Box::new(|x: &u64| x * x * x)),
+	]));
+
+	assert_eq!(dc.try_get((Power::Square, 3)), Ok(&9));
+	assert_eq!(dc.try_get((Power::Cube, 3)), Ok(&27));
+	assert_eq!(dc.try_get((Power::Square, 3)), Ok(&9));
+	assert_eq!(dc.len(), 2);
+
+	assert_eq!(dc.try_get((Power::Fourth, 3)), Err(UnknownTag));
+	assert_eq!(dc.len(), 2);
+
+	assert!(dc.register(Power::Fourth, |x| x.pow(4)).is_none());
+	assert_eq!(dc.try_get((Power::Fourth, 3)), Ok(&81));
+}
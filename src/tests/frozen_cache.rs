@@ -0,0 +1,47 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+use crate::tests::*;
+use crate::HashCache;
+
+#[test]
+fn warm_and_share() {
+	let calls = AtomicUsize::new(0);
+
+	let hc = HashCache::<usize, u64>::new(|x| {
+		calls.fetch_add(1, Ordering::Relaxed);
+		square(x)
+	});
+
+	let frozen = hc.warm_and_share([1, 2, 3, 2, 1]);
+
+	assert_eq!(calls.load(Ordering::Relaxed), 3);
+	assert_eq!(frozen.len(), 3);
+
+	thread::scope(|s| {
+		for _ in 0..4 {
+			let frozen = Arc::clone(&frozen);
+
+			s.spawn(move || {
+				for i in 1..=3 {
+					assert_eq!(frozen.get(&i), &square(&i));
+				}
+
+				assert!(!frozen.contains(&4));
+				assert_eq!(frozen.peek(&4), None);
+			});
+		}
+	});
+
+	// reads never compute anything
+	assert_eq!(calls.load(Ordering::Relaxed), 3);
+}
+
+#[test]
+#[should_panic(expected = "not computed before freezing")]
+fn get_missing() {
+	let frozen = HashCache::<usize, u64>::new(square).freeze();
+
+	frozen.get(&1);
+}
@@ -0,0 +1,100 @@
+use std::collections::HashMap;
+
+use crate::container::SparseContainer;
+use crate::deps_cache::Deps;
+use crate::fuel_cache::{FuelCache, OutOfFuel};
+use crate::TryFnCache;
+
+fn fib_cache<'f>(fuel: u64) -> FuelCache<'f, HashMap<u64, u64>> {
+	FuelCache::recursive(fuel, |c, x| match x {
+		0 => Ok(0),
+		1 => Ok(1),
+		_ => Ok(*c.try_get(x - 1)? + *c.try_get(x - 2)?),
+	})
+}
+
+#[test]
+fn within_fuel() {
+	// fib(n) calls the function exactly once for each of 0..=n
+	let mut fc = fib_cache(11);
+
+	assert_eq!(fc.try_get(10), Ok(&55));
+	assert_eq!(fc.cache().len(), 11);
+}
+
+#[test]
+fn out_of_fuel() {
+	let mut fc = fib_cache(10);
+
+	assert_eq!(fc.try_get(10), Err(OutOfFuel));
+
+	// 10 down to 1 were called, and only 1 finished before the fuel ran out
+	assert!(fc.cache().has(&1));
+	assert!(!fc.cache().has(&0));
+	assert!(!fc.cache().has(&10));
+
+	// the fuel is refilled for each call, and cached values cost nothing
+	fc.set_fuel(1);
+	assert_eq!(fc.try_get(0), Ok(&0));
+	assert_eq!(fc.try_get(1), Ok(&1));
+	assert_eq!(fc.try_get(3), Err(OutOfFuel));
+
+	fc.set_fuel(9);
+	assert_eq!(fc.try_get(10), Ok(&55));
+}
+
+#[test]
+fn zero_fuel() {
+	let mut fc = fib_cache(0);
+
+	assert_eq!(fc.try_get(0), Err(OutOfFuel));
+	assert!(fc.cache().is_empty());
+}
+
+#[test]
+fn records_dependencies() {
+	let mut fc = FuelCache::<Deps<HashMap<u64, u64>>>::recursive(10, |c, x| match x {
+		0 => Ok(0),
+		1 => Ok(1),
+		_ => Ok(*c.try_get(x - 1)? + *c.try_get(x - 2)?),
+	});
+
+	fc.try_get(4).unwrap();
+
+	// the value asked for records what it read, the same as those it recursed into
+	assert_eq!(fc.cache().dependencies(&4), Some(&[3, 2][..]));
+	assert_eq!(fc.cache().dependencies(&3), Some(&[2, 1][..]));
+}
+
+#[derive(Debug, PartialEq)]
+enum ParseError {
+	NotANumber,
+	OutOfFuel,
+}
+
+impl From<OutOfFuel> for ParseError {
+	fn from(_: OutOfFuel) -> Self {
+		ParseError::OutOfFuel
+	}
+}
+
+#[test]
+fn custom_error() {
+	// the sum of the digits of a string, one digit per call
+	let mut fc = FuelCache::<HashMap<String, u32>, ParseError>::recursive(3, |c, s| {
+		let mut chars = s.chars();
+		match chars.next() {
+			None => Ok(0),
+			Some(d) => {
+				let d = d.to_digit(10).ok_or(ParseError::NotANumber)?;
+				Ok(d + *c.try_get(chars.as_str().to_string())?)
+			}
+		}
+	});
+
+	assert_eq!(fc.try_get("12".to_string()), Ok(&3));
+	assert_eq!(fc.try_get("1x".to_string()), Err(ParseError::NotANumber));
+	assert_eq!(fc.try_get("1234".to_string()), Err(ParseError::OutOfFuel));
+	assert!(fc.cache().has(&"2".to_string()));
+	assert_eq!(TryFnCache::try_get(&mut fc, "312".to_string()), Ok(&6));
+}
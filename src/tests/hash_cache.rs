@@ -1,9 +1,16 @@
-use std::hash::BuildHasherDefault;
+use std::cell::Cell;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{BuildHasher, BuildHasherDefault};
 use std::rc::Rc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
 
+use crate::generic_cache::{RefCache, Uninitialized};
 use crate::tests::*;
-use crate::FnCache;
-use crate::HashCache;
+use crate::{FnCache, FnCacheMany, FnCachePeek, TryFnCache};
+use crate::{GenericCache, HashCache};
 
 use hashers::fx_hash::FxHasher;
 
@@ -37,14 +44,14 @@ fn get_closure_capture() {
 	test_factor_square(&mut hc, y);
 }
 
-// TODO currently this fails
-//#[test]
-//fn get_fn_ptr_recursive() {
-//
-//	let mut hc = HashCache::recursive(fib);
-//
-//	test_fib(&mut hc);
-//}
+// `fib` cannot be passed directly, since it can only be instantiated for a `RefCache` with one
+// specific lifetime, so it is wrapped in a closure. See `GenericCache::recursive`.
+#[test]
+fn get_fn_recursive() {
+	let mut hc = HashCache::recursive(|c, x| fib(c, x));
+
+	test_fib(&mut hc);
+}
 
 #[test]
 fn get_closure_recursive() {
@@ -105,14 +112,14 @@ fn reserve() {
 	hc.get(2);
 
 	for additional in 20..60 {
-		hc.cache.shrink_to_fit();
+		hc.shrink_to_fit();
 		hc.reserve(additional);
 
 		assert!(
-			hc.len() + additional <= hc.cache.capacity(),
+			hc.len() + additional <= hc.capacity(),
 			"len = {}, capacity = {}, additional = {}",
 			hc.len(),
-			hc.cache.capacity(),
+			hc.capacity(),
 			additional
 		);
 	}
@@ -146,3 +153,814 @@ fn static_context() {
 	hc.get(1);
 	hc.get(2);
 }
+
+#[test]
+fn get_two() {
+	let mut hc = HashCache::<usize, u64>::recursive(|cache, x| match x {
+		0 => 0,
+		1 => 1,
+		_ => {
+			let (a, b) = cache.get_two(x - 1, x - 2);
+			a + b
+		}
+	});
+
+	assert_eq!(hc.get_two(5, 6), (&5, &8));
+	assert_eq!(hc.get_two(7, 7), (&13, &13));
+
+	let (a, b) = hc.get_two(4, 4);
+	assert!(std::ptr::eq(a, b));
+}
+
+#[derive(Clone, Default)]
+struct CountingState(Rc<Cell<usize>>);
+
+impl BuildHasher for CountingState {
+	type Hasher = DefaultHasher;
+
+	fn build_hasher(&self) -> DefaultHasher {
+		self.0.set(self.0.get() + 1);
+		DefaultHasher::new()
+	}
+}
+
+#[test]
+fn get_many_hash_count() {
+	let state = CountingState::default();
+	let mut hc = HashCache::with_hasher(state.clone(), square);
+
+	// an empty map skips hashing on lookups, and growing rehashes, so avoid both
+	hc.get(0);
+	hc.reserve(10);
+	state.0.set(0);
+
+	// each missing input is checked, inserted, then looked up once more
+	hc.get_many([1, 2, 3]);
+	assert_eq!(state.0.get(), 9);
+
+	// each present input is checked, then looked up once more
+	state.0.set(0);
+	hc.get_many([1, 2, 3]);
+	assert_eq!(state.0.get(), 6);
+}
+
+#[test]
+fn get_entry() {
+	let mut hc = HashCache::<usize, u64>::new(square);
+
+	assert_eq!(hc.get_entry(3), (true, &9));
+	assert_eq!(hc.get_entry(3), (false, &9));
+	assert_eq!(hc.get_entry(4), (true, &16));
+	assert_eq!(hc.len(), 2);
+}
+
+#[test]
+fn with_capacity_and_hasher() {
+	let state = CountingState::default();
+	let mut hc = HashCache::with_capacity_and_hasher(100, state.clone(), square);
+
+	assert!(hc.cache().capacity() >= 100);
+
+	test_square(&mut hc);
+
+	assert!(state.0.get() > 0);
+	assert!(hc.cache().capacity() >= 100);
+}
+
+#[test]
+fn recursive_with_capacity_and_hasher() {
+	let state = CountingState::default();
+	let mut hc =
+		HashCache::recursive_with_capacity_and_hasher(100, state.clone(), |c, x| fib(c, x));
+
+	assert!(hc.cache().capacity() >= 100);
+
+	test_fib(&mut hc);
+
+	assert!(state.0.get() > 0);
+}
+
+#[cfg(feature = "debug_trace")]
+#[test]
+fn max_depth_for() {
+	let mut hc = HashCache::<usize, u64>::recursive(|c, x| fib(c, x));
+
+	assert_eq!(hc.max_depth_for(10), 10);
+	assert_eq!(hc.max_depth_for(20), 20);
+	assert_eq!(hc.len(), 0);
+
+	hc.get(30);
+
+	assert_eq!(hc.max_depth_for(30), 30);
+	assert_eq!(HashCache::new(square).max_depth_for(30), 1);
+}
+
+#[test]
+fn replace_cache() {
+	let mut hc = HashCache::<usize, u64>::new(square);
+
+	hc.get(1);
+	hc.get(2);
+
+	let prebuilt = (0..5).map(|x| (x, 100 + x as u64)).collect();
+	let old = hc.replace_cache(prebuilt);
+
+	assert_eq!(old.len(), 2);
+	assert_eq!(old.get(&2), Some(&4));
+
+	assert_eq!(hc.len(), 5);
+	assert_eq!(hc.get(2), &102);
+	assert_eq!(hc.get(4), &104);
+	assert_eq!(hc.get(5), &25);
+}
+
+#[test]
+fn negative_caching() {
+	let calls = AtomicUsize::new(0);
+
+	let mut hc = HashCache::<usize, Option<usize>>::new(|x| {
+		calls.fetch_add(1, Ordering::Relaxed);
+		x.checked_sub(3)
+	});
+
+	assert_eq!(hc.get(0), &None);
+	assert_eq!(hc.get(1), &None);
+	assert_eq!(hc.get(5), &Some(2));
+	assert_eq!(calls.load(Ordering::Relaxed), 3);
+
+	assert_eq!(hc.get(0), &None);
+	assert_eq!(hc.get(1), &None);
+	assert_eq!(calls.load(Ordering::Relaxed), 3);
+
+	assert!(hc.is_negative(&0));
+	assert!(hc.is_negative(&1));
+	assert!(!hc.is_negative(&2));
+	assert!(!hc.is_negative(&5));
+}
+
+#[test]
+fn recursive_iterative() {
+	let mut hc = GenericCache::<HashMap<usize, u64>>::recursive_iterative(
+		|x| match x {
+			0 | 1 => vec![],
+			_ => vec![x - 1, x - 2],
+		},
+		|cache, x| match x {
+			0 => 0,
+			1 => 1,
+			_ => cache.get(x - 1).wrapping_add(*cache.get(x - 2)),
+		},
+	);
+
+	test_fib(&mut hc);
+
+	// deep enough to overflow the stack if computed recursively
+	let n = 200_000;
+	let expected = (2..=n)
+		.fold((0u64, 1u64), |(a, b), _| (b, a.wrapping_add(b)))
+		.1;
+
+	assert_eq!(hc.get(n), &expected);
+	assert_eq!(hc.len(), n + 1);
+}
+
+#[test]
+#[should_panic(expected = "cyclic dependency")]
+fn recursive_iterative_cycle() {
+	// 5 depends on 4, which depends on 5 again through 3
+	let mut hc = GenericCache::<HashMap<usize, usize>>::recursive_iterative(
+		|x| match x {
+			0 => vec![],
+			3 => vec![5],
+			_ => vec![x - 1],
+		},
+		|cache, x| match x {
+			0 => 0,
+			_ => cache.get(x - 1) + 1,
+		},
+	);
+
+	hc.get(6);
+}
+
+#[test]
+fn recursive_iterative_shared_dependencies() {
+	// every input depends on all smaller ones, which are pushed many times without a cycle
+	let mut hc = GenericCache::<HashMap<usize, usize>>::recursive_iterative(
+		|x| (0..*x).collect(),
+		|cache, x| (0..*x).map(|d| *cache.get(d)).sum::<usize>() + 1,
+	);
+
+	assert_eq!(hc.get(10), &1024);
+}
+
+#[test]
+fn key_set() {
+	let mut hc = HashCache::<usize, u64>::recursive(|c, x| fib(c, x));
+
+	assert!(hc.key_set().is_empty());
+
+	hc.get(4);
+	hc.get(7);
+	hc.remove(&2);
+
+	assert_eq!(hc.key_set(), HashSet::from([&0, &1, &3, &4, &5, &6, &7]));
+}
+
+#[test]
+fn get_many_empty() {
+	let mut hc = HashCache::<usize, u64>::new(square);
+
+	assert_eq!(hc.get_many([]), [] as [&u64; 0]);
+	assert_eq!(hc.len(), 0);
+
+	hc.get(3);
+
+	assert_eq!(hc.get_many([]), [] as [&u64; 0]);
+	assert_eq!(hc.len(), 1);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn get_and_persist() {
+	let mut hc = HashCache::<usize, u64>::new(square);
+	let mut writer = Vec::new();
+
+	assert_eq!(hc.get_and_persist(3, &mut writer).unwrap(), &9);
+	assert_eq!(hc.get_and_persist(4, &mut writer).unwrap(), &16);
+	assert_eq!(hc.get_and_persist(3, &mut writer).unwrap(), &9);
+
+	assert_eq!(writer, b"[3,9]\n[4,16]\n[3,9]\n");
+	assert_eq!(hc.len(), 2);
+}
+
+#[test]
+fn recursive_prefetching() {
+	let calls = AtomicUsize::new(0);
+
+	let mut hc = GenericCache::<HashMap<usize, usize>>::recursive_prefetching(|_, x| {
+		calls.fetch_add(1, Ordering::Relaxed);
+		(x * 10, if *x == 5 { vec![6, 7] } else { vec![] })
+	});
+
+	assert_eq!(hc.get(5), &50);
+	assert_eq!(calls.load(Ordering::Relaxed), 3);
+
+	assert_eq!(hc.get_entry(6), (false, &60));
+	assert_eq!(hc.get_entry(7), (false, &70));
+	assert_eq!(calls.load(Ordering::Relaxed), 3);
+}
+
+#[test]
+fn recursive_prefetching_pending_hints() {
+	let calls = AtomicUsize::new(0);
+
+	// each input hints itself, and the input it was reached from, which are both still pending
+	let mut hc = GenericCache::<HashMap<usize, usize>>::recursive_prefetching(|cache, x| {
+		calls.fetch_add(1, Ordering::Relaxed);
+		let output = match x {
+			0 => 0,
+			_ => cache.get(x - 1) + 1,
+		};
+		(output, vec![*x, (x + 1).min(3)])
+	});
+
+	assert_eq!(hc.get(3), &3);
+	assert_eq!(calls.load(Ordering::Relaxed), 4);
+	assert_eq!(hc.len(), 4);
+}
+
+#[cfg(feature = "debug_trace")]
+#[test]
+fn collision_stats() {
+	use std::hash::Hasher;
+
+	#[derive(Default)]
+	struct FirstByteHasher(u64);
+
+	impl Hasher for FirstByteHasher {
+		fn finish(&self) -> u64 {
+			self.0
+		}
+
+		fn write(&mut self, bytes: &[u8]) {
+			self.0 = bytes[0] as u64 % 4;
+		}
+	}
+
+	let mut good = HashCache::new(square);
+	let mut poor = HashCache::with_hasher(BuildHasherDefault::<FirstByteHasher>::default(), square);
+
+	for i in 0..100 {
+		good.get(i);
+		poor.get(i);
+	}
+
+	let good = good.collision_stats();
+	let poor = poor.collision_stats();
+
+	assert_eq!(good.keys, 100);
+	assert_eq!(poor.keys, 100);
+	assert_eq!(poor.occupied_buckets, 4);
+	assert_eq!(poor.max_bucket_len, 25);
+	assert!(good.max_bucket_len < poor.max_bucket_len);
+	assert!(good.collision_rate() < poor.collision_rate());
+}
+
+#[test]
+fn diff() {
+	let mut old = HashCache::<usize, u64>::new(square);
+	let mut new = HashCache::<usize, u64>::new(|x| match x {
+		3 | 7 => 0,
+		_ => square(x),
+	});
+
+	for i in 0..10 {
+		old.get(i);
+		new.get(i + 2);
+	}
+
+	let mut diff = old.diff(&new);
+	diff.only_left.sort();
+	diff.only_right.sort();
+	diff.changed.sort();
+
+	assert_eq!(diff.only_left, [0, 1]);
+	assert_eq!(diff.only_right, [10, 11]);
+	assert_eq!(diff.changed, [(3, 9, 0), (7, 49, 0)]);
+
+	assert!(old.diff(&old).changed.is_empty());
+}
+
+#[test]
+fn diff_partial_eq() {
+	// the outputs are only PartialEq, and NaN is never equal to itself
+	let mut left = HashCache::<usize, f64>::new(|x| *x as f64);
+	let mut right = HashCache::<usize, f64>::new(|x| match x {
+		1 => f64::NAN,
+		_ => *x as f64,
+	});
+
+	for i in 0..3 {
+		left.get(i);
+		right.get(i);
+	}
+
+	let diff = left.diff(&right);
+
+	assert_eq!(diff.changed.len(), 1);
+	assert_eq!(diff.changed[0].0, 1);
+	assert_eq!(diff.changed[0].1, 1.0);
+	assert!(diff.changed[0].2.is_nan());
+}
+
+#[test]
+fn warm_cancellable() {
+	use std::sync::atomic::AtomicBool;
+	use std::thread;
+	use std::time::Duration;
+
+	let token = AtomicBool::new(false);
+	let mut hc = HashCache::<usize, u64>::new(|x| {
+		thread::sleep(Duration::from_millis(10));
+		square(x)
+	});
+
+	hc.get(0);
+
+	let completed = thread::scope(|s| {
+		s.spawn(|| {
+			thread::sleep(Duration::from_millis(55));
+			token.store(true, Ordering::Relaxed);
+		});
+
+		hc.warm_cancellable(0..100, &token)
+	});
+
+	assert!(completed > 1 && completed < 100);
+	assert_eq!(hc.len(), completed);
+	assert!((0..completed).all(|i| hc.cache().has(&i)));
+
+	// cancelled, so nothing more is computed
+	assert_eq!(hc.warm_cancellable(100..200, &token), 0);
+	assert_eq!(hc.len(), completed);
+}
+
+#[test]
+fn chunks() {
+	let mut hc = HashCache::<usize, u64>::new(square);
+
+	for i in 0..95 {
+		hc.get(i);
+	}
+
+	let chunks: Vec<_> = hc.chunks(10).collect();
+
+	assert_eq!(chunks.len(), 10);
+	assert!(chunks[..9].iter().all(|c| c.len() == 10));
+	assert_eq!(chunks.iter().map(Vec::len).sum::<usize>(), 95);
+
+	let keys: HashSet<_> = chunks
+		.into_iter()
+		.flatten()
+		.map(|(i, o)| {
+			assert_eq!(*o, square(i));
+			*i
+		})
+		.collect();
+
+	assert_eq!(keys, (0..95).collect());
+}
+
+#[test]
+fn fold() {
+	let mut hc = HashCache::<usize, u64>::new(square);
+
+	assert_eq!(hc.fold(0, |sum, _, o| sum + o), 0);
+
+	test_square(&mut hc);
+
+	assert_eq!(hc.fold(0, |sum, _, o| sum + o), 1 + 4 + 25 + 100);
+	assert_eq!(hc.len(), hc.fold(0, |n, _, _| n + 1));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serialize_and_load_entries() {
+	let mut hc = HashCache::<usize, u64>::new(square);
+
+	test_square(&mut hc);
+
+	let mut json = Vec::new();
+	hc.serialize_entries(&mut serde_json::Serializer::new(&mut json))
+		.unwrap();
+
+	let calls = AtomicUsize::new(0);
+	let mut loaded = HashCache::<usize, u64>::load_entries(
+		&mut serde_json::Deserializer::from_slice(&json),
+		|x| {
+			calls.fetch_add(1, Ordering::Relaxed);
+			square(x)
+		},
+	)
+	.unwrap();
+
+	assert_eq!(loaded.len(), hc.len());
+
+	for (i, o) in hc.iter() {
+		assert_eq!(loaded.get(*i), o);
+	}
+
+	assert_eq!(calls.load(Ordering::Relaxed), 0);
+
+	assert_eq!(loaded.get(1000), &1_000_000);
+	assert_eq!(calls.load(Ordering::Relaxed), 1);
+}
+
+#[test]
+fn insert_if_absent() {
+	let mut hc = HashCache::<usize, u64>::recursive(|c, x| fib(c, x));
+
+	// wrong base case, to show it is used instead of the function
+	assert_eq!(hc.insert_if_absent(0, 2), &2);
+	assert_eq!(hc.insert_if_absent(0, 3), &2);
+
+	assert_eq!(hc.get(3), &(1 + 1 + 2));
+	assert_eq!(hc.len(), 4);
+}
+
+#[test]
+fn get_or_insert_with() {
+	let mut hc = HashCache::<usize, u64>::recursive(|c, x| fib(c, x));
+
+	// a stub which the function then builds on
+	assert_eq!(hc.get_or_insert_with(1, |_| 10), &10);
+	assert_eq!(hc.get(3), &20);
+
+	// present, so the override is ignored
+	assert_eq!(hc.get_or_insert_with(3, |_| unreachable!()), &20);
+}
+
+#[test]
+fn get_many_vec() {
+	let mut hc = HashCache::<usize, u64>::recursive(|c, x| match x {
+		0 => 0,
+		1 => 1,
+		_ => {
+			let inputs: Vec<_> = (x - 2..*x).collect();
+			c.get_many_vec(&inputs).into_iter().sum()
+		}
+	});
+
+	let inputs: Vec<_> = (0..20).rev().collect();
+
+	assert_eq!(hc.get_many_vec(&inputs)[0], &4181);
+	assert_eq!(hc.get_many_vec(&[]), Vec::<&u64>::new());
+	assert_eq!(hc.get_many_vec(&[10, 10, 12]), [&55, &55, &144]);
+}
+
+#[test]
+fn get_many_without_peek() {
+	// a cache which cannot peek still only needs get_many to implement FnCacheMany
+	struct OnlyMany<'f>(HashCache<'f, usize, u64>);
+
+	impl FnCache<usize, u64> for OnlyMany<'_> {
+		fn get(&mut self, input: usize) -> &u64 {
+			self.0.get(input)
+		}
+	}
+
+	impl FnCacheMany<usize, u64> for OnlyMany<'_> {
+		fn get_many<const N: usize>(&mut self, inputs: [usize; N]) -> [&u64; N] {
+			self.0.get_many(inputs)
+		}
+	}
+
+	let mut cache = OnlyMany(HashCache::new(square));
+
+	assert_eq!(cache.get_two(2, 3), (&4, &9));
+}
+
+#[test]
+fn contains() {
+	fn get_if_cached(cache: &mut impl FnCachePeek<usize, u64>, x: usize) -> Option<u64> {
+		cache.contains(&x).then(|| *cache.get(x))
+	}
+
+	let mut hc = HashCache::<usize, u64>::recursive(|c, x| {
+		// a recursive function can check too
+		assert!(!c.contains(x));
+		fib(c, x)
+	});
+
+	assert_eq!(get_if_cached(&mut *hc, 5), None);
+
+	hc.get(5);
+
+	assert_eq!(get_if_cached(&mut *hc, 5), Some(5));
+	assert_eq!(get_if_cached(&mut *hc, 6), None);
+}
+
+#[test]
+fn contains_default() {
+	struct OnlyPeek<'f>(HashCache<'f, usize, u64>);
+
+	impl FnCache<usize, u64> for OnlyPeek<'_> {
+		fn get(&mut self, input: usize) -> &u64 {
+			self.0.get(input)
+		}
+	}
+
+	impl FnCachePeek<usize, u64> for OnlyPeek<'_> {
+		fn peek(&self, input: &usize) -> Option<&u64> {
+			self.0.peek(input)
+		}
+	}
+
+	let mut cache = OnlyPeek(HashCache::new(square));
+
+	assert!(!cache.contains(&3));
+
+	cache.get(3);
+
+	assert!(cache.contains(&3));
+	assert!(!cache.contains(&4));
+}
+
+#[test]
+fn drain() {
+	let mut hc = HashCache::<usize, u64>::new(square);
+
+	test_square(&mut hc);
+
+	let len = hc.len();
+	let capacity = hc.cache().capacity();
+
+	let mut drained: Vec<_> = hc.drain().collect();
+	drained.sort();
+
+	assert_eq!(drained.len(), len);
+	assert!(drained.iter().all(|(i, o)| *o == square(i)));
+	assert_eq!(hc.len(), 0);
+	assert_eq!(hc.cache().capacity(), capacity);
+}
+
+#[test]
+fn as_ref_cache() {
+	fn fib_sum(cache: &mut RefCache<HashMap<usize, u64>>, x: usize) -> u64 {
+		(0..=x).map(|i| *cache.get(i)).sum()
+	}
+
+	let mut hc = HashCache::<usize, u64>::recursive(|c, x| match x {
+		0 => 0,
+		1 => 1,
+		// the helper is reused inside the recursion as fib(x) = fib_sum(x - 2) + 1
+		_ => fib_sum(c, x - 2) + 1,
+	});
+
+	assert_eq!(fib_sum(&mut hc.as_ref_cache(), 6), 20);
+	assert_eq!(hc.get(10), &55);
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn get_many_par() {
+	let calls = AtomicUsize::new(0);
+
+	let mut hc = HashCache::new(|x: &usize| {
+		calls.fetch_add(1, Ordering::Relaxed);
+		square(x)
+	});
+
+	hc.get(2);
+
+	assert_eq!(hc.get_many_par(&[1, 2, 3, 1]), [&1, &4, &9, &1]);
+	assert_eq!(calls.load(Ordering::Relaxed), 3);
+	assert_eq!(hc.len(), 3);
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn get_many_par_large_batch() {
+	let calls = AtomicUsize::new(0);
+
+	let mut hc = HashCache::new(|x: &usize| {
+		calls.fetch_add(1, Ordering::Relaxed);
+		square(x)
+	});
+
+	let inputs: Vec<usize> = (0..100_000).map(|i| i % 10_000).collect();
+	let outputs = hc.get_many_par(&inputs);
+
+	assert!(outputs.iter().zip(&inputs).all(|(o, i)| **o == square(i)));
+	assert_eq!(calls.load(Ordering::Relaxed), 10_000);
+	assert_eq!(hc.len(), 10_000);
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn get_many_par_recursive() {
+	let mut hc = HashCache::<usize, u64>::recursive(|c, x| fib(c, x));
+
+	assert_eq!(hc.get_many_par(&[10, 5]), [&55, &5]);
+	assert_eq!(hc.len(), 11);
+}
+
+#[test]
+#[should_panic(expected = "before it was set")]
+fn uninitialized_get() {
+	let mut gc = GenericCache::<HashMap<usize, u64>>::uninitialized();
+
+	gc.get(1);
+}
+
+#[test]
+fn uninitialized_set_fn() {
+	let mut gc = GenericCache::<HashMap<usize, u64>>::uninitialized();
+
+	assert_eq!(gc.try_get(1), Err(Uninitialized));
+	assert!(gc.is_empty());
+
+	gc.insert_if_absent(1, 10);
+
+	// a cached value needs no function
+	assert_eq!(gc.try_get(1), Ok(&10));
+	gc.remove(&1);
+
+	gc.set_recursive_fn(|c, x| fib(c, x));
+
+	test_fib(&mut gc);
+
+	gc.set_fn(square);
+
+	// values computed by the old function are kept
+	assert_eq!(gc.get(5), &5);
+	assert_eq!(gc.get(20), &400);
+}
+
+#[test]
+fn by_value() {
+	let mut hc = HashCache::<usize, u64>::new(square);
+
+	assert_eq!(hc.max_by_value(), None);
+	assert_eq!(hc.min_by_value(), None);
+
+	test_square(&mut hc);
+
+	assert_eq!(hc.max_by_value(), Some((&10, &100)));
+	assert_eq!(hc.min_by_value(), Some((&1, &1)));
+
+	let mut ties = HashCache::<usize, u64>::new(|x| *x as u64 % 3);
+
+	for i in 0..10 {
+		ties.get(i);
+	}
+
+	assert_eq!(ties.max_by_value(), Some((&2, &2)));
+	assert_eq!(ties.min_by_value(), Some((&0, &0)));
+}
+
+#[test]
+fn shrink_to_fit() {
+	let mut hc = HashCache::<usize, u64>::new(square);
+
+	for i in 0..1000 {
+		hc.get(i);
+	}
+
+	for i in 10..1000 {
+		hc.remove(&i);
+	}
+
+	let before = hc.capacity();
+	hc.shrink_to_fit();
+
+	assert!(hc.capacity() < before);
+	assert!(hc.capacity() >= 10);
+	assert_eq!(hc.get(5), &25);
+}
+
+#[test]
+fn rebuild_into() {
+	use crate::BTreeCache;
+
+	let calls = AtomicUsize::new(0);
+
+	let mut hc = HashCache::<usize, u64>::new(square);
+
+	test_square(&mut hc);
+
+	let mut bc: BTreeCache<usize, u64> = hc.rebuild_into(|x| {
+		calls.fetch_add(1, Ordering::Relaxed);
+		square(x)
+	});
+
+	assert_eq!(bc.key_vec(), [&1, &2, &5, &10]);
+	assert_eq!(bc.get(10), &100);
+	assert_eq!(calls.load(Ordering::Relaxed), 0);
+
+	assert_eq!(bc.get(3), &9);
+	assert_eq!(calls.load(Ordering::Relaxed), 1);
+	assert_eq!(hc.len(), 4);
+}
+
+#[test]
+fn warm_prioritized() {
+	let mut hc = HashCache::<usize, u64>::new(square);
+
+	hc.get(4);
+
+	let inputs = vec![(1, 10), (2, 50), (3, 30), (4, 40), (5, 20)];
+
+	// 4 is already cached, so only 2 and 3 fit in the budget
+	assert_eq!(hc.warm_prioritized(inputs.clone(), 2), 2);
+
+	let mut keys: Vec<_> = hc.keys().copied().collect();
+	keys.sort();
+
+	assert_eq!(keys, [2, 3, 4]);
+
+	assert_eq!(hc.warm_prioritized(inputs, 10), 2);
+	assert_eq!(hc.len(), 5);
+}
+
+#[test]
+fn fork_empty() {
+	let mut hc = HashCache::<usize, u64>::recursive(|c, x| fib(c, x));
+
+	hc.get(10);
+
+	let mut fork = hc.fork_empty();
+
+	assert!(fork.is_empty());
+
+	test_fib(&mut fork);
+
+	assert_eq!(hc.len(), 11);
+}
+
+#[test]
+fn fork_empty_parallel() {
+	let computing = AtomicUsize::new(0);
+
+	// each call waits up to a second for the other fork to be computing at the same time
+	let hc = HashCache::<usize, bool>::new(|_| {
+		computing.fetch_add(1, Ordering::SeqCst);
+
+		let start = Instant::now();
+
+		while computing.load(Ordering::SeqCst) < 2 && start.elapsed() < Duration::from_secs(1) {
+			thread::yield_now();
+		}
+
+		computing.load(Ordering::SeqCst) >= 2
+	});
+
+	let mut forks = [hc.fork_empty(), hc.fork_empty()];
+
+	thread::scope(|s| {
+		for (i, fork) in forks.iter_mut().enumerate() {
+			s.spawn(move || assert!(*fork.get(i)));
+		}
+	});
+}
@@ -0,0 +1,45 @@
+use crate::index_cache::IndexCache;
+use crate::tests::*;
+use crate::FnCache;
+
+#[test]
+fn get_fn_ptr() {
+	let mut ic = IndexCache::<usize, u64>::new(square);
+
+	test_square(&mut ic);
+}
+
+#[test]
+fn get_closure_recursive() {
+	let mut ic = IndexCache::<usize, u64>::recursive(|c, x| fib(c, x));
+
+	test_fib(&mut ic);
+}
+
+#[test]
+fn insertion_order() {
+	let mut ic = IndexCache::<usize, u64>::new(square);
+
+	for i in [7, 2, 9, 4] {
+		ic.get(i);
+	}
+
+	assert_eq!(ic.key_vec(), [&7, &2, &9, &4]);
+}
+
+#[test]
+fn remove_keeps_order() {
+	let mut ic = IndexCache::<usize, u64>::new(square);
+
+	for i in [7, 2, 9, 4] {
+		ic.get(i);
+	}
+
+	assert_eq!(ic.remove(&2), Some(4));
+	assert_eq!(ic.key_vec(), [&7, &9, &4]);
+
+	ic.reserve(10);
+	ic.clear();
+
+	assert!(ic.is_empty());
+}
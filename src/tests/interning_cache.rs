@@ -0,0 +1,81 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use crate::container::ContainerLen;
+use crate::{FnCache, InterningCache};
+
+#[test]
+fn shared_allocation() {
+	let mut ic = InterningCache::new(|x: &usize| vec![x % 3; 1024]);
+
+	for i in 0..100 {
+		ic.get(i);
+	}
+
+	assert_eq!(ic.len(), 100);
+	assert_eq!(ic.cache().pool_len(), 3);
+
+	let a = ic.get(1).clone();
+	let b = ic.get(4).clone();
+	let c = ic.get(2).clone();
+
+	assert!(Arc::ptr_eq(&a, &b));
+	assert!(!Arc::ptr_eq(&a, &c));
+	assert_eq!(*a, vec![1; 1024]);
+}
+
+#[test]
+fn recursive() {
+	let calls = AtomicUsize::new(0);
+
+	let mut ic = InterningCache::<usize, u64>::recursive(|cache, x| {
+		calls.fetch_add(1, Ordering::Relaxed);
+		match x {
+			0 => 1,
+			_ => **cache.get(x - 1),
+		}
+	});
+
+	assert_eq!(**ic.get(10), 1);
+	assert_eq!(calls.load(Ordering::Relaxed), 11);
+	assert_eq!(ic.cache().len(), 11);
+	assert_eq!(ic.cache().pool_len(), 1);
+}
+
+#[test]
+fn remove() {
+	let mut ic = InterningCache::new(|x: &usize| x % 2);
+
+	ic.get(0);
+	ic.get(1);
+	ic.get(3);
+
+	assert_eq!(ic.cache().pool_len(), 2);
+
+	ic.remove(&1);
+	assert_eq!(ic.cache().pool_len(), 2);
+
+	ic.remove(&3);
+	assert_eq!(ic.cache().pool_len(), 1);
+
+	ic.clear();
+	assert_eq!(ic.cache().pool_len(), 0);
+}
+
+#[test]
+fn remove_while_held() {
+	let mut ic = InterningCache::new(|x: &usize| x % 2);
+
+	let held = ic.get(1).clone();
+	ic.get(3);
+
+	ic.remove(&1);
+	ic.remove(&3);
+
+	assert_eq!(ic.cache().pool_len(), 0);
+	assert_eq!(*held, 1);
+
+	// inserting again does not reuse the output which is still held
+	assert!(!Arc::ptr_eq(ic.get(5), &held));
+	assert_eq!(ic.cache().pool_len(), 1);
+}
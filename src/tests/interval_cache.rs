@@ -0,0 +1,67 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::interval_cache::IntervalCache;
+
+#[test]
+fn get_containing() {
+	let calls = AtomicUsize::new(0);
+
+	let mut ic = IntervalCache::new(|x: &i32| {
+		calls.fetch_add(1, Ordering::Relaxed);
+		let start = x.div_euclid(10) * 10;
+		(start..start + 10, start)
+	});
+
+	assert_eq!(ic.get_containing(&15), Some(&10));
+	assert_eq!(ic.get_containing(&-3), Some(&-10));
+	assert_eq!(calls.load(Ordering::Relaxed), 2);
+
+	// inside the cached intervals
+	for x in [10, 12, 19, -10, -1] {
+		assert_eq!(ic.get_cached(&x), Some(&(x.div_euclid(10) * 10)));
+	}
+
+	// outside them
+	for x in [0, 9, 20, -11] {
+		assert_eq!(ic.get_cached(&x), None);
+	}
+
+	assert_eq!(calls.load(Ordering::Relaxed), 2);
+	assert_eq!(ic.get_containing(&25), Some(&20));
+	assert_eq!(calls.load(Ordering::Relaxed), 3);
+	assert_eq!(ic.len(), 3);
+}
+
+#[test]
+fn nested_intervals() {
+	let mut ic = IntervalCache::new(|x: &u32| match x {
+		0..=99 => (0..100, "wide"),
+		_ => (*x..*x + 1, "point"),
+	});
+
+	ic.get_containing(&50);
+	ic.get_containing(&200);
+
+	// a short interval after a long one must not hide the long one
+	assert_eq!(ic.get_cached(&150), None);
+	assert_eq!(ic.get_cached(&99), Some(&"wide"));
+	assert_eq!(ic.get_cached(&200), Some(&"point"));
+
+	let mut ic = IntervalCache::new(|x: &u32| (*x..x + 1, *x));
+
+	ic.get_containing(&5);
+	ic.get_containing(&1);
+	ic.get_containing(&3);
+
+	assert_eq!(ic.get_cached(&3), Some(&3));
+	assert_eq!(ic.get_cached(&2), None);
+}
+
+#[test]
+fn interval_not_containing_point() {
+	let mut ic = IntervalCache::new(|_: &u32| (0..1, ()));
+
+	assert_eq!(ic.get_containing(&5), None);
+	assert_eq!(ic.len(), 1);
+	assert_eq!(ic.get_cached(&0), Some(&()));
+}
@@ -0,0 +1,34 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::{BTreeCache, MemoizedIteratorExt, VecCache};
+
+#[test]
+fn memoized_map_once_per_value() {
+	let calls = AtomicUsize::new(0);
+
+	let mut bc = BTreeCache::new(|x: &usize| {
+		calls.fetch_add(1, Ordering::Relaxed);
+		x * 2
+	});
+
+	let doubled: Vec<_> = (0..20)
+		.map(|x| x % 4)
+		.memoized_map(&mut bc, |x| x)
+		.collect();
+
+	assert_eq!(doubled, (0..20).map(|x| x % 4 * 2).collect::<Vec<_>>());
+	assert_eq!(calls.load(Ordering::Relaxed), 4);
+}
+
+#[test]
+fn memoized_map_key_fn() {
+	let mut vc = VecCache::new(|x: &usize| x.to_string());
+
+	let strings: Vec<_> = ["a", "bb", "c", "ddd"]
+		.into_iter()
+		.memoized_map(&mut vc, |s| s.len())
+		.collect();
+
+	assert_eq!(strings, ["1", "2", "1", "3"]);
+	assert_eq!(vc.len(), 4);
+}
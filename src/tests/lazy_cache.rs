@@ -0,0 +1,89 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use crate::tests::*;
+use crate::{FnCache, FnCacheMany, LazyCache};
+
+#[test]
+fn get_fn_ptr() {
+	let mut lc = LazyCache::new(square);
+
+	test_square(&mut lc);
+}
+
+#[test]
+fn get_closure_recursive() {
+	let mut lc = LazyCache::<usize, u64>::recursive(|c, x| fib(c, x));
+
+	test_fib(&mut lc);
+}
+
+#[test]
+fn force_runs_thunk_once() {
+	let runs = Arc::new(AtomicUsize::new(0));
+	let mut lc = LazyCache::new(square);
+
+	let thunk_runs = runs.clone();
+	lc.insert_lazy(3, move || {
+		thunk_runs.fetch_add(1, Ordering::Relaxed);
+		100
+	});
+
+	assert_eq!(runs.load(Ordering::Relaxed), 0);
+	assert_eq!(lc.len(), 1);
+
+	assert_eq!(lc.force(&3), &100);
+	assert_eq!(runs.load(Ordering::Relaxed), 1);
+
+	assert_eq!(lc.get(3), &100);
+	assert_eq!(lc.get_many([3, 3]), [&100, &100]);
+	assert_eq!(lc.force(&3), &100);
+	assert_eq!(runs.load(Ordering::Relaxed), 1);
+	assert_eq!(lc.len(), 1);
+}
+
+#[test]
+fn force_without_thunk() {
+	let mut lc = LazyCache::new(square);
+
+	assert_eq!(lc.force(&4), &16);
+	assert!(!lc.cache().is_pending(&4));
+}
+
+#[test]
+fn recursive_uses_thunk() {
+	let mut lc = LazyCache::<usize, u64>::recursive(|c, x| fib(c, x));
+
+	// a wrong value, to show the function used it instead of computing its own
+	lc.insert_lazy(3, || 100);
+
+	assert_eq!(lc.get(5), &(100 + 100 + 1));
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn force_all_parallel() {
+	let runs = Arc::new(AtomicUsize::new(0));
+
+	let mut lc = LazyCache::<usize, u64>::new(|_| unreachable!());
+
+	for i in 0..100 {
+		let runs = runs.clone();
+		lc.insert_lazy(i, move || {
+			runs.fetch_add(1, Ordering::Relaxed);
+			square(&i)
+		});
+	}
+
+	lc.force_all_parallel();
+
+	assert_eq!(runs.load(Ordering::Relaxed), 100);
+	assert_eq!(lc.len(), 100);
+
+	for i in 0..100 {
+		assert!(!lc.cache().is_pending(&i));
+		assert_eq!(lc.get(i), &square(&i));
+	}
+
+	assert_eq!(runs.load(Ordering::Relaxed), 100);
+}
@@ -0,0 +1,101 @@
+use crate::container::SparseContainer;
+use crate::tests::*;
+use crate::{FnCache, FnCacheMany, FnCachePeek, LfuCache};
+
+#[test]
+fn get_fn_ptr() {
+	let mut lc = LfuCache::with_capacity(10, square);
+
+	test_square(&mut lc);
+}
+
+#[test]
+fn get_closure_recursive() {
+	let mut lc = LfuCache::<usize, u64>::recursive_with_capacity(20, |c, x| fib(c, x));
+
+	test_fib(&mut lc);
+}
+
+#[test]
+fn hot_keys_survive() {
+	let mut lc = LfuCache::with_capacity(3, square);
+
+	for _ in 0..5 {
+		lc.get(1);
+		lc.get(2);
+	}
+
+	assert_eq!(lc.cache().uses(&1), Some(5));
+
+	// each one-off key only evicts the previous one-off key, however recently the hot keys were
+	// used
+	for i in 10..20 {
+		lc.get(i);
+	}
+
+	assert!(lc.cache().has(&1));
+	assert!(lc.cache().has(&2));
+	assert!(lc.cache().has(&19));
+	assert!(!lc.cache().has(&18));
+	assert_eq!(lc.len(), 3);
+}
+
+#[test]
+fn ties_evict_least_recent() {
+	let mut lc = LfuCache::with_capacity(2, square);
+
+	lc.get(1);
+	lc.get(2);
+	lc.get(1);
+	lc.get(2);
+	lc.get(3);
+
+	assert!(!lc.cache().has(&1));
+	assert!(lc.cache().has(&2));
+	assert!(lc.cache().has(&3));
+}
+
+#[test]
+fn remove() {
+	let mut lc = LfuCache::with_capacity(2, square);
+
+	lc.get(1);
+	lc.get(2);
+
+	assert_eq!(lc.remove(&1), Some(1));
+
+	lc.get(3);
+
+	assert!(lc.cache().has(&2));
+	assert!(lc.cache().has(&3));
+	assert_eq!(lc.capacity(), 2);
+}
+
+#[test]
+fn would_evict() {
+	let mut lc = LfuCache::with_capacity(3, square);
+
+	lc.get(1);
+	lc.get(1);
+	lc.get(2);
+	assert_eq!(lc.would_evict(&3), None);
+
+	lc.get(3);
+	lc.get(3);
+	assert_eq!(lc.would_evict(&3), None);
+
+	let predicted = *lc.would_evict(&4).unwrap();
+	assert_eq!(predicted, 2);
+
+	lc.get(4);
+	assert!(!lc.contains(&predicted));
+	assert_eq!(lc.len(), 3);
+}
+
+#[test]
+#[should_panic]
+fn get_many_over_capacity() {
+	let mut lc = LfuCache::with_capacity(2, square);
+
+	lc.get_many([1, 2, 3]);
+}
@@ -0,0 +1,87 @@
+use std::collections::HashMap;
+
+use crate::container::SparseContainer;
+use crate::deps_cache::Deps;
+use crate::limit_cache::{LimitCache, TooManyEntries};
+use crate::TryFnCache;
+
+#[test]
+fn within_limit() {
+	let mut lc = LimitCache::<HashMap<u64, u64>>::recursive(13, |c, x| match x {
+		0 => Ok(0),
+		1 => Ok(1),
+		_ => Ok(*c.try_get(x - 1)? + *c.try_get(x - 2)?),
+	});
+
+	assert_eq!(lc.try_get(10), Ok(&55));
+	assert_eq!(lc.try_get(12), Ok(&144));
+	assert_eq!(lc.cache().len(), 13);
+}
+
+#[test]
+fn crafted_input() {
+	// each input needs every smaller input, so a large input needs a large cache
+	let mut lc = LimitCache::<HashMap<u64, u64>>::recursive(100, |c, x| match x {
+		0 => Ok(0),
+		_ => Ok(*c.try_get(x - 1)? + 1),
+	});
+
+	assert_eq!(lc.try_get(1_000_000), Err(TooManyEntries));
+	assert_eq!(lc.cache().len(), 0);
+
+	// the limit still allows smaller inputs
+	assert_eq!(lc.try_get(99), Ok(&99));
+	assert_eq!(lc.cache().len(), 100);
+	assert_eq!(lc.try_get(100), Err(TooManyEntries));
+	assert!(!lc.cache().has(&100));
+
+	lc.set_max_entries_during_compute(101);
+	assert_eq!(lc.try_get(100), Ok(&100));
+}
+
+#[test]
+fn records_dependencies() {
+	let mut lc = LimitCache::<Deps<HashMap<u64, u64>>>::recursive(10, |c, x| match x {
+		0 => Ok(0),
+		1 => Ok(1),
+		_ => Ok(*c.try_get(x - 1)? + *c.try_get(x - 2)?),
+	});
+
+	lc.try_get(4).unwrap();
+
+	// the value asked for records what it read, the same as those it recursed into
+	assert_eq!(lc.cache().dependencies(&4), Some(&[3, 2][..]));
+	assert_eq!(lc.cache().dependencies(&3), Some(&[2, 1][..]));
+}
+
+#[derive(Debug, PartialEq)]
+enum CollatzError {
+	Zero,
+	TooManyEntries,
+}
+
+impl From<TooManyEntries> for CollatzError {
+	fn from(_: TooManyEntries) -> Self {
+		CollatzError::TooManyEntries
+	}
+}
+
+#[test]
+fn custom_error() {
+	// the number of steps for the collatz sequence to reach one
+	let mut lc = LimitCache::<HashMap<u64, u64>, CollatzError>::recursive(10, |c, x| match x {
+		0 => Err(CollatzError::Zero),
+		1 => Ok(0),
+		x if x % 2 == 0 => Ok(*c.try_get(x / 2)? + 1),
+		x => Ok(*c.try_get(3 * x + 1)? + 1),
+	});
+
+	assert_eq!(lc.try_get(0), Err(CollatzError::Zero));
+	assert_eq!(lc.try_get(6), Ok(&8));
+	assert_eq!(lc.cache().len(), 9);
+	assert_eq!(
+		TryFnCache::try_get(&mut lc, 7),
+		Err(CollatzError::TooManyEntries)
+	);
+	assert_eq!(lc.cache().len(), 9);
+}
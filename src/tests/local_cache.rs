@@ -0,0 +1,49 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::local_cache::LocalHashCache;
+use crate::tests::{fib, square};
+use crate::{FnCache, FnCacheMany};
+
+#[test]
+fn get_fn_ptr() {
+	let mut lc = LocalHashCache::new(square);
+
+	assert_eq!(lc.get(5), &25);
+	assert_eq!(lc.get_many([2, 5]), [&4, &25]);
+	assert_eq!(lc.len(), 2);
+}
+
+#[test]
+fn get_closure_recursive() {
+	let mut lc = LocalHashCache::<usize, u64>::recursive(|c, x| fib(c, x));
+
+	assert_eq!(lc.get(10), &55);
+	assert_eq!(lc.len(), 11);
+	assert_eq!(lc.get_many_vec(&[12, 3]), [&144, &2]);
+}
+
+#[test]
+fn captures_rc_refcell() {
+	let calls = Rc::new(RefCell::new(Vec::new()));
+	let log = calls.clone();
+
+	let mut lc = LocalHashCache::<usize, u64>::recursive(move |c, x| {
+		log.borrow_mut().push(*x);
+		fib(c, x)
+	});
+
+	lc.get(4);
+	lc.get(3);
+
+	assert_eq!(*calls.borrow(), [4, 3, 2, 1, 0]);
+
+	assert_eq!(lc.remove(&4), Some(3));
+	lc.get(4);
+
+	assert_eq!(*calls.borrow(), [4, 3, 2, 1, 0, 4]);
+
+	lc.clear();
+
+	assert!(lc.is_empty());
+}
@@ -0,0 +1,53 @@
+use std::num::NonZeroUsize;
+
+use lru::LruCache;
+
+use crate::container::SparseContainer;
+use crate::tests::*;
+use crate::{FnCache, FnCacheMany, GenericCache};
+
+fn lru_cache<'f>(capacity: usize) -> GenericCache<'f, LruCache<usize, u64>> {
+	GenericCache::with_cache(LruCache::new(NonZeroUsize::new(capacity).unwrap()), square)
+}
+
+#[test]
+fn get_fn_ptr() {
+	let mut cache = lru_cache(10);
+
+	test_square(&mut cache);
+}
+
+#[test]
+fn eviction_order() {
+	let mut cache = lru_cache(3);
+
+	cache.get(1);
+	cache.get(2);
+	cache.get(3);
+
+	// a hit makes 1 the most recently used
+	cache.get(1);
+	cache.get(4);
+
+	assert!(cache.cache().has(&1));
+	assert!(!cache.cache().has(&2));
+	assert!(cache.cache().has(&3));
+	assert!(cache.cache().has(&4));
+
+	// get_many also counts as a use
+	cache.get_many([3, 1]);
+	cache.get(5);
+
+	assert!(!cache.cache().has(&4));
+	assert_eq!(cache.len(), 3);
+}
+
+#[test]
+fn remove() {
+	let mut cache = lru_cache(3);
+
+	cache.get(1);
+
+	assert_eq!(cache.remove(&1), Some(1));
+	assert_eq!(cache.remove(&1), None);
+}
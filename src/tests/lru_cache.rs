@@ -0,0 +1,238 @@
+use crate::container::SparseContainer;
+use crate::tests::*;
+use crate::{FnCache, FnCacheMany, FnCachePeek, LruCache};
+
+#[test]
+fn get_fn_ptr() {
+	let mut lc = LruCache::with_capacity(10, square);
+
+	test_square(&mut lc);
+}
+
+#[test]
+fn get_closure_recursive() {
+	let mut lc = LruCache::<usize, u64>::recursive_with_capacity(20, |c, x| fib(c, x));
+
+	test_fib(&mut lc);
+}
+
+#[test]
+fn eviction_order() {
+	let mut lc = LruCache::with_capacity(3, square);
+
+	lc.get(1);
+	lc.get(2);
+	lc.get(3);
+
+	// a hit makes 1 the most recently used
+	lc.get(1);
+	lc.get(4);
+
+	assert!(lc.cache().has(&1));
+	assert!(!lc.cache().has(&2));
+	assert!(lc.cache().has(&3));
+	assert!(lc.cache().has(&4));
+	assert_eq!(lc.len(), 3);
+
+	lc.get_many([3, 1]);
+	lc.get(5);
+
+	assert!(!lc.cache().has(&4));
+	assert_eq!(lc.len(), 3);
+}
+
+#[test]
+fn recursive_hits_update_recency() {
+	let mut lc = LruCache::<usize, u64>::recursive_with_capacity(3, |c, x| fib(c, x));
+
+	// 0, 1, 2 are computed, then 1 is read again computing 3, evicting 0
+	lc.get(3);
+
+	assert!(!lc.cache().has(&0));
+	assert!(lc.cache().has(&1));
+	assert!(lc.cache().has(&2));
+	assert!(lc.cache().has(&3));
+}
+
+#[test]
+fn remove() {
+	let mut lc = LruCache::with_capacity(2, square);
+
+	lc.get(1);
+	lc.get(2);
+
+	assert_eq!(lc.remove(&1), Some(1));
+
+	lc.get(3);
+
+	assert!(lc.cache().has(&2));
+	assert!(lc.cache().has(&3));
+}
+
+#[test]
+#[should_panic]
+fn zero_capacity() {
+	LruCache::with_capacity(0, square);
+}
+
+#[test]
+fn on_evict_two_level() {
+	use std::collections::HashMap;
+	use std::sync::{Arc, Mutex};
+
+	use crate::container::ContainerClear;
+	use crate::lru_cache::Lru;
+	use crate::GenericCache;
+
+	// evicted values fall back to a slower, unbounded tier
+	let slow = Arc::new(Mutex::new(HashMap::new()));
+	let tier = slow.clone();
+
+	let mut lc = GenericCache::with_cache(
+		Lru::with_capacity(2).on_evict(move |i, o| {
+			tier.lock().unwrap().insert(i, o);
+		}),
+		square,
+	);
+
+	lc.get(1);
+	lc.get(2);
+	lc.get(3);
+
+	assert_eq!(*slow.lock().unwrap(), HashMap::from([(1, 1)]));
+
+	// removing returns the value instead
+	assert_eq!(lc.remove(&2), Some(4));
+	assert_eq!(slow.lock().unwrap().len(), 1);
+
+	lc.clear();
+	assert_eq!(*slow.lock().unwrap(), HashMap::from([(1, 1), (3, 9)]));
+
+	let count = Arc::new(Mutex::new(0));
+	let evictions = count.clone();
+	let mut lru = Lru::with_capacity(2)
+		.on_evict(move |_: usize, _: u64| *evictions.lock().unwrap() += 1)
+		.evict_on_clear(false);
+
+	lru.put(1, 1);
+	lru.put(2, 4);
+	lru.clear();
+
+	assert_eq!(*count.lock().unwrap(), 0);
+}
+
+#[test]
+fn remove_skips_on_evict() {
+	use std::sync::{Arc, Mutex};
+
+	use crate::container::ContainerRemove;
+	use crate::lru_cache::Lru;
+	use crate::GenericCache;
+
+	let evicted = Arc::new(Mutex::new(Vec::new()));
+	let log = evicted.clone();
+
+	let mut lc = GenericCache::with_cache(
+		Lru::with_capacity(2).on_evict(move |i, o| log.lock().unwrap().push((i, o))),
+		square,
+	);
+
+	lc.get(1);
+	lc.get(2);
+
+	assert_eq!(lc.remove(&1), Some(1));
+	assert_eq!(lc.remove(&1), None);
+
+	// the room left by the removed value is filled without evicting anything
+	lc.get(3);
+
+	assert!(evicted.lock().unwrap().is_empty());
+
+	let mut lru = Lru::with_capacity(1).on_evict(|_: usize, _: u64| panic!("evicted on remove"));
+
+	lru.put(1, 1);
+
+	assert_eq!(lru.remove(&1), Some(1));
+}
+
+#[test]
+fn evict_on_remove() {
+	use std::sync::{Arc, Mutex};
+
+	use crate::container::ContainerRemove;
+	use crate::lru_cache::Lru;
+	use crate::GenericCache;
+
+	let evicted = Arc::new(Mutex::new(Vec::new()));
+	let log = evicted.clone();
+
+	let mut lc = GenericCache::with_cache(
+		Lru::with_capacity(2)
+			.on_evict(move |i, o| log.lock().unwrap().push((i, o)))
+			.evict_on_remove(true),
+		square,
+	);
+
+	lc.get(1);
+	lc.get(2);
+
+	assert_eq!(lc.remove(&1), None);
+	assert_eq!(*evicted.lock().unwrap(), [(1, 1)]);
+
+	assert_eq!(lc.remove(&1), None);
+	assert_eq!(lc.len(), 1);
+
+	lc.get(3);
+	lc.get(4);
+
+	assert_eq!(*evicted.lock().unwrap(), [(1, 1), (2, 4)]);
+
+	// without a function, the value is still returned
+	let mut lru = Lru::with_capacity(1).evict_on_remove(true);
+
+	lru.put(1, 1);
+
+	assert_eq!(lru.remove(&1), Some(1));
+}
+
+#[test]
+fn utilization() {
+	let mut lc = LruCache::with_capacity(4, square);
+
+	assert_eq!(lc.utilization(), 0.0);
+
+	let mut last = 0.0;
+
+	for i in 0..4 {
+		lc.get(i);
+
+		assert!(lc.utilization() > last);
+		last = lc.utilization();
+	}
+
+	assert_eq!(lc.utilization(), 1.0);
+
+	lc.get(4);
+
+	assert_eq!(lc.utilization(), 1.0);
+}
+
+#[test]
+fn would_evict() {
+	let mut lc = LruCache::with_capacity(3, square);
+
+	lc.get(1);
+	lc.get(2);
+	assert_eq!(lc.would_evict(&3), None);
+
+	lc.get(3);
+	lc.get(1);
+	assert_eq!(lc.would_evict(&1), None);
+
+	let predicted = *lc.would_evict(&4).unwrap();
+	assert_eq!(predicted, 2);
+
+	lc.get(4);
+	assert!(!lc.contains(&predicted));
+	assert_eq!(lc.len(), 3);
+}
@@ -0,0 +1,42 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crate::container::SparseContainer;
+use crate::merge_cache::Merge;
+use crate::tests::*;
+use crate::{FnCache, GenericCache};
+
+#[test]
+fn keep_minimum() {
+	let mut container = Merge::new(HashMap::new(), |a: &u64, b: u64| b.min(*a));
+
+	assert_eq!(container.put(1, 10), &10);
+	assert_eq!(container.put(1, 7), &7);
+	assert_eq!(container.put(1, 9), &7);
+	assert_eq!(container.put(2, 3), &3);
+
+	assert_eq!(container.get(&1), Some(&7));
+	assert_eq!(container.get(&2), Some(&3));
+}
+
+#[test]
+fn accumulate() {
+	let mut container = Merge::new(BTreeMap::new(), |a: &u64, b: u64| a + b);
+
+	container.put(1, 1);
+	container.put(1, 2);
+	container.put(1, 3);
+
+	assert_eq!(container.get(&1), Some(&6));
+}
+
+#[test]
+fn generic_cache() {
+	let mut cache = GenericCache::with_cache(
+		Merge::new(HashMap::new(), |a: &u64, b: u64| b.min(*a)),
+		square,
+	);
+
+	test_square(&mut cache);
+
+	assert_eq!(cache.get(3), &9);
+}
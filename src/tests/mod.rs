@@ -1,12 +1,62 @@
 #![cfg(test)]
+mod acc_cache;
+#[cfg(feature = "async")]
+mod async_cache;
 mod btree_cache;
+mod bucket_cache;
+mod budget_cache;
+mod builder_cache;
+mod checked_cache;
+#[cfg(feature = "compress")]
+mod compressed_cache;
+mod counted_cache;
+mod deadline_cache;
+mod deps_cache;
+mod direct_cache;
+mod dispatch_cache;
+mod frozen_cache;
+mod fuel_cache;
 mod hash_cache;
+#[cfg(feature = "indexmap")]
+mod index_cache;
+mod interning_cache;
+#[cfg(feature = "interval")]
+mod interval_cache;
+mod iter;
+mod lazy_cache;
+mod lfu_cache;
+mod limit_cache;
+mod local_cache;
+#[cfg(feature = "lru")]
+mod lru_adapter;
+mod lru_cache;
+mod merge_cache;
+mod prehashed_cache;
+mod priority_cache;
+#[cfg(feature = "debug_trace")]
+mod provenance_cache;
+#[cfg(feature = "ratelimit")]
+mod ratelimit_cache;
+mod replay_cache;
+#[cfg(feature = "retry")]
+mod retry_cache;
+mod spill_cache;
+#[cfg(feature = "stats")]
+mod stats_cache;
+mod sync_cache;
+mod try_cache;
+mod ttl_cache;
+mod validated_cache;
 mod vec_cache;
+mod weak_cache;
 
 use std::borrow::Borrow;
 use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 use crate::container::{ContainerLen, SparseContainer};
+use crate::ttl_cache::Clock;
 use crate::{FnCache, FnCacheMany, GenericCache};
 
 fn test_get<C, V>(hc: &mut GenericCache<C>, k: C::Input, v: V)
@@ -54,6 +104,7 @@ where
 	test_factor_square(cache, 1)
 }
 
+#[allow(clippy::identity_op)]
 fn test_factor_square<C>(cache: &mut GenericCache<C>, factor: u64)
 where
 	C: SparseContainer<Input = usize, Output = u64> + ContainerLen,
@@ -101,3 +152,24 @@ fn fib(cache: &mut impl FnCache<usize, u64>, x: &usize) -> u64 {
 		_ => *cache.get(x - 1) + *cache.get(x - 2),
 	}
 }
+
+#[derive(Clone)]
+struct ManualClock(Arc<Mutex<Instant>>);
+
+impl ManualClock {
+	fn advance(&self, by: Duration) {
+		*self.0.lock().unwrap() += by;
+	}
+}
+
+impl Default for ManualClock {
+	fn default() -> Self {
+		Self(Arc::new(Mutex::new(Instant::now())))
+	}
+}
+
+impl Clock for ManualClock {
+	fn now(&self) -> Instant {
+		*self.0.lock().unwrap()
+	}
+}
@@ -0,0 +1,67 @@
+use std::cell::Cell;
+use std::hash::{Hash, Hasher};
+
+use crate::prehashed_cache::{HashedKey, PrehashedCache};
+use crate::{FnCache, HashCache};
+
+thread_local! {
+	static HASHES: Cell<usize> = const { Cell::new(0) };
+}
+
+/// A key which counts how many times it has been hashed.
+#[derive(Clone, PartialEq, Eq)]
+struct ExpensiveKey(Vec<u64>);
+
+impl Hash for ExpensiveKey {
+	fn hash<H: Hasher>(&self, state: &mut H) {
+		HASHES.with(|h| h.set(h.get() + 1));
+		self.0.hash(state)
+	}
+}
+
+fn hashes() -> usize {
+	HASHES.with(|h| h.replace(0))
+}
+
+fn keys() -> impl Iterator<Item = ExpensiveKey> {
+	(0..100).map(|i| ExpensiveKey((i..i + 1000).collect()))
+}
+
+#[test]
+fn get() {
+	let mut pc = PrehashedCache::<Vec<u64>, u64>::new(|v| v.iter().sum());
+
+	assert_eq!(pc.get(HashedKey::new(vec![1, 2, 3])), &6);
+	assert_eq!(pc.get(HashedKey::new(vec![3, 2, 1])), &6);
+	assert_eq!(pc.get(HashedKey::new(vec![1, 2, 3])), &6);
+	assert_eq!(pc.len(), 2);
+}
+
+#[test]
+fn hashes_each_key_once() {
+	let mut hc = HashCache::new(|k: &ExpensiveKey| k.0.len());
+
+	hashes();
+
+	for _ in 0..2 {
+		for key in keys() {
+			hc.get(key);
+		}
+	}
+
+	let plain = hashes();
+
+	let mut pc = PrehashedCache::new(|k: &HashedKey<ExpensiveKey>| k.0.len());
+
+	for _ in 0..2 {
+		for key in keys() {
+			pc.get(HashedKey::new(key));
+		}
+	}
+
+	let prehashed = hashes();
+
+	// one hash per get, where a plain map hashes on every lookup
+	assert_eq!(prehashed, 200);
+	assert!(plain > prehashed, "plain {plain}, prehashed {prehashed}");
+}
@@ -0,0 +1,96 @@
+use crate::container::SparseContainer;
+use crate::tests::*;
+use crate::{FnCache, FnCachePeek, PriorityCache};
+
+#[test]
+fn get_fn_ptr() {
+	let mut pc = PriorityCache::with_priority(10, |_, o: &u64| *o, square);
+
+	test_square(&mut pc);
+}
+
+#[test]
+fn keeps_hardest() {
+	// the "difficulty" of each input is the input itself
+	let mut pc = PriorityCache::<usize, u64>::with_priority(3, |i, _| *i as u64, square);
+
+	for i in [5, 1, 9, 3, 7, 2] {
+		pc.get(i);
+	}
+
+	// each new value evicts the easiest one stored before it
+	assert_eq!(pc.len(), 3);
+	assert!(pc.cache().has(&9));
+	assert!(pc.cache().has(&7));
+	assert!(pc.cache().has(&2));
+	assert_eq!(pc.cache().priority(&9), Some(&9));
+
+	pc.get(8);
+
+	assert!(!pc.cache().has(&2));
+	assert!(pc.cache().has(&8));
+}
+
+#[test]
+fn equal_priority_evicts_oldest() {
+	let mut pc = PriorityCache::with_priority(2, |_, _| 0, square);
+
+	pc.get(1);
+	pc.get(2);
+	pc.get(3);
+
+	assert!(!pc.cache().has(&1));
+	assert!(pc.cache().has(&2));
+	assert!(pc.cache().has(&3));
+}
+
+#[test]
+fn remove() {
+	let mut pc = PriorityCache::with_priority(2, |i: &usize, _| *i, square);
+
+	pc.get(1);
+	pc.get(2);
+
+	assert_eq!(pc.remove(&1), Some(1));
+	assert_eq!(pc.len(), 1);
+
+	pc.get(3);
+
+	assert!(pc.cache().has(&2));
+	assert!(pc.cache().has(&3));
+}
+
+#[test]
+fn utilization() {
+	let mut pc = PriorityCache::with_priority(2, |i: &usize, _| *i, square);
+
+	assert_eq!(pc.utilization(), 0.0);
+
+	pc.get(1);
+
+	assert_eq!(pc.utilization(), 0.5);
+
+	pc.get(2);
+	pc.get(3);
+
+	assert_eq!(pc.utilization(), 1.0);
+}
+
+#[test]
+fn would_evict() {
+	let mut pc = PriorityCache::<usize, u64>::with_priority(3, |i, _| *i as u64, square);
+
+	pc.get(5);
+	pc.get(3);
+	assert_eq!(pc.would_evict(&1), None);
+
+	pc.get(7);
+	assert_eq!(pc.would_evict(&5), None);
+
+	let predicted = *pc.would_evict(&9).unwrap();
+	assert_eq!(predicted, 3);
+
+	pc.get(9);
+	assert!(!pc.contains(&predicted));
+	assert_eq!(pc.len(), 3);
+}
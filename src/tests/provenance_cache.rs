@@ -0,0 +1,41 @@
+use crate::tests::*;
+use crate::{FnCache, ProvenanceCache};
+
+fn fib_provenance() -> ProvenanceCache<'static, usize, u64> {
+	ProvenanceCache::recursive(|cache, x| match x {
+		0 | 1 => cache.base(*x as u64),
+		_ => *cache.get(x - 1) + *cache.get(x - 2),
+	})
+}
+
+#[test]
+fn get_closure_recursive() {
+	let mut pc = fib_provenance();
+
+	test_fib(&mut pc);
+}
+
+#[test]
+fn base_cases() {
+	let mut pc = fib_provenance();
+
+	pc.get(10);
+
+	assert_eq!(pc.is_base_case(&0), Some(true));
+	assert_eq!(pc.is_base_case(&1), Some(true));
+
+	for i in 2..=10 {
+		assert_eq!(pc.is_base_case(&i), Some(false));
+	}
+
+	assert_eq!(pc.is_base_case(&11), None);
+}
+
+#[test]
+fn unmarked_values_are_computed() {
+	let mut pc = ProvenanceCache::<usize, u64>::new(square);
+
+	pc.get(3);
+
+	assert_eq!(pc.is_base_case(&3), Some(false));
+}
@@ -0,0 +1,55 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::ratelimit_cache::{RateLimited, RateLimitedCache, TokenBucket};
+use crate::tests::*;
+use crate::FnCache;
+
+#[test]
+fn misses_are_spaced() {
+	let mut rc = RateLimitedCache::<HashMap<usize, u64>>::new(TokenBucket::new(20.0, 1), square);
+
+	let start = Instant::now();
+
+	for i in 0..4 {
+		rc.get(i);
+	}
+
+	// the first miss uses the full bucket, then each waits for a token
+	assert!(start.elapsed() >= Duration::from_millis(140));
+
+	let start = Instant::now();
+
+	for _ in 0..1000 {
+		for i in 0..4 {
+			rc.get(i);
+		}
+	}
+
+	assert!(start.elapsed() < Duration::from_millis(50));
+}
+
+#[test]
+fn recursive_misses_are_limited() {
+	let mut rc =
+		RateLimitedCache::<HashMap<usize, u64>>::recursive(TokenBucket::new(50.0, 2), |c, x| {
+			fib(c, x)
+		});
+
+	let start = Instant::now();
+
+	// 0 through 5 are computed, using the burst of 2 and then waiting for 4 more
+	assert_eq!(rc.get(5), &5);
+	assert!(start.elapsed() >= Duration::from_millis(70));
+}
+
+#[test]
+fn try_get() {
+	let mut rc = RateLimitedCache::<HashMap<usize, u64>>::new(TokenBucket::new(1.0, 2), square);
+
+	assert_eq!(rc.try_get(1), Ok(&1));
+	assert_eq!(rc.try_get(2), Ok(&4));
+	assert_eq!(rc.try_get(3), Err(RateLimited));
+	assert_eq!(rc.try_get(1), Ok(&1));
+	assert_eq!(rc.cache().len(), 2);
+}
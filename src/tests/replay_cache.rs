@@ -0,0 +1,61 @@
+use std::collections::HashSet;
+
+use crate::tests::*;
+use crate::FnCache;
+use crate::ReplayCache;
+
+fn fib_closed_form(n: usize) -> u64 {
+	let sqrt5 = 5f64.sqrt();
+	let phi = (1.0 + sqrt5) / 2.0;
+
+	(phi.powi(n as i32) / sqrt5).round() as u64
+}
+
+#[test]
+fn get_fn_ptr() {
+	let mut rc = ReplayCache::new(square);
+
+	test_square(&mut rc);
+}
+
+#[test]
+fn get_closure_recursive() {
+	let mut rc = ReplayCache::<usize, u64>::recursive(|cache, x| fib(cache, x));
+
+	test_fib(&mut rc);
+}
+
+#[test]
+fn replay_fib() {
+	let mut rc = ReplayCache::<usize, u64>::recursive(|cache, x| fib(cache, x));
+
+	rc.get(40);
+	rc.get(20);
+
+	let mut seen = HashSet::new();
+
+	for (i, o) in rc.replay() {
+		assert_eq!(o, fib_closed_form(i));
+
+		if i >= 2 {
+			assert!(seen.contains(&(i - 1)));
+			assert!(seen.contains(&(i - 2)));
+		}
+
+		assert!(seen.insert(i));
+	}
+
+	assert_eq!(seen.len(), 41);
+}
+
+#[test]
+fn clear() {
+	let mut rc = ReplayCache::<usize, u64>::new(square);
+
+	rc.get(1);
+	rc.get(2);
+	rc.clear();
+	rc.get(3);
+
+	assert_eq!(rc.replay().collect::<Vec<_>>(), [(3, 9)]);
+}
@@ -0,0 +1,52 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::{Duration, Instant};
+
+use crate::container::SparseContainer;
+use crate::retry_cache::{RetryCache, RetryPolicy};
+
+const POLICY: RetryPolicy = RetryPolicy {
+	attempts: 3,
+	backoff: Duration::from_millis(5),
+};
+
+#[test]
+fn fails_twice_then_succeeds() {
+	let calls = AtomicU32::new(0);
+
+	let mut rc = RetryCache::<HashMap<u64, u64>, u32>::retry(
+		|x| match calls.fetch_add(1, Ordering::Relaxed) {
+			n @ (0 | 1) => Err(n),
+			_ => Ok(x * x),
+		},
+		POLICY,
+	);
+
+	let start = Instant::now();
+
+	assert_eq!(rc.try_get(3), Ok(&9));
+	assert_eq!(calls.load(Ordering::Relaxed), 3);
+
+	// waited 5ms, then 10ms
+	assert!(start.elapsed() >= Duration::from_millis(15));
+
+	// the success is cached
+	assert_eq!(rc.try_get(3), Ok(&9));
+	assert_eq!(calls.load(Ordering::Relaxed), 3);
+}
+
+#[test]
+fn gives_up() {
+	let calls = AtomicU32::new(0);
+
+	let mut rc = RetryCache::<HashMap<u64, u64>, u32>::retry(
+		|_| Err(calls.fetch_add(1, Ordering::Relaxed)),
+		POLICY,
+	);
+
+	assert_eq!(rc.try_get(3), Err(2));
+	assert!(!rc.cache().has(&3));
+
+	assert_eq!(rc.try_get(3), Err(5));
+	assert_eq!(calls.load(Ordering::Relaxed), 6);
+}
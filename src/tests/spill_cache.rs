@@ -0,0 +1,90 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::container::{ContainerRemove, SparseContainer};
+use crate::lru_cache::Lru;
+use crate::spill_cache::{Spill, SpillingCache};
+use crate::tests::*;
+use crate::FnCache;
+
+#[test]
+fn get_closure_recursive() {
+	let mut sc = SpillingCache::recursive_with_cache(
+		Spill::new(Lru::with_capacity(2), HashMap::new()),
+		|c, x| fib(c, x),
+	);
+
+	test_fib(&mut sc);
+}
+
+#[test]
+fn evicted_values_spill() {
+	let calls = AtomicUsize::new(0);
+
+	let mut sc =
+		SpillingCache::with_cache(Spill::new(Lru::with_capacity(3), HashMap::new()), |x| {
+			calls.fetch_add(1, Ordering::Relaxed);
+			square(x)
+		});
+
+	for i in 0..6 {
+		sc.get(i);
+	}
+
+	assert_eq!(sc.len(), 6);
+	assert_eq!(sc.cache().l1().len(), 3);
+	assert_eq!(sc.cache().l2().len(), 3);
+
+	for i in 0..3 {
+		assert!(sc.cache().l2().has(&i));
+	}
+
+	// every value is still available without computing it again
+	for i in 0..6 {
+		assert_eq!(sc.get(i), &square(&i));
+	}
+
+	assert_eq!(calls.load(Ordering::Relaxed), 6);
+}
+
+#[test]
+fn promote_on_hit() {
+	let mut sc =
+		SpillingCache::with_cache(Spill::new(Lru::with_capacity(2), HashMap::new()), square);
+
+	sc.get(1);
+	sc.get(2);
+	sc.get(3);
+
+	sc.get(1);
+
+	assert!(sc.cache().l1().contains(&1));
+	assert!(!sc.cache().l2().has(&1));
+	assert!(sc.cache().l2().has(&2));
+
+	assert_eq!(sc.remove(&2), Some(4));
+	assert_eq!(sc.len(), 2);
+}
+
+#[test]
+fn put_spilled() {
+	let mut spill = Spill::new(Lru::with_capacity(2), HashMap::new());
+
+	spill.put(1, 1);
+	spill.put(2, 4);
+	spill.put(3, 9);
+
+	assert!(spill.l2().has(&1));
+
+	spill.put(1, 100);
+
+	assert_eq!(spill.len(), 3);
+	assert!(spill.l1().contains(&1));
+	assert!(!spill.l2().has(&1));
+	assert_eq!(spill.get(&1), Some(&100));
+
+	assert_eq!(spill.remove(&1), Some(100));
+	assert_eq!(spill.remove(&1), None);
+	assert!(!spill.has(&1));
+	assert_eq!(spill.len(), 2);
+}
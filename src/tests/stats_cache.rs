@@ -0,0 +1,143 @@
+use std::collections::HashMap;
+
+use crate::stats_cache::{CacheStats, Stats};
+use crate::tests::*;
+use crate::{FnCache, GenericCache, StatsCache};
+
+#[test]
+fn get_fn_ptr() {
+	let mut sc = StatsCache::new(square);
+
+	test_square(&mut sc);
+}
+
+#[test]
+fn get_closure_recursive() {
+	let mut sc = StatsCache::<usize, u64>::recursive(|c, x| fib(c, x));
+
+	test_fib(&mut sc);
+}
+
+#[test]
+fn hits_and_misses() {
+	let mut sc = StatsCache::<usize, u64>::recursive(|c, x| fib(c, x));
+
+	sc.get(4);
+
+	// 0 through 4 are computed, and 1 and 2 are read again
+	assert_eq!(sc.cache().misses(), 5);
+	assert_eq!(sc.cache().hits(), 2);
+}
+
+#[test]
+fn insert_is_not_a_miss() {
+	let mut sc = StatsCache::<usize, u64>::recursive(|c, x| fib(c, x));
+
+	sc.insert_if_absent(0, 0);
+	sc.insert_if_absent(1, 1);
+
+	assert_eq!(sc.cache().stats(), CacheStats { hits: 0, misses: 0 });
+
+	sc.get(4);
+
+	// only 2 through 4 are computed, reading 0, 1 twice and 2
+	assert_eq!(sc.cache().misses(), 3);
+	assert_eq!(sc.cache().hits(), 4);
+}
+
+#[test]
+fn windowed_miss_rate() {
+	let mut sc = GenericCache::with_cache(
+		Stats::<HashMap<_, _>>::with_window(HashMap::new(), 10),
+		square,
+	);
+
+	sc.get(0);
+
+	for _ in 0..99 {
+		sc.get(0);
+	}
+
+	assert_eq!(sc.windowed_miss_rate(), 0.0);
+
+	for i in 1..=5 {
+		sc.get(i);
+	}
+
+	assert_eq!(sc.windowed_miss_rate(), 0.5);
+	assert!(sc.miss_rate() < 0.1);
+}
+
+#[test]
+fn estimated_speedup() {
+	use std::thread;
+	use std::time::Duration;
+
+	let mut sc = StatsCache::<usize, u64>::timed(|x| {
+		thread::sleep(Duration::from_millis(5));
+		square(x)
+	});
+
+	sc.get(1);
+	sc.get(2);
+
+	assert!(sc.cache().compute_time() >= Duration::from_millis(10));
+	assert!(sc.estimated_speedup() < 1.1);
+
+	for _ in 0..100 {
+		sc.get(1);
+		sc.get(2);
+	}
+
+	// 202 accesses for the cost of 2 computations, less the lookups
+	assert!(sc.estimated_speedup() > 10.0);
+	assert!(sc.estimated_speedup() <= 101.0);
+}
+
+#[test]
+fn recursive_compute_time_counted_once() {
+	use std::thread;
+	use std::time::Duration;
+
+	let mut sc = StatsCache::<usize, u64>::recursive_timed(|c, x| {
+		thread::sleep(Duration::from_millis(5));
+		fib(c, x)
+	});
+
+	sc.get(4);
+
+	// 5 computations, each sleeping once, nested inside each other
+	let time = sc.cache().compute_time();
+	assert!(time >= Duration::from_millis(25));
+	assert!(time < Duration::from_millis(60));
+}
+
+#[test]
+fn untimed_speedup() {
+	let mut sc = StatsCache::new(square);
+
+	sc.get(1);
+	sc.get(1);
+
+	assert_eq!(sc.estimated_speedup(), 1.0);
+}
+
+#[test]
+fn stats_recursive() {
+	let mut sc = StatsCache::<usize, u64>::recursive(|c, x| fib(c, x));
+
+	sc.get(5);
+
+	// 0 through 5 are computed, and 1, 2 and 3 are each read a second time
+	assert_eq!(sc.stats(), CacheStats { hits: 3, misses: 6 });
+
+	sc.get_many([5, 6]);
+
+	assert_eq!(sc.stats(), CacheStats { hits: 6, misses: 7 });
+
+	sc.reset_stats();
+	sc.get(6);
+
+	assert_eq!(sc.stats(), CacheStats { hits: 1, misses: 0 });
+	assert_eq!(sc.windowed_miss_rate(), 0.0);
+}
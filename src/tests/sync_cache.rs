@@ -0,0 +1,150 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Barrier;
+use std::thread;
+use std::time::Duration;
+
+use crate::sync_cache::SyncCache;
+
+#[test]
+fn get() {
+	let sc = SyncCache::<u64, u64>::new(|x| x * x);
+
+	assert!(!sc.contains(&3));
+	assert_eq!(*sc.get(3), 9);
+	assert!(sc.contains(&3));
+	assert_eq!(sc.len(), 1);
+}
+
+#[test]
+fn recursive() {
+	let sc = SyncCache::<u64, u64>::recursive(|c, x| match x {
+		0 | 1 => *x,
+		_ => *c.get(x - 1) + *c.get(x - 2),
+	});
+
+	assert_eq!(*sc.get(50), 12586269025);
+	assert_eq!(sc.len(), 51);
+}
+
+#[test]
+fn racing_misses_compute_once() {
+	let calls = AtomicUsize::new(0);
+	let barrier = Barrier::new(8);
+
+	let sc = SyncCache::<u64, u64>::new(|x| {
+		calls.fetch_add(1, Ordering::Relaxed);
+		thread::sleep(Duration::from_millis(20));
+		x * 2
+	});
+
+	thread::scope(|s| {
+		for _ in 0..8 {
+			s.spawn(|| {
+				barrier.wait();
+				assert_eq!(*sc.get(21), 42);
+			});
+		}
+	});
+
+	assert_eq!(calls.load(Ordering::Relaxed), 1);
+}
+
+#[test]
+fn hits_do_not_block() {
+	let sc = SyncCache::<u64, u64>::new(|x| {
+		if *x == 1 {
+			thread::sleep(Duration::from_millis(500));
+		}
+		*x
+	});
+
+	sc.get(0);
+
+	thread::scope(|s| {
+		// a slow miss on another input does not hold any lock
+		s.spawn(|| sc.get(1));
+
+		thread::sleep(Duration::from_millis(20));
+
+		let start = std::time::Instant::now();
+
+		for _ in 0..1000 {
+			assert_eq!(*sc.get(0), 0);
+		}
+
+		assert!(start.elapsed() < Duration::from_millis(400));
+		assert!(!sc.contains(&1));
+	});
+
+	assert!(sc.contains(&1));
+}
+
+#[test]
+fn get_or_compute_single_winner() {
+	let barrier = Barrier::new(8);
+	let winners = AtomicUsize::new(0);
+
+	let sc = SyncCache::<u64, u64>::new(|x| {
+		thread::sleep(Duration::from_millis(10));
+		x + 1
+	});
+
+	thread::scope(|s| {
+		for _ in 0..8 {
+			s.spawn(|| {
+				barrier.wait();
+
+				for key in 0..4 {
+					let (output, computed) = sc.get_or_compute(key);
+
+					assert_eq!(*output, key + 1);
+
+					if computed {
+						winners.fetch_add(1, Ordering::Relaxed);
+					}
+				}
+			});
+		}
+	});
+
+	// exactly one winner for each key
+	assert_eq!(winners.load(Ordering::Relaxed), 4);
+	assert_eq!(sc.len(), 4);
+}
+
+#[test]
+#[cfg(feature = "parking_lot")]
+fn panic_does_not_poison() {
+	use std::hash::{Hash, Hasher};
+	use std::panic::{self, AssertUnwindSafe};
+
+	static HASHES: AtomicUsize = AtomicUsize::new(0);
+
+	// hashing 13 panics on the second hash, which happens while inserting its slot, with the
+	// write lock held
+	#[derive(Clone, Copy, PartialEq, Eq)]
+	struct Key(u64);
+
+	impl Hash for Key {
+		fn hash<H: Hasher>(&self, state: &mut H) {
+			if self.0 == 13 && HASHES.fetch_add(1, Ordering::Relaxed) == 1 {
+				panic!("hash of 13");
+			}
+
+			self.0.hash(state)
+		}
+	}
+
+	let sc = SyncCache::<Key, u64>::new(|x| match x.0 {
+		7 => panic!("compute of 7"),
+		x => x * x,
+	});
+
+	assert!(panic::catch_unwind(AssertUnwindSafe(|| sc.get(Key(7)))).is_err());
+	assert!(panic::catch_unwind(AssertUnwindSafe(|| sc.get(Key(13)))).is_err());
+
+	assert_eq!(*sc.get(Key(13)), 169);
+	assert_eq!(*sc.get(Key(3)), 9);
+	assert!(!sc.contains(&Key(7)));
+	assert_eq!(sc.len(), 2);
+}
@@ -0,0 +1,124 @@
+use std::collections::HashMap;
+
+use crate::container::SparseContainer;
+use crate::deps_cache::Deps;
+use crate::try_cache::TryCache;
+
+#[test]
+fn success() {
+	let mut tc = TryCache::<HashMap<u64, u64>, ()>::try_recursive(|c, x| match x {
+		0 => Ok(0),
+		1 => Ok(1),
+		_ => Ok(*c.try_get(x - 1)? + *c.try_get(x - 2)?),
+	});
+
+	assert_eq!(tc.try_get(10), Ok(&55));
+	assert_eq!(tc.cache().len(), 11);
+}
+
+#[test]
+fn deep_error_short_circuits() {
+	// a tree where each node depends on its two children, and one deep leaf fails
+	let mut tc = TryCache::<HashMap<u64, u64>, u64>::try_recursive(|c, x| match x {
+		32.. => match x {
+			45 => Err(*x),
+			_ => Ok(1),
+		},
+		_ => Ok(*c.try_get(2 * x)? + *c.try_get(2 * x + 1)?),
+	});
+
+	assert_eq!(tc.try_get(1), Err(45));
+
+	// nothing on the path to the failing leaf is cached
+	for x in [1, 2, 5, 11, 22, 45] {
+		assert!(!tc.cache().has(&x));
+	}
+
+	// siblings computed before the failure remain
+	for x in [4, 8, 9, 10, 44] {
+		assert!(tc.cache().has(&x));
+	}
+
+	assert_eq!(tc.try_get(4), Ok(&8));
+	assert_eq!(tc.try_get(3), Ok(&16));
+	assert_eq!(tc.try_get(5), Err(45));
+}
+
+#[test]
+fn vec_transient_failure() {
+	use std::sync::atomic::{AtomicUsize, Ordering};
+
+	use crate::try_cache::TryVecCache;
+	use crate::TryFnCache;
+
+	let failures = AtomicUsize::new(2);
+
+	let mut tc = TryVecCache::<u64, usize>::new(|x| {
+		if *x == 5 && failures.load(Ordering::Relaxed) > 0 {
+			Err(failures.fetch_sub(1, Ordering::Relaxed))
+		} else {
+			Ok(*x as u64 * *x as u64)
+		}
+	});
+
+	assert_eq!(tc.try_get(3), Ok(&9));
+	assert_eq!(tc.try_get(8), Err(2));
+
+	// everything before the failure is kept, and nothing after it
+	assert_eq!(tc.len(), 5);
+
+	assert_eq!(tc.try_get(4), Ok(&16));
+	assert_eq!(tc.try_get(6), Err(1));
+	assert_eq!(tc.try_get(8), Ok(&64));
+	assert_eq!(tc.len(), 9);
+}
+
+#[test]
+fn try_fn_cache_generic() {
+	use crate::try_cache::TryVecCache;
+	use crate::TryFnCache;
+
+	fn sum_to(cache: &mut impl TryFnCache<u64, u64, ()>, n: u64) -> Result<u64, ()> {
+		(0..=n).map(|i| cache.try_get(i).copied()).sum()
+	}
+
+	let mut tc = TryCache::<HashMap<u64, u64>, ()>::try_recursive(|_, x| Ok(*x));
+	let mut tv = TryVecCache::<u64, (), u64>::new_indexed(|x| Ok(*x));
+
+	assert_eq!(sum_to(&mut tc, 4), Ok(10));
+	assert_eq!(sum_to(&mut tv, 4), Ok(10));
+}
+
+#[test]
+fn records_dependencies() {
+	let mut tc = TryCache::<Deps<HashMap<u64, u64>>, ()>::try_recursive(|c, x| match x {
+		0 => Ok(0),
+		1 => Ok(1),
+		_ => Ok(*c.try_get(x - 1)? + *c.try_get(x - 2)?),
+	});
+
+	tc.try_get(4).unwrap();
+
+	// the value asked for records what it read, the same as those it recursed into
+	assert_eq!(tc.cache().dependencies(&4), Some(&[3, 2][..]));
+	assert_eq!(tc.cache().dependencies(&3), Some(&[2, 1][..]));
+}
+
+#[test]
+fn vec_with_offset() {
+	use crate::try_cache::TryVecCache;
+	use crate::TryFnCache;
+
+	let mut tc = TryVecCache::<u64, String, u64>::with_offset_indexed(100, |x| match x {
+		103 => Err(format!("{x} failed")),
+		_ => Ok(x - 100),
+	});
+
+	assert_eq!(tc.try_get(102), Ok(&2));
+	assert_eq!(tc.try_get(105), Err("103 failed".to_string()));
+
+	assert_eq!(tc.len(), 3);
+	assert_eq!(tc.cache().offset(), 100);
+	assert_eq!(tc.cache().logical_len(), 103);
+	assert!(tc.cache().iter().eq([(100, &0), (101, &1), (102, &2)]));
+}
@@ -0,0 +1,102 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::Duration;
+
+use crate::tests::*;
+use crate::ttl_cache::Ttl;
+use crate::{FnCache, GenericCache, TtlCache};
+
+#[test]
+fn get_fn_ptr() {
+	let mut tc = TtlCache::with_ttl(Duration::from_secs(60), square);
+
+	test_square(&mut tc);
+}
+
+#[test]
+fn get_closure_recursive() {
+	let mut tc =
+		TtlCache::<usize, u64>::recursive_with_ttl(Duration::from_secs(60), |c, x| fib(c, x));
+
+	test_fib(&mut tc);
+}
+
+#[test]
+fn expiry() {
+	let calls = AtomicUsize::new(0);
+	let mut tc = TtlCache::<usize, usize>::with_ttl(Duration::from_millis(20), |x| {
+		calls.fetch_add(1, Ordering::Relaxed);
+		*x
+	});
+
+	tc.get(1);
+	tc.get(1);
+	assert_eq!(calls.load(Ordering::Relaxed), 1);
+
+	thread::sleep(Duration::from_millis(30));
+
+	assert!(!tc.is_live(&1));
+	assert_eq!(tc.len(), 1);
+	assert_eq!(tc.live_len(), 0);
+
+	tc.get(1);
+	assert_eq!(calls.load(Ordering::Relaxed), 2);
+	assert_eq!(tc.len(), 1);
+	assert_eq!(tc.live_len(), 1);
+}
+
+#[test]
+fn dependency_expires_mid_computation() {
+	let calls = [(); 3].map(|_| AtomicUsize::new(0));
+
+	let mut tc = TtlCache::<usize, usize>::recursive_with_ttl(Duration::from_millis(20), |c, x| {
+		calls[*x].fetch_add(1, Ordering::Relaxed);
+
+		match x {
+			0 => 0,
+			1 => *c.get(0) + 1,
+			_ => {
+				c.get(1);
+				// let 0 and 1 expire before they are used again
+				thread::sleep(Duration::from_millis(30));
+				*c.get(1) + 1
+			}
+		}
+	});
+
+	assert_eq!(tc.get(2), &2);
+	assert_eq!(
+		calls.each_ref().map(|c| c.load(Ordering::Relaxed)),
+		[2, 2, 1]
+	);
+}
+
+#[test]
+fn age() {
+	let clock = ManualClock::default();
+	let mut tc = GenericCache::with_cache(
+		Ttl::with_clock(HashMap::new(), Duration::from_secs(10), clock.clone()),
+		square,
+	);
+
+	tc.get(1);
+	assert_eq!(tc.age(&1), Some(Duration::ZERO));
+	assert_eq!(tc.age(&2), None);
+
+	clock.advance(Duration::from_secs(4));
+	tc.get(2);
+
+	assert_eq!(tc.age(&1), Some(Duration::from_secs(4)));
+	assert_eq!(tc.age(&2), Some(Duration::ZERO));
+
+	// expired, but not yet replaced
+	clock.advance(Duration::from_secs(7));
+
+	assert_eq!(tc.age(&1), Some(Duration::from_secs(11)));
+	assert!(!tc.is_live(&1));
+	assert!(tc.is_live(&2));
+
+	tc.get(1);
+	assert_eq!(tc.age(&1), Some(Duration::ZERO));
+}
@@ -0,0 +1,50 @@
+use std::collections::HashMap;
+
+use crate::validated_cache::{InvalidOutput, ValidatedCache};
+use crate::{FnCache, FnCachePeek, TryFnCache};
+
+fn non_negative<'f>() -> ValidatedCache<'f, HashMap<i64, i64>> {
+	ValidatedCache::with_validator(|x| x - 10, |_, y| *y >= 0)
+}
+
+#[test]
+fn valid() {
+	let mut vc = non_negative();
+
+	assert_eq!(vc.try_get(15), Ok(&5));
+	assert_eq!(vc.get(10), &0);
+	assert_eq!(vc.cache().len(), 2);
+}
+
+#[test]
+fn invalid_not_cached() {
+	let mut vc = non_negative();
+
+	assert_eq!(vc.try_get(3), Err(InvalidOutput));
+	assert!(!vc.contains(&3));
+	assert!(vc.cache().is_empty());
+}
+
+#[test]
+#[should_panic(expected = "failed validation")]
+fn invalid_get_panics() {
+	non_negative().get(3);
+}
+
+#[test]
+fn try_fn_cache() {
+	fn get_all(
+		cache: &mut impl TryFnCache<i64, i64, InvalidOutput>,
+		inputs: &[i64],
+	) -> Result<i64, InvalidOutput> {
+		inputs.iter().map(|x| cache.try_get(*x).copied()).sum()
+	}
+
+	let mut vc = non_negative();
+
+	assert_eq!(get_all(&mut vc, &[10, 12, 15]), Ok(7));
+	assert_eq!(get_all(&mut vc, &[12, 5, 20]), Err(InvalidOutput));
+	assert!(vc.contains(&12));
+	assert!(!vc.contains(&5));
+	assert!(!vc.contains(&20));
+}
@@ -4,7 +4,7 @@ use std::rc::Rc;
 
 use crate::tests::*;
 use crate::VecCache;
-use crate::{FnCache, FnCacheMany};
+use crate::{FnCache, FnCacheMany, FnCachePeek};
 
 fn test_get<T, V>(vc: &mut VecCache<T>, n: usize, v: V)
 where
@@ -198,3 +198,323 @@ fn static_context() {
 	test_get_many(&mut *vc, [8, 0, 5, 3], [8, 0, 5, 3]);
 	test_get_many(&mut *vc, [0, 5, 3, 12], [0, 5, 3, 12]);
 }
+
+#[test]
+fn get_two() {
+	let mut vc = VecCache::<u64>::recursive(fib);
+
+	assert_eq!(vc.get_two(5, 6), (&5, &8));
+	assert_eq!(vc.get_two(7, 7), (&13, &13));
+
+	let (a, b) = vc.get_two(4, 4);
+	assert!(std::ptr::eq(a, b));
+	assert_eq!(vc.len(), 8);
+}
+
+#[test]
+fn u32_input() {
+	let mut vc = VecCache::<u64, u32>::recursive_indexed(|cache, x| match x {
+		0 => 0,
+		1 => 1,
+		_ => *cache.get(x - 1) + *cache.get(x - 2),
+	});
+
+	assert_eq!(vc.get(10u32), &55);
+	assert_eq!(vc.get_many([3u32, 12]), [&2, &144]);
+	assert_eq!(vc.len(), 13);
+}
+
+#[test]
+#[should_panic]
+fn negative_input() {
+	let mut vc = VecCache::<i8, i8>::new_indexed(|x| *x);
+
+	vc.get(-1);
+}
+
+#[test]
+fn get_entry() {
+	let mut vc = VecCache::new(square);
+
+	assert_eq!(vc.get_entry(3), (true, &9));
+	assert_eq!(vc.get_entry(3), (false, &9));
+	assert_eq!(vc.get_entry(1), (false, &1));
+	assert_eq!(vc.get_entry(4), (true, &16));
+}
+
+#[test]
+fn stored_and_logical_len() {
+	let mut vc = VecCache::new(square);
+
+	assert_eq!(vc.stored_len(), 0);
+	assert_eq!(vc.logical_len(), 0);
+
+	vc.get(4);
+
+	assert_eq!(vc.stored_len(), 5);
+	assert_eq!(vc.logical_len(), 5);
+	assert_eq!(vc.len(), vc.stored_len());
+}
+
+#[test]
+fn get_many_empty() {
+	let mut vc = VecCache::new(square);
+
+	assert_eq!(vc.get_many([]), [] as [&u64; 0]);
+	assert_eq!(vc.len(), 0);
+	assert_eq!(vc.cache.capacity(), 0);
+
+	vc.get(3);
+
+	assert_eq!(vc.get_many([]), [] as [&u64; 0]);
+	assert_eq!(vc.len(), 4);
+}
+
+#[test]
+fn into_fn() {
+	fn apply_all(mut f: impl FnMut(usize) -> u64 + 'static, inputs: &[usize]) -> Vec<u64> {
+		inputs.iter().map(|&i| f(i)).collect()
+	}
+
+	let vc = VecCache::<u64>::recursive(fib);
+
+	assert_eq!(apply_all(vc.into_fn(), &[5, 10, 5]), [5, 55, 5]);
+}
+
+#[test]
+fn reserve_to() {
+	let mut vc = VecCache::<[u64; 4]>::new(|x| [*x as u64; 4]);
+
+	vc.get(2);
+
+	for len in [10, 40, 20, 100] {
+		let old = vc.capacity();
+		let bytes = vc.reserve_to(len);
+
+		assert!(vc.capacity() >= len);
+		assert_eq!(bytes, (vc.capacity() - old) * 32);
+	}
+
+	assert_eq!(vc.reserve_to(50), 0);
+}
+
+#[test]
+fn iter() {
+	let mut vc = VecCache::<u64, u8>::new_indexed(|x| *x as u64 * 3);
+
+	vc.get(5);
+
+	let entries: Vec<_> = vc.iter().collect();
+
+	assert_eq!(entries.len(), 6);
+	assert!(entries
+		.iter()
+		.enumerate()
+		.all(|(i, &(x, o))| x as usize == i && *o == x as u64 * 3));
+}
+
+#[test]
+fn peek() {
+	use crate::FnCachePeek;
+
+	let mut vc = VecCache::<u64, i32>::new_indexed(|x| *x as u64 * 2);
+
+	assert_eq!(vc.peek(&0), None);
+
+	vc.get(3);
+
+	assert_eq!(vc.peek(&2), Some(&4));
+	assert_eq!(vc.peek(&3), Some(&6));
+	assert_eq!(vc.peek(&4), None);
+	assert_eq!(vc.peek(&-1), None);
+	assert_eq!(vc.len(), 4);
+}
+
+#[test]
+fn insert() {
+	use crate::vec_cache::InsertGap;
+
+	let mut vc = VecCache::<u64>::new(|x| *x as u64 * 10);
+
+	assert_eq!(vc.insert_if_absent(1, 7), Err(InsertGap));
+	assert!(vc.is_empty());
+
+	assert_eq!(vc.insert_if_absent(0, 5), Ok(&5));
+	assert_eq!(vc.insert_if_absent(0, 6), Ok(&5));
+	assert_eq!(vc.get(2), &20);
+	assert_eq!(vc.insert_if_absent(3, 9), Ok(&9));
+
+	assert_eq!(vc.get_many([0, 1, 3]), [&5, &10, &9]);
+	assert_eq!(vc.len(), 4);
+}
+
+#[test]
+fn is_dense() {
+	use crate::HashCache;
+
+	assert!(VecCache::new(square).is_dense());
+	assert!(!HashCache::new(square).is_dense());
+}
+
+#[test]
+fn get_many_vec() {
+	let mut vc = VecCache::<u64>::recursive(fib);
+
+	assert_eq!(vc.get_many_vec(&[10, 3, 12]), [&55, &2, &144]);
+	assert_eq!(vc.len(), 13);
+	assert!(vc.get_many_vec(&[]).is_empty());
+}
+
+#[test]
+fn contains() {
+	let mut vc = VecCache::<u64, i8>::new_indexed(|x| *x as u64);
+
+	assert!(!vc.contains(&0));
+
+	vc.get(3);
+
+	assert!(vc.contains(&0));
+	assert!(vc.contains(&3));
+	assert!(!vc.contains(&4));
+	assert!(!vc.contains(&-1));
+}
+
+#[test]
+fn drain() {
+	let mut vc = VecCache::new(square);
+
+	vc.get(9);
+
+	let capacity = vc.capacity();
+
+	assert!(vc.drain().eq((0..10).map(|i| (i, square(&i)))));
+	assert_eq!(vc.len(), 0);
+	assert_eq!(vc.capacity(), capacity);
+
+	assert_eq!(vc.get(3), &9);
+}
+
+#[test]
+fn heap_bytes() {
+	let mut vc = VecCache::new(square);
+
+	assert_eq!(vc.heap_bytes(), 0);
+
+	vc.reserve(100);
+
+	assert!(vc.capacity() >= 100);
+	assert_eq!(vc.heap_bytes(), vc.capacity() * core::mem::size_of::<u64>());
+
+	let mut pairs = VecCache::new(|x: &usize| (*x as u8, *x as u64));
+
+	pairs.get(20);
+
+	assert_eq!(pairs.heap_bytes(), pairs.capacity() * 16);
+}
+
+#[test]
+fn with_offset() {
+	use crate::FnCachePeek;
+
+	let mut vc = VecCache::<u64>::with_offset(1000, |x| square(x) - 1_000_000);
+
+	assert_eq!(vc.get(1000), &0);
+	assert_eq!(vc.get_many([1003, 1001]), [&6009, &2001]);
+
+	assert_eq!(vc.len(), 4);
+	assert_eq!(vc.logical_len(), 1004);
+	assert_eq!(vc.offset(), 1000);
+
+	assert!(vc.contains(&1002));
+	assert!(!vc.contains(&999));
+	assert!(!vc.contains(&1004));
+	assert_eq!(vc.peek(&5), None);
+
+	assert_eq!(vc.insert_if_absent(1004, 1), Ok(&1));
+	assert!(vc.iter().map(|(i, _)| i).eq(1000..1005));
+}
+
+#[test]
+fn recursive_with_offset() {
+	let mut vc = VecCache::<u64>::recursive_with_offset(10, |c, x| match x {
+		10 | 11 => 1,
+		_ => *c.get(x - 1) + *c.get(x - 2),
+	});
+
+	assert_eq!(vc.get(20), &89);
+	assert_eq!(vc.len(), 11);
+}
+
+#[test]
+#[should_panic(expected = "below the offset")]
+fn below_offset() {
+	let mut vc = VecCache::<u64>::with_offset(10, square);
+
+	vc.get(9);
+}
+
+#[test]
+fn shrink_to_fit() {
+	let mut vc = VecCache::new(square);
+
+	vc.get(100);
+	vc.clear();
+	vc.shrink_to_fit();
+
+	assert_eq!(vc.capacity(), 0);
+}
+
+#[test]
+fn get_many_single_fill() {
+	use std::sync::atomic::{AtomicUsize, Ordering};
+
+	let calls = AtomicUsize::new(0);
+
+	let mut vc = VecCache::new(|x: &usize| {
+		calls.fetch_add(1, Ordering::Relaxed);
+		square(x)
+	});
+
+	assert_eq!(vc.get_many([50, 100, 3]), [&2500, &10000, &9]);
+	assert_eq!(calls.load(Ordering::Relaxed), 101);
+	assert!(vc.capacity() >= 101);
+
+	assert_eq!(vc.get_many_vec(&[120, 110]), [&14400, &12100]);
+	assert_eq!(calls.load(Ordering::Relaxed), 121);
+}
+
+#[test]
+fn untyped_closure_infers_usize() {
+	// the input would fall back to i32 if the constructor were generic over it
+	let mut vc = VecCache::new(|x| x * 2);
+
+	let x: &usize = vc.get(3);
+	assert_eq!(x, &6);
+
+	let mut vc = VecCache::recursive(|cache, x| match x {
+		0 => 0,
+		_ => cache.get(x - 1) + 1,
+	});
+
+	let input = 4;
+	assert_eq!(vc.get(input), &4);
+	assert_eq!(std::mem::size_of_val(&input), std::mem::size_of::<usize>());
+}
+
+#[test]
+fn try_get_out_of_range() {
+	use crate::vec_cache::OutOfRange;
+	use crate::TryFnCache;
+
+	let mut vc = VecCache::<u64, i8>::new_indexed(|x| *x as u64);
+
+	assert_eq!(vc.try_get(-1), Err(OutOfRange));
+	assert_eq!(vc.try_get(3), Ok(&3));
+	assert_eq!(vc.len(), 4);
+
+	let mut vc = VecCache::<u64, u64>::with_offset_indexed(10, |x| *x);
+
+	assert_eq!(vc.try_get(9), Err(OutOfRange));
+	assert!(vc.is_empty());
+	assert_eq!(vc.try_get(11), Ok(&11));
+}
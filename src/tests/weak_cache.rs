@@ -0,0 +1,87 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use crate::weak_cache::WeakCache;
+
+#[test]
+fn alive_values_are_reused() {
+	let calls = AtomicUsize::new(0);
+	let mut wc = WeakCache::new(|x: &usize| {
+		calls.fetch_add(1, Ordering::Relaxed);
+		*x
+	});
+
+	let one = wc.get(1);
+
+	assert!(Arc::ptr_eq(&one, &wc.get(1)));
+	assert_eq!(calls.load(Ordering::Relaxed), 1);
+
+	drop(one);
+
+	assert_eq!(*wc.get(1), 1);
+	assert_eq!(calls.load(Ordering::Relaxed), 2);
+	assert_eq!(wc.len(), 1);
+}
+
+#[test]
+fn gc() {
+	let mut wc = WeakCache::new(|x: &usize| *x);
+
+	let kept: Vec<_> = (0..10).map(|i| wc.get(i)).collect();
+
+	for i in 10..20 {
+		wc.get(i);
+	}
+
+	// some dead entries may already have been purged lazily
+	let len = wc.len();
+
+	assert_eq!(wc.gc(), len - 10);
+	assert_eq!(wc.len(), 10);
+	assert_eq!(wc.gc(), 0);
+
+	drop(kept);
+}
+
+#[test]
+fn dead_entries_purged_lazily() {
+	let mut wc = WeakCache::new(|x: &usize| *x);
+
+	for i in 0..1000 {
+		wc.get(i);
+	}
+
+	// every value was dropped straight away, so the cache never holds many more than are alive
+	assert!(wc.len() <= 2);
+}
+
+#[test]
+fn recursive_restores_collected_entries() {
+	let calls = AtomicUsize::new(0);
+
+	let mut wc = WeakCache::<usize, u64>::recursive(|c, x| {
+		calls.fetch_add(1, Ordering::Relaxed);
+
+		Arc::new(match x {
+			0 => 0,
+			1 => 1,
+			_ => {
+				// dependencies are dropped as soon as they are used
+				let a = *c.get(x - 1);
+				a + *c.get(x - 2)
+			}
+		})
+	});
+
+	let top = wc.get(10);
+	assert_eq!(*top, 55);
+
+	let two = wc.get(2);
+	assert_eq!(*two, 1);
+
+	// 2 was collected, so it was stored again, and is now reused
+	let before = calls.load(Ordering::Relaxed);
+	assert!(Arc::ptr_eq(&two, &wc.get(2)));
+	assert_eq!(calls.load(Ordering::Relaxed), before);
+	assert!(Arc::ptr_eq(&top, &wc.get(10)));
+}
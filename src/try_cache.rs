@@ -0,0 +1,371 @@
+use std::sync::Arc;
+
+use crate::container::SparseContainer;
+use crate::vec_cache::from_index;
+use crate::{TryFnCache, VecCache};
+
+/// How much computing a single call to [`TryCache::try_get`] may do, checked before each value
+/// that is not yet cached is computed.
+///
+/// Each call to [`TryCache::try_get`] starts a fresh [`Self::State`], which is shared by every
+/// recursive call made through the [`TryRefCache`]. Once [`Self::check`] returns an error, the
+/// function propagates it using `?`, aborting the whole computation.
+pub trait Allowance<C, E> {
+	/// What is left of the allowance during a single call to [`TryCache::try_get`].
+	type State;
+
+	/// Start the allowance for a call to [`TryCache::try_get`].
+	fn start(&self) -> Self::State;
+
+	/// Called before computing a value which is not in `cache`, returning an error if there is no
+	/// allowance left to compute it.
+	fn check(state: &mut Self::State, cache: &C) -> Result<(), E>;
+
+	/// Called once computing a value which passed [`Self::check`] has finished, whether it
+	/// succeeded or not.
+	fn finish(state: &mut Self::State) {
+		let _ = state;
+	}
+}
+
+/// The default [`Allowance`] of a [`TryCache`], which never stops a computation.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Unlimited;
+
+impl<C, E> Allowance<C, E> for Unlimited {
+	type State = ();
+
+	fn start(&self) {}
+
+	fn check(_: &mut (), _: &C) -> Result<(), E> {
+		Ok(())
+	}
+}
+
+/// A cache for a fallible recursive function, where an error at any depth aborts the whole
+/// computation. This is the fallible counterpart of [`GenericCache`](crate::GenericCache), and
+/// holds any of the same [`SparseContainer`]s, but takes a function returning a [`Result`].
+///
+/// The function gets values through the [`TryRefCache`], which returns any error from computing a
+/// value so the function can propagate it using `?`. Only successful results are cached, so a
+/// failed input is computed again the next time it is requested. Values which were successfully
+/// computed before the error, such as siblings of the failing input, remain in the cache.
+///
+/// An [`Allowance`] can also stop the computation early, returning its own error through the
+/// same path, as is done by [`DeadlineCache`](crate::deadline_cache::DeadlineCache).
+///
+/// ```
+/// # use fn_cache::try_cache::TryCache;
+/// # use std::collections::HashMap;
+/// let mut cache = TryCache::<HashMap<u64, u64>, String>::try_recursive(|cache, x| match x {
+///     0 => Ok(0),
+///     1 => Ok(1),
+///     13 => Err(format!("{x} is unlucky")),
+///     _ => Ok(*cache.try_get(x - 1)? + *cache.try_get(x - 2)?),
+/// });
+///
+/// assert_eq!(cache.try_get(10), Ok(&55));
+/// assert_eq!(cache.try_get(15), Err("13 is unlucky".to_string()));
+/// ```
+pub struct TryCache<'f, C: SparseContainer, E, A: Allowance<C, E> = Unlimited> {
+	cache: C,
+	allowance: A,
+	#[allow(clippy::type_complexity)]
+	f: Box<dyn Fn(&mut TryRefCache<C, E, A>, &C::Input) -> Result<C::Output, E> + Send + 'f>,
+}
+
+impl<'f, C: SparseContainer, E, A: Allowance<C, E>> TryCache<'f, C, E, A> {
+	/// Create a `TryCache` out of a cache, the allowance for each call to [`Self::try_get`], and
+	/// a fallible recursive function.
+	pub fn try_recursive_with_allowance(
+		cache: C,
+		allowance: A,
+		f: impl Fn(&mut TryRefCache<C, E, A>, &C::Input) -> Result<C::Output, E> + Send + 'f,
+	) -> Self {
+		Self {
+			cache,
+			allowance,
+			f: Box::new(f),
+		}
+	}
+
+	/// Get a reference to the underlying cache object.
+	pub fn cache(&self) -> &C {
+		&self.cache
+	}
+
+	/// Get a reference to the allowance for each call to [`Self::try_get`].
+	pub fn allowance(&self) -> &A {
+		&self.allowance
+	}
+
+	/// Get a mutable reference to the allowance for each call to [`Self::try_get`].
+	pub fn allowance_mut(&mut self) -> &mut A {
+		&mut self.allowance
+	}
+
+	/// Retrieve a value stored in the cache, computing it if it does not yet exist.
+	///
+	/// Returns the first error from the function, including any from recursive calls, or from the
+	/// allowance, in which case nothing is cached for `input`.
+	pub fn try_get(&mut self, input: C::Input) -> Result<&C::Output, E> {
+		let mut ref_cache = TryRefCache {
+			cache: &mut self.cache,
+			f: self.f.as_ref(),
+			state: self.allowance.start(),
+		};
+
+		if ref_cache.try_get_inner(&input)? {
+			Ok(self.cache.get(&input).unwrap())
+		} else {
+			let output = ref_cache.compute(&input)?;
+			Ok(self.cache.put(input, output))
+		}
+	}
+}
+
+impl<'f, C: SparseContainer, E> TryCache<'f, C, E> {
+	/// Create a `TryCache` out of a cache and a fallible recursive function.
+	pub fn try_recursive_with_cache(
+		cache: C,
+		f: impl Fn(&mut TryRefCache<C, E>, &C::Input) -> Result<C::Output, E> + Send + 'f,
+	) -> Self {
+		Self::try_recursive_with_allowance(cache, Unlimited, f)
+	}
+}
+
+impl<'f, C, E> TryCache<'f, C, E>
+where
+	C: SparseContainer + Default,
+{
+	/// Create a `TryCache` using the `Default` implementation of the container, and a fallible
+	/// recursive function.
+	pub fn try_recursive(
+		f: impl Fn(&mut TryRefCache<C, E>, &C::Input) -> Result<C::Output, E> + Send + 'f,
+	) -> Self {
+		Self::try_recursive_with_cache(Default::default(), f)
+	}
+}
+
+impl<'f, C, E, A> TryFnCache<C::Input, C::Output, E> for TryCache<'f, C, E, A>
+where
+	C: SparseContainer,
+	A: Allowance<C, E>,
+{
+	fn try_get(&mut self, input: C::Input) -> Result<&C::Output, E> {
+		TryCache::try_get(self, input)
+	}
+}
+
+/// The cache passed to the function of a [`TryCache`], sharing the allowance of the top-level
+/// call.
+pub struct TryRefCache<'c, C: SparseContainer, E, A: Allowance<C, E> = Unlimited> {
+	cache: &'c mut C,
+	#[allow(clippy::type_complexity)]
+	f: &'c (dyn Fn(&mut Self, &C::Input) -> Result<C::Output, E> + Send),
+	pub(crate) state: A::State,
+}
+
+impl<'c, C: SparseContainer, E, A: Allowance<C, E>> TryRefCache<'c, C, E, A> {
+	/// Returns whether `input` is already cached, or the error from the allowance if there is not
+	/// enough left to compute it.
+	fn try_get_inner(&mut self, input: &C::Input) -> Result<bool, E> {
+		if self.cache.has(input) {
+			self.cache.touch(input);
+			Ok(true)
+		} else {
+			A::check(&mut self.state, self.cache)?;
+			Ok(false)
+		}
+	}
+
+	/// Compute the value for `input`, which has passed [`Allowance::check`].
+	fn compute(&mut self, input: &C::Input) -> Result<C::Output, E> {
+		self.cache.begin(input);
+		let output = (self.f)(self, input);
+		A::finish(&mut self.state);
+		output
+	}
+
+	/// Retrieve a value stored in the cache, computing it if it does not yet exist.
+	///
+	/// Returns the error from the function or the allowance if computing the value fails, in
+	/// which case nothing is cached for `input`.
+	pub fn try_get(&mut self, input: C::Input) -> Result<&C::Output, E> {
+		if self.try_get_inner(&input)? {
+			Ok(self.cache.get(&input).unwrap())
+		} else {
+			let output = self.compute(&input)?;
+			Ok(self.cache.put(input, output))
+		}
+	}
+}
+
+impl<'c, C, E, A> TryFnCache<C::Input, C::Output, E> for TryRefCache<'c, C, E, A>
+where
+	C: SparseContainer,
+	A: Allowance<C, E>,
+{
+	fn try_get(&mut self, input: C::Input) -> Result<&C::Output, E> {
+		TryRefCache::try_get(self, input)
+	}
+}
+
+/// A cache for a fallible function which uses a [`Vec`], the fallible counterpart of
+/// [`VecCache`].
+///
+/// The function of a [`VecCache`] returns its output directly, so a fallible function needs its
+/// own cache, with a function returning a [`Result`]. The values are stored in a [`VecCache`],
+/// which is available through [`Self::cache`], so everything other than computing values works
+/// the same, including starting from an offset.
+///
+/// Values are computed in order, starting from the offset. If computing a value fails, every
+/// value before it stays in the cache, and nothing after it is computed, so the cache never has a
+/// gap. The failed input is computed again the next time it, or anything after it, is requested.
+///
+/// ```
+/// # use fn_cache::try_cache::TryVecCache;
+/// # use fn_cache::TryFnCache;
+/// let mut cache = TryVecCache::<u64, String>::recursive(|cache, x| match x {
+///     0 | 1 => Ok(*x as u64),
+///     13 => Err(format!("{x} is unlucky")),
+///     _ => Ok(*cache.try_get(x - 1)? + *cache.try_get(x - 2)?),
+/// });
+///
+/// assert_eq!(cache.try_get(10), Ok(&55));
+/// assert_eq!(cache.try_get(15), Err("13 is unlucky".to_string()));
+/// assert_eq!(cache.len(), 13);
+/// ```
+pub struct TryVecCache<'f, O, E, I = usize> {
+	cache: VecCache<'f, O, I>,
+	#[allow(clippy::type_complexity)]
+	f: Arc<dyn Fn(&mut Self, &I) -> Result<O, E> + 'f + Send + Sync>,
+}
+
+impl<'f, O, E> TryVecCache<'f, O, E> {
+	/// Create a cache for the provided fallible function. If the function stores references,
+	/// the cache can only live as long as those references.
+	///
+	/// As with [`VecCache::new`], the input is always `usize`. For other input types, see
+	/// [`Self::new_indexed`].
+	pub fn new<F>(f: F) -> Self
+	where
+		F: Fn(&usize) -> Result<O, E> + 'f + Send + Sync,
+	{
+		Self::new_indexed(f)
+	}
+
+	/// Create a cache for the provided fallible recursive function. If the function stores
+	/// references, the cache can only live as long as those references.
+	pub fn recursive<F>(f: F) -> Self
+	where
+		F: Fn(&mut Self, &usize) -> Result<O, E> + 'f + Send + Sync,
+	{
+		Self::recursive_indexed(f)
+	}
+
+	/// Create a cache for the provided fallible function, starting from the input `base` instead
+	/// of zero. See [`VecCache::with_offset`].
+	pub fn with_offset<F>(base: usize, f: F) -> Self
+	where
+		F: Fn(&usize) -> Result<O, E> + 'f + Send + Sync,
+	{
+		Self::with_offset_indexed(base, f)
+	}
+
+	/// Create a cache for the provided fallible recursive function, starting from the input
+	/// `base` instead of zero. See [`VecCache::with_offset`].
+	pub fn recursive_with_offset<F>(base: usize, f: F) -> Self
+	where
+		F: Fn(&mut Self, &usize) -> Result<O, E> + 'f + Send + Sync,
+	{
+		Self::recursive_with_offset_indexed(base, f)
+	}
+}
+
+impl<'f, O, E, I> TryVecCache<'f, O, E, I>
+where
+	I: Copy + TryInto<usize> + TryFrom<usize>,
+{
+	/// Create a cache for the provided fallible function, taking inputs of the integer type `I`.
+	/// See [`VecCache::new_indexed`].
+	pub fn new_indexed<F>(f: F) -> Self
+	where
+		F: Fn(&I) -> Result<O, E> + 'f + Send + Sync,
+	{
+		Self::recursive_indexed(move |_, x| f(x))
+	}
+
+	/// Create a cache for the provided fallible recursive function, taking inputs of the integer
+	/// type `I`.
+	pub fn recursive_indexed<F>(f: F) -> Self
+	where
+		F: Fn(&mut Self, &I) -> Result<O, E> + 'f + Send + Sync,
+	{
+		Self::recursive_with_offset_indexed(0, f)
+	}
+
+	/// Create a cache for the provided fallible function, taking inputs of the integer type `I`,
+	/// and starting from the input `base` instead of zero.
+	pub fn with_offset_indexed<F>(base: usize, f: F) -> Self
+	where
+		F: Fn(&I) -> Result<O, E> + 'f + Send + Sync,
+	{
+		Self::recursive_with_offset_indexed(base, move |_, x| f(x))
+	}
+
+	/// Create a cache for the provided fallible recursive function, taking inputs of the integer
+	/// type `I`, and starting from the input `base` instead of zero.
+	pub fn recursive_with_offset_indexed<F>(base: usize, f: F) -> Self
+	where
+		F: Fn(&mut Self, &I) -> Result<O, E> + 'f + Send + Sync,
+	{
+		Self {
+			cache: VecCache::with_offset_indexed(base, |_| {
+				unreachable!("the values of a TryVecCache are only computed by its own function")
+			}),
+			f: Arc::new(f),
+		}
+	}
+}
+
+impl<'f, O, E, I> TryVecCache<'f, O, E, I> {
+	/// Get a reference to the [`VecCache`] holding the values.
+	pub fn cache(&self) -> &VecCache<'f, O, I> {
+		&self.cache
+	}
+
+	/// Returns the number of elements in the cache.
+	pub fn len(&self) -> usize {
+		self.cache.len()
+	}
+
+	/// Returns true if the cache holds no elements.
+	pub fn is_empty(&self) -> bool {
+		self.cache.is_empty()
+	}
+}
+
+impl<'f, O, E, I> TryFnCache<I, O, E> for TryVecCache<'f, O, E, I>
+where
+	I: Copy + TryInto<usize> + TryFrom<usize>,
+{
+	/// Every missing value up to `input` is computed in order, reserving space for all of them at
+	/// once, until one fails.
+	fn try_get(&mut self, input: I) -> Result<&O, E> {
+		let index = self.cache.index_of(input);
+		let len = self.cache.len();
+
+		if len <= index {
+			self.cache.reserve(index - len + 1);
+		}
+
+		while self.cache.len() <= index {
+			let next = from_index(self.cache.logical_len());
+			let next_val = (self.f.clone())(self, &next)?;
+			self.cache.cache.push(next_val);
+		}
+
+		Ok(&self.cache.cache[index])
+	}
+}
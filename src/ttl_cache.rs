@@ -0,0 +1,226 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::container::{
+	ContainerClear, ContainerIter, ContainerLen, ContainerRemove, SparseContainer,
+};
+use crate::generic_cache::{GenericCache, RefCache};
+
+/// A cache where each value expires a fixed time after it was computed.
+///
+/// An expired value is treated as absent, so it is computed again the next time it is requested.
+/// This includes values requested by a recursive function, so a dependency which expires while
+/// computing another value is recomputed rather than reused.
+///
+/// ```
+/// # use fn_cache::{FnCache, TtlCache};
+/// # use std::time::Duration;
+/// let mut cache = TtlCache::<usize, usize>::with_ttl(Duration::from_secs(60), |x| *x);
+///
+/// assert_eq!(cache.get(1), &1);
+/// assert!(cache.is_live(&1));
+/// ```
+///
+/// Other containers can be used by using a `GenericCache<Ttl<C>>` directly, as long as they
+/// store the time each output was computed alongside it.
+pub type TtlCache<'f, I, O> = GenericCache<'f, Ttl<HashMap<I, (Instant, O)>>>;
+
+/// A source of the current time, used to timestamp values in a [`Ttl`] container.
+///
+/// This allows a manual clock to be used in tests, instead of waiting for values to expire.
+pub trait Clock {
+	/// Returns the current time.
+	fn now(&self) -> Instant;
+}
+
+/// A [`Clock`] which uses [`Instant::now`].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+	fn now(&self) -> Instant {
+		Instant::now()
+	}
+}
+
+/// A [`SparseContainer`] which stores the time each output was computed alongside it, and
+/// treats outputs older than its time to live as absent.
+///
+/// Only [`SparseContainer::has`] checks whether a value has expired. A value which expires after
+/// it was found can still be returned by [`SparseContainer::get`], so that a value is never lost
+/// between checking for it and returning it.
+pub struct Ttl<C, K = SystemClock> {
+	inner: C,
+	ttl: Duration,
+	clock: K,
+}
+
+impl<C> Ttl<C> {
+	/// Wrap a container, where each output expires `ttl` after it was computed.
+	pub fn new(inner: C, ttl: Duration) -> Self {
+		Self::with_clock(inner, ttl, SystemClock)
+	}
+}
+
+impl<C, K: Clock> Ttl<C, K> {
+	/// Wrap a container, where each output expires `ttl` after it was computed, according to
+	/// `clock`.
+	pub fn with_clock(inner: C, ttl: Duration, clock: K) -> Self {
+		Self { inner, ttl, clock }
+	}
+
+	/// Get a reference to the wrapped container.
+	pub fn inner(&self) -> &C {
+		&self.inner
+	}
+
+	/// Returns how long each output lives after it was computed.
+	pub fn ttl(&self) -> Duration {
+		self.ttl
+	}
+
+	/// Returns how long ago `inserted` was, according to the clock.
+	fn elapsed(&self, inserted: Instant) -> Duration {
+		self.clock.now().saturating_duration_since(inserted)
+	}
+
+	fn is_fresh(&self, inserted: Instant) -> bool {
+		self.elapsed(inserted) <= self.ttl
+	}
+}
+
+impl<C, K, O> SparseContainer for Ttl<C, K>
+where
+	K: Clock,
+	C: ContainerRemove<Output = (Instant, O)>,
+{
+	type Input = C::Input;
+	type Output = O;
+
+	fn has(&self, input: &Self::Input) -> bool {
+		matches!(self.inner.get(input), Some((inserted, _)) if self.is_fresh(*inserted))
+	}
+
+	fn get(&self, input: &Self::Input) -> Option<&Self::Output> {
+		self.inner.get(input).map(|(_, o)| o)
+	}
+
+	fn put(&mut self, input: Self::Input, output: Self::Output) -> &Self::Output {
+		// replace any expired value, rather than keeping it
+		self.inner.remove(&input);
+		&self.inner.put(input, (self.clock.now(), output)).1
+	}
+
+	fn touch(&mut self, input: &Self::Input) {
+		self.inner.touch(input)
+	}
+
+	fn begin(&mut self, input: &Self::Input) {
+		self.inner.begin(input)
+	}
+}
+
+/// The length includes expired values which have not been replaced yet. See
+/// [`GenericCache::live_len`] to count only values which have not expired.
+impl<C, K, O> ContainerLen for Ttl<C, K>
+where
+	K: Clock,
+	C: ContainerRemove<Output = (Instant, O)> + ContainerLen,
+{
+	fn len(&self) -> usize {
+		self.inner.len()
+	}
+}
+
+impl<C, K, O> ContainerClear for Ttl<C, K>
+where
+	K: Clock,
+	C: ContainerRemove<Output = (Instant, O)> + ContainerClear,
+{
+	fn clear(&mut self) {
+		self.inner.clear()
+	}
+}
+
+impl<C, K, O> ContainerRemove for Ttl<C, K>
+where
+	K: Clock,
+	C: ContainerRemove<Output = (Instant, O)>,
+{
+	fn remove(&mut self, input: &Self::Input) -> Option<Self::Output> {
+		self.inner.remove(input).map(|(_, o)| o)
+	}
+}
+
+impl<'f, C, K, O> GenericCache<'f, Ttl<C, K>>
+where
+	C: ContainerRemove<Output = (Instant, O)> + Default,
+	K: Clock + Default,
+{
+	/// Create a cache where each value expires `ttl` after it was computed, using the `Default`
+	/// implementation of the container.
+	pub fn with_ttl(ttl: Duration, f: impl Fn(&C::Input) -> O + Send + Sync + 'f) -> Self {
+		Self::with_cache(Ttl::with_clock(C::default(), ttl, K::default()), f)
+	}
+
+	/// Create a cache where each value expires `ttl` after it was computed, using the `Default`
+	/// implementation of the container, and a recursive function.
+	pub fn recursive_with_ttl(
+		ttl: Duration,
+		f: impl Fn(&mut RefCache<Ttl<C, K>>, &C::Input) -> O + Send + Sync + 'f,
+	) -> Self {
+		Self::recursive_with_cache(Ttl::with_clock(C::default(), ttl, K::default()), f)
+	}
+}
+
+impl<'f, C, K, O> GenericCache<'f, Ttl<C, K>>
+where
+	C: ContainerRemove<Output = (Instant, O)>,
+	K: Clock,
+{
+	/// Returns true if `input` has a value in the cache which has not expired.
+	pub fn is_live(&self, input: &C::Input) -> bool {
+		self.cache.has(input)
+	}
+
+	/// Returns how long ago the value for `input` was computed, or `None` if it is not in the
+	/// cache.
+	///
+	/// Expired values which have not been replaced yet still report their age, so this can be
+	/// used for custom freshness policies. A `ttl` of [`Duration::MAX`] disables expiry
+	/// entirely.
+	///
+	/// ```
+	/// # use fn_cache::{FnCache, TtlCache};
+	/// # use std::time::Duration;
+	/// let mut cache = TtlCache::<usize, usize>::with_ttl(Duration::MAX, |x| *x);
+	///
+	/// cache.get(1);
+	///
+	/// assert!(cache.age(&1).unwrap() < Duration::from_secs(60));
+	/// assert_eq!(cache.age(&2), None);
+	/// ```
+	pub fn age(&self, input: &C::Input) -> Option<Duration> {
+		self.cache
+			.inner
+			.get(input)
+			.map(|(inserted, _)| self.cache.elapsed(*inserted))
+	}
+}
+
+impl<'f, C, K, O> GenericCache<'f, Ttl<C, K>>
+where
+	C: ContainerRemove<Output = (Instant, O)> + ContainerIter,
+	K: Clock,
+{
+	/// Returns the number of values in the cache which have not expired.
+	///
+	/// Unlike [`Self::len`], this visits every value in the cache.
+	pub fn live_len(&self) -> usize {
+		self.cache
+			.inner
+			.iter()
+			.filter(|(_, (inserted, _))| self.cache.is_fresh(*inserted))
+			.count()
+	}
+}
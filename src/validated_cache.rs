@@ -0,0 +1,124 @@
+use std::fmt;
+
+use crate::container::SparseContainer;
+use crate::{FnCache, FnCachePeek, TryFnCache};
+
+/// The error returned when a [`ValidatedCache`] computes a value which fails validation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InvalidOutput;
+
+impl fmt::Display for InvalidOutput {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str("cache function returned a value which failed validation")
+	}
+}
+
+impl std::error::Error for InvalidOutput {}
+
+/// A cache which checks each newly computed value against an invariant before storing it.
+///
+/// A value which fails validation is never cached. [`Self::try_get`], and so
+/// [`TryFnCache::try_get`], returns [`InvalidOutput`] for it, while [`FnCache::get`] panics.
+///
+/// ```
+/// # use fn_cache::validated_cache::{InvalidOutput, ValidatedCache};
+/// # use std::collections::HashMap;
+/// let mut cache = ValidatedCache::<HashMap<u32, f64>>::with_validator(
+///     |x| 1.0 / *x as f64,
+///     |_, p| (0.0..=1.0).contains(p),
+/// );
+///
+/// assert_eq!(cache.try_get(4), Ok(&0.25));
+/// assert_eq!(cache.try_get(0), Err(InvalidOutput));
+/// ```
+pub struct ValidatedCache<'f, C: SparseContainer> {
+	cache: C,
+	#[allow(clippy::type_complexity)]
+	f: Box<dyn Fn(&C::Input) -> C::Output + Send + 'f>,
+	#[allow(clippy::type_complexity)]
+	validate: Box<dyn Fn(&C::Input, &C::Output) -> bool + Send + 'f>,
+}
+
+impl<'f, C: SparseContainer> ValidatedCache<'f, C> {
+	/// Create a `ValidatedCache` out of a cache, a function, and a validator which returns true
+	/// for every value that may be cached.
+	pub fn with_cache_and_validator(
+		cache: C,
+		f: impl Fn(&C::Input) -> C::Output + Send + 'f,
+		validate: impl Fn(&C::Input, &C::Output) -> bool + Send + 'f,
+	) -> Self {
+		Self {
+			cache,
+			f: Box::new(f),
+			validate: Box::new(validate),
+		}
+	}
+
+	/// Get a reference to the underlying cache object.
+	pub fn cache(&self) -> &C {
+		&self.cache
+	}
+
+	/// Retrieve a value stored in the cache, computing it if it does not yet exist.
+	///
+	/// Returns [`InvalidOutput`] if a newly computed value fails validation, in which case
+	/// nothing is cached.
+	pub fn try_get(&mut self, input: C::Input) -> Result<&C::Output, InvalidOutput> {
+		if self.cache.has(&input) {
+			self.cache.touch(&input);
+			return Ok(self.cache.get(&input).unwrap());
+		}
+
+		self.cache.begin(&input);
+		let output = (self.f)(&input);
+
+		if (self.validate)(&input, &output) {
+			Ok(self.cache.put(input, output))
+		} else {
+			Err(InvalidOutput)
+		}
+	}
+}
+
+impl<'f, C> ValidatedCache<'f, C>
+where
+	C: SparseContainer + Default,
+{
+	/// Create a `ValidatedCache` using the `Default` implementation of the container, a
+	/// function, and a validator which returns true for every value that may be cached.
+	pub fn with_validator(
+		f: impl Fn(&C::Input) -> C::Output + Send + 'f,
+		validate: impl Fn(&C::Input, &C::Output) -> bool + Send + 'f,
+	) -> Self {
+		Self::with_cache_and_validator(Default::default(), f, validate)
+	}
+}
+
+impl<'f, C: SparseContainer> FnCache<C::Input, C::Output> for ValidatedCache<'f, C> {
+	/// # Panics
+	/// If a newly computed value fails validation.
+	fn get(&mut self, input: C::Input) -> &C::Output {
+		match self.try_get(input) {
+			Ok(output) => output,
+			Err(e) => panic!("{e}"),
+		}
+	}
+}
+
+impl<'f, C: SparseContainer> TryFnCache<C::Input, C::Output, InvalidOutput>
+	for ValidatedCache<'f, C>
+{
+	fn try_get(&mut self, input: C::Input) -> Result<&C::Output, InvalidOutput> {
+		ValidatedCache::try_get(self, input)
+	}
+}
+
+impl<'f, C: SparseContainer> FnCachePeek<C::Input, C::Output> for ValidatedCache<'f, C> {
+	fn peek(&self, input: &C::Input) -> Option<&C::Output> {
+		self.cache.get(input)
+	}
+
+	fn contains(&self, input: &C::Input) -> bool {
+		self.cache.has(input)
+	}
+}
@@ -1,85 +1,347 @@
-use crate::{FnCache, FnCacheMany};
+use crate::container::{ContainerCapacity, ContainerShrink};
+use crate::{FnCache, FnCacheMany, FnCachePeek, TryFnCache};
 
+use std::fmt;
 use std::sync::Arc;
 
+/// The error returned when inserting a value into a [`VecCache`] would leave a gap before it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InsertGap;
+
+impl fmt::Display for InsertGap {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str("value inserted past the end of the cache")
+	}
+}
+
+impl std::error::Error for InsertGap {}
+
+/// The error returned when an input to a [`VecCache`] cannot be converted to `usize`, or is below
+/// the offset of the cache.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OutOfRange;
+
+impl fmt::Display for OutOfRange {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str("input is out of the range of the cache")
+	}
+}
+
+impl std::error::Error for OutOfRange {}
+
 /// A cache for a function which uses a [`Vec`].
 ///
 /// This cache is optimized for functions which must
 /// be calculated in order, so that there can be no
 /// gaps in the cache, and use `usize` as an argument.
 ///
-/// If the function does not start at zero, or require
-/// every previous value to be calculated for the next
-/// one, consider using a [`HashCache`](crate::HashCache)
-/// instead.
-pub struct VecCache<'f, O> {
+/// Other integer types can be used as the input by
+/// setting `I`, as long as they can be converted to
+/// and from `usize`. Any input which cannot be
+/// converted will cause a panic from [`FnCache::get`],
+/// while [`TryFnCache::try_get`] returns [`OutOfRange`].
+///
+/// If the function is only defined from some input other
+/// than zero, the cache can start from that input instead,
+/// using [`Self::with_offset`].
+///
+/// If the function does not require every previous value
+/// to be calculated for the next one, consider using a
+/// [`HashCache`](crate::HashCache) instead.
+pub struct VecCache<'f, O, I = usize> {
 	pub(crate) cache: Vec<O>,
-	f: Arc<dyn Fn(&mut Self, &usize) -> O + 'f + Send + Sync>,
+	offset: usize,
+	#[allow(clippy::type_complexity)]
+	f: Arc<dyn Fn(&mut Self, &I) -> O + 'f + Send + Sync>,
 }
 
-impl<'f, O> FnCache<usize, O> for VecCache<'f, O> {
-	fn get(&mut self, input: usize) -> &O {
-		let len = self.cache.len();
-
-		if len <= input {
-			self.cache.reserve(input - len + 1);
-		}
+impl<'f, O, I> FnCache<I, O> for VecCache<'f, O, I>
+where
+	I: Copy + TryInto<usize> + TryFrom<usize>,
+{
+	fn get(&mut self, input: I) -> &O {
+		let input = self.index_of(input);
 
-		while self.cache.len() <= input {
-			let next = self.cache.len();
-			let next_val = self.compute(next);
-			self.cache.push(next_val);
-		}
+		self.fill_to(input);
 
 		self.cache.get(input).unwrap()
 	}
+
+	fn is_dense(&self) -> bool {
+		true
+	}
 }
 
-impl<'f, O> FnCacheMany<usize, O> for VecCache<'f, O> {
-	fn get_many<const N: usize>(&mut self, inputs: [usize; N]) -> [&O; N] {
-		let len = self.cache.len();
-		let max = inputs.iter().max().copied().unwrap_or(0);
+impl<'f, O, I> FnCachePeek<I, O> for VecCache<'f, O, I>
+where
+	I: Copy + TryInto<usize> + TryFrom<usize>,
+{
+	/// Any input past the end of the cache, before the offset, or which cannot be converted to
+	/// `usize`, is `None`.
+	fn peek(&self, input: &I) -> Option<&O> {
+		self.try_index_of(*input).and_then(|i| self.cache.get(i))
+	}
+}
 
-		if len <= max {
-			self.cache.reserve(max - len + 1);
-		}
+impl<'f, O, I> TryFnCache<I, O, OutOfRange> for VecCache<'f, O, I>
+where
+	I: Copy + TryInto<usize> + TryFrom<usize>,
+{
+	/// Returns [`OutOfRange`] instead of panicking for an input which cannot be converted to
+	/// `usize`, or is below the offset.
+	fn try_get(&mut self, input: I) -> Result<&O, OutOfRange> {
+		let input = self.try_index_of(input).ok_or(OutOfRange)?;
+
+		self.fill_to(input);
+
+		Ok(self.cache.get(input).unwrap())
+	}
+}
+
+impl<'f, O, I> FnCacheMany<I, O> for VecCache<'f, O, I>
+where
+	I: Copy + TryInto<usize> + TryFrom<usize>,
+{
+	/// Every value up to the largest input is filled in a single pass, after which each input is
+	/// only a lookup, regardless of the order of the inputs.
+	fn get_many<const N: usize>(&mut self, inputs: [I; N]) -> [&O; N] {
+		let inputs = inputs.map(|i| self.index_of(i));
 
-		for i in inputs {
-			self.get(i);
+		if let Some(&max) = inputs.iter().max() {
+			self.fill_to(max);
 		}
 
 		inputs.map(|i| self.cache.get(i).unwrap())
 	}
+
+	fn get_many_vec(&mut self, inputs: &[I]) -> Vec<&O> {
+		let inputs: Vec<_> = inputs.iter().map(|&i| self.index_of(i)).collect();
+
+		if let Some(&max) = inputs.iter().max() {
+			self.fill_to(max);
+		}
+
+		inputs
+			.into_iter()
+			.map(|i| self.cache.get(i).unwrap())
+			.collect()
+	}
 }
 
 impl<'f, O> VecCache<'f, O> {
 	/// Create a cache for the provided function. If the
 	/// function stores references, the cache can only
 	/// live as long as those references.
+	///
+	/// The input is always `usize`, so that the input of
+	/// a closure is inferred even if it is not annotated.
+	/// For other input types, see [`Self::new_indexed`].
 	pub fn new<F>(f: F) -> Self
 	where
 		F: Fn(&usize) -> O + 'f + Send + Sync,
 	{
-		Self::recursive(move |_, x| f(x))
+		Self::new_indexed(f)
 	}
 
 	/// Create a cache for the provided recursive function.
 	/// If the function stores references, the cache can
 	/// only live as long as those references.
+	///
+	/// For input types other than `usize`, see
+	/// [`Self::recursive_indexed`].
 	pub fn recursive<F>(f: F) -> Self
 	where
 		F: Fn(&mut Self, &usize) -> O + 'f + Send + Sync,
+	{
+		Self::recursive_indexed(f)
+	}
+
+	/// Create a cache for the provided function, starting from the input `base` instead of zero.
+	///
+	/// Only values from `base` onwards are computed and stored, so the function is never called
+	/// for an input below `base`. Getting a value for an input below `base` panics.
+	///
+	/// For input types other than `usize`, see [`Self::with_offset_indexed`].
+	///
+	/// ```
+	/// # use fn_cache::{FnCache, VecCache};
+	/// let mut cache = VecCache::<usize>::with_offset(1000, |x| x - 1000);
+	///
+	/// assert_eq!(cache.get(1002), &2);
+	/// assert_eq!(cache.len(), 3);
+	/// assert_eq!(cache.logical_len(), 1003);
+	/// ```
+	pub fn with_offset<F>(base: usize, f: F) -> Self
+	where
+		F: Fn(&usize) -> O + 'f + Send + Sync,
+	{
+		Self::with_offset_indexed(base, f)
+	}
+
+	/// Create a cache for the provided recursive function, starting from the input `base`
+	/// instead of zero. See [`Self::with_offset`].
+	pub fn recursive_with_offset<F>(base: usize, f: F) -> Self
+	where
+		F: Fn(&mut Self, &usize) -> O + 'f + Send + Sync,
+	{
+		Self::recursive_with_offset_indexed(base, f)
+	}
+}
+
+impl<'f, O, I> VecCache<'f, O, I>
+where
+	I: Copy + TryInto<usize> + TryFrom<usize>,
+{
+	/// Create a cache for the provided function, taking inputs of the integer type `I`.
+	///
+	/// ```
+	/// # use fn_cache::{FnCache, VecCache};
+	/// let mut cache = VecCache::<u64, u32>::new_indexed(|x| *x as u64 * 2);
+	///
+	/// assert_eq!(cache.get(3u32), &6);
+	/// ```
+	pub fn new_indexed<F>(f: F) -> Self
+	where
+		F: Fn(&I) -> O + 'f + Send + Sync,
+	{
+		Self::recursive_indexed(move |_, x| f(x))
+	}
+
+	/// Create a cache for the provided recursive function, taking inputs of the integer type
+	/// `I`. See [`Self::new_indexed`].
+	pub fn recursive_indexed<F>(f: F) -> Self
+	where
+		F: Fn(&mut Self, &I) -> O + 'f + Send + Sync,
+	{
+		Self::recursive_with_offset_indexed(0, f)
+	}
+
+	/// Create a cache for the provided function, taking inputs of the integer type `I`, and
+	/// starting from the input `base` instead of zero. See [`Self::with_offset`].
+	pub fn with_offset_indexed<F>(base: usize, f: F) -> Self
+	where
+		F: Fn(&I) -> O + 'f + Send + Sync,
+	{
+		Self::recursive_with_offset_indexed(base, move |_, x| f(x))
+	}
+
+	/// Create a cache for the provided recursive function, taking inputs of the integer type
+	/// `I`, and starting from the input `base` instead of zero. See [`Self::with_offset`].
+	pub fn recursive_with_offset_indexed<F>(base: usize, f: F) -> Self
+	where
+		F: Fn(&mut Self, &I) -> O + 'f + Send + Sync,
 	{
 		VecCache {
 			cache: Vec::default(),
+			offset: base,
 			f: Arc::new(f),
 		}
 	}
 
-	fn compute(&mut self, input: usize) -> O {
-		(self.f.clone())(self, &input)
+	/// Retrieve a value from the cache, along with whether it
+	/// was computed and inserted by this call (a miss), or
+	/// was already present (a hit).
+	///
+	/// Earlier values filled in to reach `input` are not
+	/// reported.
+	pub fn get_entry(&mut self, input: I) -> (bool, &O) {
+		let inserted = self.cache.len() <= self.index_of(input);
+
+		(inserted, self.get(input))
+	}
+
+	/// Insert a known value for `input` into the cache, without calling the function, unless a
+	/// value for `input` is already cached, and return a reference to the stored value.
+	///
+	/// Since the cache has no gaps, a value can only be inserted at the end of the cache, which
+	/// is [`Self::logical_len`]. Any input past the end returns [`InsertGap`], and the cache is
+	/// left unchanged, rather than computing the values in between. If the value for `input` is
+	/// already cached, the existing value is kept.
+	///
+	/// ```
+	/// # use fn_cache::{FnCache, VecCache};
+	/// # use fn_cache::vec_cache::InsertGap;
+	/// let mut cache = VecCache::<usize>::recursive(|cache, x| *cache.get(x - 1) + *cache.get(x - 2));
+	///
+	/// assert_eq!(cache.insert_if_absent(0, 0), Ok(&0));
+	/// assert_eq!(cache.insert_if_absent(2, 1), Err(InsertGap));
+	/// assert_eq!(cache.insert_if_absent(1, 1), Ok(&1));
+	///
+	/// assert_eq!(cache.get(10), &55);
+	/// ```
+	pub fn insert_if_absent(&mut self, input: I, output: O) -> Result<&O, InsertGap> {
+		let input = self.index_of(input);
+
+		if input == self.cache.len() {
+			self.cache.push(output);
+		} else if input > self.cache.len() {
+			return Err(InsertGap);
+		}
+
+		Ok(&self.cache[input])
+	}
+
+	/// Returns an iterator over every input in the cache, along with its output, in order of
+	/// the input.
+	///
+	/// ```
+	/// # use fn_cache::{FnCache, VecCache};
+	/// let mut cache = VecCache::<usize>::new(|x| x * x);
+	///
+	/// cache.get(2);
+	///
+	/// assert!(cache.iter().eq([(0, &0), (1, &1), (2, &4)]));
+	/// ```
+	pub fn iter(&self) -> impl Iterator<Item = (I, &O)> {
+		let offset = self.offset;
+
+		self.cache
+			.iter()
+			.enumerate()
+			.map(move |(i, o)| (from_index(i + offset), o))
+	}
+
+	/// Converts an input to its index in the backing [`Vec`], by subtracting the offset.
+	pub(crate) fn index_of(&self, input: I) -> usize {
+		let input = to_index(input);
+
+		match input.checked_sub(self.offset) {
+			Some(i) => i,
+			None => panic!("VecCache input {input} is below the offset {}", self.offset),
+		}
+	}
+
+	/// Converts an input to its index in the backing [`Vec`], or `None` if it cannot be converted
+	/// to `usize` or is below the offset.
+	fn try_index_of(&self, input: I) -> Option<usize> {
+		input
+			.try_into()
+			.ok()
+			.and_then(|i: usize| i.checked_sub(self.offset))
+	}
+
+	/// Computes every missing value up to and including `index`, reserving space for all of them
+	/// at once.
+	fn fill_to(&mut self, index: usize) {
+		let len = self.cache.len();
+
+		if len <= index {
+			self.cache.reserve(index - len + 1);
+		}
+
+		while self.cache.len() <= index {
+			let next = self.cache.len();
+			let next_val = self.compute(next);
+			self.cache.push(next_val);
+		}
+	}
+
+	fn compute(&mut self, index: usize) -> O {
+		(self.f.clone())(self, &from_index(index + self.offset))
 	}
+}
 
+impl<'f, O, I> VecCache<'f, O, I> {
 	/// Clears the cache. removing all values.
 	/// Keeps the allocated memory for reuse.
 	pub fn clear(&mut self) {
@@ -87,14 +349,146 @@ impl<'f, O> VecCache<'f, O> {
 	}
 
 	/// Returns the number of elements in the cache.
+	///
+	/// This is the number of values stored, the same as
+	/// [`Self::stored_len`]. For a cache starting from an
+	/// offset, this counts from the offset, so it does not
+	/// include the inputs below it.
 	pub fn len(&self) -> usize {
 		self.cache.len()
 	}
 
+	/// Returns the number of values physically stored in
+	/// the backing [`Vec`].
+	pub fn stored_len(&self) -> usize {
+		self.cache.len()
+	}
+
+	/// Returns the index one past the last cached input,
+	/// which is the next input that will be computed.
+	///
+	/// This is the offset plus [`Self::stored_len`], so for a
+	/// cache filled from zero, the two are equal.
+	pub fn logical_len(&self) -> usize {
+		self.offset + self.cache.len()
+	}
+
+	/// Returns the first input the cache holds a value for,
+	/// which is zero unless created with an offset.
+	pub fn offset(&self) -> usize {
+		self.offset
+	}
+
+	/// Returns true if the cache holds no elements.
+	pub fn is_empty(&self) -> bool {
+		self.cache.is_empty()
+	}
+
+	/// Removes every value from the cache, returning each input and output in order of the
+	/// input. Keeps the allocated memory for reuse.
+	///
+	/// ```
+	/// # use fn_cache::{FnCache, VecCache};
+	/// let mut cache = VecCache::<usize>::new(|x| x * x);
+	///
+	/// cache.get(2);
+	///
+	/// assert_eq!(cache.drain().collect::<Vec<_>>(), [(0, 0), (1, 1), (2, 4)]);
+	/// assert!(cache.is_empty());
+	/// ```
+	pub fn drain(&mut self) -> impl Iterator<Item = (I, O)> + '_
+	where
+		I: TryFrom<usize>,
+	{
+		let offset = self.offset;
+
+		self.cache
+			.drain(..)
+			.enumerate()
+			.map(move |(i, o)| (from_index(i + offset), o))
+	}
+
 	/// Reserves capacity for at least `additional` more elements
 	/// to be inserted in the cache. The collection may
 	/// reserve more space to avoid frequent reallocations.
 	pub fn reserve(&mut self, additional: usize) {
 		self.cache.reserve(additional)
 	}
+
+	/// Reserves capacity for the cache to hold at least `len` elements in total, returning the
+	/// number of bytes newly allocated for values.
+	///
+	/// This is useful to report how much memory was committed before filling the cache. If the
+	/// capacity is already large enough, nothing is allocated and this returns zero.
+	///
+	/// ```
+	/// # use fn_cache::VecCache;
+	/// let mut cache = VecCache::<u64>::new(|x| *x as u64);
+	///
+	/// let bytes = cache.reserve_to(100);
+	///
+	/// assert_eq!(bytes, cache.capacity() * 8);
+	/// assert_eq!(cache.reserve_to(50), 0);
+	/// ```
+	pub fn reserve_to(&mut self, len: usize) -> usize {
+		let old = self.cache.capacity();
+
+		self.cache.reserve(len.saturating_sub(self.cache.len()));
+
+		(self.cache.capacity() - old) * core::mem::size_of::<O>()
+	}
+
+	/// Returns the number of elements the cache can hold without reallocating.
+	pub fn capacity(&self) -> usize {
+		self.cache.capacity()
+	}
+
+	/// Returns the size of the heap allocation backing the cache, in bytes, which is the capacity
+	/// times the size of `O`.
+	///
+	/// This is exact for outputs which own no heap memory of their own, such as integers. Memory
+	/// owned by each output, such as the contents of a [`Vec`], is not included.
+	///
+	/// ```
+	/// # use fn_cache::VecCache;
+	/// let mut cache = VecCache::<u32>::new(|x| *x as u32);
+	///
+	/// cache.reserve(10);
+	///
+	/// assert_eq!(cache.heap_bytes(), cache.capacity() * 4);
+	/// ```
+	pub fn heap_bytes(&self) -> usize {
+		self.cache.capacity() * core::mem::size_of::<O>()
+	}
+
+	/// Shrinks the capacity of the cache as much as possible, such as after clearing it.
+	pub fn shrink_to_fit(&mut self) {
+		self.cache.shrink_to_fit()
+	}
+}
+
+impl<O> ContainerShrink for Vec<O> {
+	fn shrink_to_fit(&mut self) {
+		self.shrink_to_fit()
+	}
+}
+
+impl<O> ContainerCapacity for Vec<O> {
+	fn capacity(&self) -> usize {
+		self.capacity()
+	}
+}
+
+pub(crate) fn to_index<I: TryInto<usize>>(input: I) -> usize {
+	match input.try_into() {
+		Ok(i) => i,
+		Err(_) => panic!("VecCache input cannot be converted to usize"),
+	}
+}
+
+pub(crate) fn from_index<I: TryFrom<usize>>(index: usize) -> I {
+	match I::try_from(index) {
+		Ok(i) => i,
+		Err(_) => panic!("VecCache index {index} cannot be converted to the input type"),
+	}
 }
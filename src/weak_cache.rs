@@ -0,0 +1,123 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Weak};
+
+use core::hash::Hash;
+
+/// A cache which only holds weak references to its values, so that a value is only kept for as
+/// long as something else holds it.
+///
+/// The function returns each value in an [`Arc`], and the cache keeps a [`Weak`] to it. Getting
+/// a value which is still alive returns another strong reference to it, while a value which has
+/// been dropped is computed again. Since values may be dropped at any time, values are returned
+/// as an owned [`Arc`], rather than a reference into the cache.
+///
+/// Dead entries are replaced when they are next requested, and are removed from the whole cache
+/// whenever it has doubled in size since the last time. [`Self::gc`] removes them immediately.
+///
+/// ```
+/// # use fn_cache::weak_cache::WeakCache;
+/// # use std::sync::Arc;
+/// let mut cache = WeakCache::new(|x: &usize| vec![0; *x]);
+///
+/// let a = cache.get(3);
+///
+/// // the same value, since `a` is keeping it alive
+/// assert!(Arc::ptr_eq(&a, &cache.get(3)));
+///
+/// drop(a);
+///
+/// assert_eq!(cache.gc(), 1);
+/// ```
+pub struct WeakCache<'f, I, O> {
+	cache: HashMap<I, Weak<O>>,
+	gc_len: usize,
+	#[allow(clippy::type_complexity)]
+	f: Box<dyn Fn(&mut WeakRefCache<I, O>, &I) -> Arc<O> + Send + 'f>,
+}
+
+impl<'f, I, O> WeakCache<'f, I, O>
+where
+	I: Eq + Hash,
+{
+	/// Create a cache for the provided function.
+	pub fn new(f: impl Fn(&I) -> O + Send + 'f) -> Self {
+		Self::recursive(move |_, i| Arc::new(f(i)))
+	}
+
+	/// Create a cache for the provided recursive function.
+	pub fn recursive(f: impl Fn(&mut WeakRefCache<I, O>, &I) -> Arc<O> + Send + 'f) -> Self {
+		Self {
+			cache: HashMap::new(),
+			gc_len: 0,
+			f: Box::new(f),
+		}
+	}
+
+	/// Retrieve a value stored in the cache, if it is still alive. Otherwise, the function is
+	/// called, and a weak reference to the result is stored before returning it.
+	pub fn get(&mut self, input: I) -> Arc<O> {
+		WeakRefCache {
+			cache: &mut self.cache,
+			gc_len: &mut self.gc_len,
+			f: self.f.as_ref(),
+		}
+		.get(input)
+	}
+
+	/// Removes every entry whose value has been dropped, returning how many were removed.
+	pub fn gc(&mut self) -> usize {
+		gc(&mut self.cache, &mut self.gc_len)
+	}
+
+	/// Returns the number of entries in the cache, including those whose value has been
+	/// dropped but not yet removed.
+	pub fn len(&self) -> usize {
+		self.cache.len()
+	}
+
+	/// Returns true if the cache holds no entries.
+	pub fn is_empty(&self) -> bool {
+		self.cache.is_empty()
+	}
+}
+
+/// The cache passed to the function of a [`WeakCache`].
+pub struct WeakRefCache<'c, I, O> {
+	cache: &'c mut HashMap<I, Weak<O>>,
+	gc_len: &'c mut usize,
+	#[allow(clippy::type_complexity)]
+	f: &'c (dyn Fn(&mut Self, &I) -> Arc<O> + Send),
+}
+
+impl<'c, I, O> WeakRefCache<'c, I, O>
+where
+	I: Eq + Hash,
+{
+	/// Retrieve a value stored in the cache, if it is still alive. Otherwise, the function is
+	/// called, and a weak reference to the result is stored before returning it.
+	pub fn get(&mut self, input: I) -> Arc<O> {
+		if let Some(output) = self.cache.get(&input).and_then(Weak::upgrade) {
+			return output;
+		}
+
+		let output = (self.f)(self, &input);
+
+		// replacing a dead entry does not grow the cache
+		if self.cache.len() >= 2 * *self.gc_len && !self.cache.contains_key(&input) {
+			gc(self.cache, self.gc_len);
+		}
+
+		self.cache.insert(input, Arc::downgrade(&output));
+
+		output
+	}
+}
+
+fn gc<I, O>(cache: &mut HashMap<I, Weak<O>>, gc_len: &mut usize) -> usize {
+	let len = cache.len();
+
+	cache.retain(|_, o| o.strong_count() > 0);
+	*gc_len = cache.len().max(1);
+
+	len - cache.len()
+}